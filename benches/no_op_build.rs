@@ -0,0 +1,46 @@
+//! no-op builds of a large project, with and without trusting modification dates
+//!
+//! Trusting modification dates only skips hashing the sources, they're still read to resolve the
+//! graph, so the difference is within the noise for the mock project.
+#[macro_use]
+extern crate criterion;
+
+use criterion::Criterion;
+use foundry_compilers::{
+    project_util::{
+        mock::{MockProjectGenerator, MockProjectSettings},
+        mock_solc::MockSolc,
+        TempProject,
+    },
+    ConfigurableArtifacts, ProjectPathsConfig,
+};
+
+fn no_op_build_benchmark(c: &mut Criterion) {
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let gen = MockProjectGenerator::new(&MockProjectSettings::large());
+    project.mock(&gen, "^0.8.10").unwrap();
+    let remappings = gen.remappings_at(project.root());
+    project.paths_mut().remappings.extend(remappings);
+    project.compile().unwrap().assert_success();
+
+    let mut group = c.benchmark_group("no-op build large");
+    group.sample_size(10);
+    for trust_mtime in [false, true] {
+        project.project_mut().trust_mtime = trust_mtime;
+        assert!(project.compile().unwrap().is_unchanged());
+        let name = if trust_mtime { "trust mtime" } else { "hash sources" };
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                project.compile().unwrap();
+            });
+        });
+    }
+}
+
+criterion_group!(benches, no_op_build_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,132 @@
+//! Peak memory of reading the compiler output of the large mock project, buffered like
+//! [`Solc::compile_output()`] and streamed like [`Solc::compile_streamed()`].
+//!
+//! [`MockSolc`] doesn't emit ASTs, which make up most of `solc`'s output, so every source gets a
+//! synthetic AST first. The output is read from a file, like it's read from `solc`'s stdout.
+//!
+//! Run with `cargo bench --bench peak_memory --features project-util`
+//!
+//! [`Solc::compile_output()`]: foundry_compilers::Solc::compile_output
+//! [`Solc::compile_streamed()`]: foundry_compilers::Solc::compile_streamed
+
+use foundry_compilers::{
+    artifacts::{ast::Ast, CompilerInput, CompilerOutput},
+    project_util::{
+        mock::{MockProjectGenerator, MockProjectSettings},
+        mock_solc::MockSolc,
+        TempProject,
+    },
+    ConfigurableArtifacts, Graph, OutputFilter, ProjectPathsConfig,
+};
+use serde_json::json;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs::{self, File},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The number of statements in the synthetic AST of every source
+const AST_STATEMENTS: usize = 2_000;
+
+/// Tracks the currently allocated and the peak number of bytes
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = self.current.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            self.peak.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.current.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc { current: AtomicUsize::new(0), peak: AtomicUsize::new(0) };
+
+/// Returns the peak number of bytes that were allocated in addition to the current allocations
+/// while `f` ran
+fn peak_during<R>(f: impl FnOnce() -> R) -> usize {
+    let start = ALLOC.current.load(Ordering::Relaxed);
+    ALLOC.peak.store(start, Ordering::Relaxed);
+    drop(f());
+    ALLOC.peak.load(Ordering::Relaxed) - start
+}
+
+/// Returns an AST of the source with [`AST_STATEMENTS`] statements
+fn synthetic_ast(id: usize, path: &str) -> Ast {
+    let statements = (0..AST_STATEMENTS)
+        .map(|i| {
+            json!({
+                "id": id * AST_STATEMENTS + i,
+                "nodeType": "ExpressionStatement",
+                "src": format!("{}:10:{id}", i * 10),
+                "expression": {
+                    "id": id * AST_STATEMENTS + i,
+                    "nodeType": "Identifier",
+                    "name": format!("value{i}"),
+                    "src": format!("{}:5:{id}", i * 10),
+                    "typeDescriptions": { "typeIdentifier": "t_uint256", "typeString": "uint256" }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::from_value(json!({
+        "absolutePath": path,
+        "id": id,
+        "nodeType": "SourceUnit",
+        "src": "0:0:0",
+        "nodes": statements,
+    }))
+    .unwrap()
+}
+
+fn main() {
+    let project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let gen = MockProjectGenerator::new(&MockProjectSettings::large());
+    project.mock(&gen, "^0.8.10").unwrap();
+
+    let (sources, _) = Graph::resolve(project.paths()).unwrap().into_sources();
+    let input = CompilerInput::with_sources(sources).pop().unwrap();
+    let mut output: CompilerOutput = MockSolc::default().compile(&input);
+    for (id, (path, source)) in output.sources.iter_mut().enumerate() {
+        source.ast = Some(synthetic_ast(id, path));
+    }
+    let stdout = project.root().join("stdout.json");
+    fs::write(&stdout, serde_json::to_vec(&output).unwrap()).unwrap();
+    let size = fs::metadata(&stdout).unwrap().len() as usize;
+    drop(output);
+
+    let buffered = peak_during(|| {
+        let content = fs::read(&stdout).unwrap();
+        serde_json::from_slice::<CompilerOutput>(&content).unwrap()
+    });
+    let streamed =
+        peak_during(|| OutputFilter::default().deserialize_from(File::open(&stdout).unwrap()));
+    // the ASTs of clean files are dropped, like for a job that only has one dirty file
+    let first = input.sources.keys().next().unwrap().display().to_string();
+    let filtered = peak_during(|| {
+        let filter = OutputFilter::default().keep_asts_of([first]).skip_generated_sources();
+        filter.deserialize_from(File::open(&stdout).unwrap())
+    });
+
+    let mib = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+    println!("output of {} sources: {:.1} MiB", input.sources.len(), mib(size));
+    println!("buffered:             {:.1} MiB peak", mib(buffered));
+    println!("streamed:             {:.1} MiB peak", mib(streamed));
+    println!("streamed, clean ASTs: {:.1} MiB peak", mib(filtered));
+}
@@ -20,12 +20,12 @@ use crate::{
         GeneratedSource, LosslessMetadata, Metadata, Offsets, Settings, StorageLayout, UserDoc,
     },
     sources::VersionedSourceFile,
-    Artifact, ArtifactOutput, SolcConfig, SolcError, SourceFile,
+    utils, Artifact, ArtifactOutput, SolcConfig, SolcError, SourceFile,
 };
 use alloy_json_abi::JsonAbi;
 use alloy_primitives::hex;
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, collections::BTreeMap, fs, path::Path};
+use std::{borrow::Cow, collections::BTreeMap, path::Path};
 
 /// Represents the `Artifact` that `ConfigurableArtifacts` emits.
 ///
@@ -601,38 +601,35 @@ impl ExtraOutputFiles {
         if self.abi {
             if let Some(ref abi) = contract.abi {
                 let file = file.with_extension("abi.json");
-                fs::write(&file, serde_json::to_string_pretty(abi)?)
-                    .map_err(|err| SolcError::io(err, file))?
+                utils::write_atomic(&file, serde_json::to_string_pretty(abi)?)?
             }
         }
 
         if self.metadata {
             if let Some(ref metadata) = contract.metadata {
                 let file = file.with_extension("metadata.json");
-                fs::write(&file, serde_json::to_string_pretty(&metadata.raw_json()?)?)
-                    .map_err(|err| SolcError::io(err, file))?
+                utils::write_atomic(&file, serde_json::to_string_pretty(&metadata.raw_json()?)?)?
             }
         }
 
         if self.ir_optimized {
             if let Some(ref iropt) = contract.ir_optimized {
                 let file = file.with_extension("iropt");
-                fs::write(&file, iropt).map_err(|err| SolcError::io(err, file))?
+                utils::write_atomic(&file, iropt)?
             }
         }
 
         if self.ir {
             if let Some(ref ir) = contract.ir {
                 let file = file.with_extension("ir");
-                fs::write(&file, ir).map_err(|err| SolcError::io(err, file))?
+                utils::write_atomic(&file, ir)?
             }
         }
 
         if self.ewasm {
             if let Some(ref ewasm) = contract.ewasm {
                 let file = file.with_extension("ewasm");
-                fs::write(&file, serde_json::to_vec_pretty(ewasm)?)
-                    .map_err(|err| SolcError::io(err, file))?;
+                utils::write_atomic(&file, serde_json::to_vec_pretty(ewasm)?)?;
             }
         }
 
@@ -640,7 +637,7 @@ impl ExtraOutputFiles {
             if let Some(ref evm) = contract.evm {
                 if let Some(ref asm) = evm.assembly {
                     let file = file.with_extension("asm");
-                    fs::write(&file, asm).map_err(|err| SolcError::io(err, file))?
+                    utils::write_atomic(&file, asm)?
                 }
             }
         }
@@ -649,8 +646,10 @@ impl ExtraOutputFiles {
             if let Some(ref evm) = contract.evm {
                 if let Some(ref bytecode) = evm.bytecode {
                     let file = file.with_extension("gensources");
-                    fs::write(&file, serde_json::to_vec_pretty(&bytecode.generated_sources)?)
-                        .map_err(|err| SolcError::io(err, file))?;
+                    utils::write_atomic(
+                        &file,
+                        serde_json::to_vec_pretty(&bytecode.generated_sources)?,
+                    )?;
                 }
             }
         }
@@ -660,7 +659,7 @@ impl ExtraOutputFiles {
                 if let Some(ref bytecode) = evm.bytecode {
                     if let Some(ref sourcemap) = bytecode.source_map {
                         let file = file.with_extension("sourcemap");
-                        fs::write(&file, sourcemap).map_err(|err| SolcError::io(err, file))?
+                        utils::write_atomic(&file, sourcemap)?
                     }
                 }
             }
@@ -670,14 +669,14 @@ impl ExtraOutputFiles {
             if let Some(ref code) = contract.get_bytecode_bytes() {
                 let code = hex::encode(code.as_ref());
                 let file = file.with_extension("bin");
-                fs::write(&file, code).map_err(|err| SolcError::io(err, file))?
+                utils::write_atomic(&file, code)?
             }
         }
         if self.deployed_bytecode {
             if let Some(ref code) = contract.get_deployed_bytecode_bytes() {
                 let code = hex::encode(code.as_ref());
                 let file = file.with_extension("deployed-bin");
-                fs::write(&file, code).map_err(|err| SolcError::io(err, file))?
+                utils::write_atomic(&file, code)?
             }
         }
 
@@ -0,0 +1,237 @@
+//! A single file index of all cached artifacts, see [`ArtifactIndex`]
+
+use crate::{
+    error::{Result, SolcError},
+    utils,
+};
+use memmap2::Mmap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// The file name of the artifact index next to the cache file
+pub const ARTIFACT_INDEX_FILENAME: &str = "artifacts-index.bin";
+
+/// Identifies the layout of an artifact index file
+const ARTIFACT_INDEX_MAGIC: &[u8; 8] = b"FCAIDX01";
+
+/// All cached artifacts in a single file, together with an index of their offsets, so that an
+/// artifact can be decoded without opening its json file or parsing any of the other artifacts.
+///
+/// It's written next to the cache file if the project uses the
+/// [`CacheFormat::Binary`](crate::cache::CacheFormat::Binary) and it's only an accelerator: the
+/// artifacts are still written as json files to the artifacts dir, which remain the interoperable
+/// format.
+///
+/// The index records the generation of the json cache file it was written with, an index whose
+/// generation doesn't match the current cache file is outdated and not used.
+///
+/// The file starts with a magic number and the length of the index, followed by the CBOR encoded
+/// index and the compact json encodings of the artifacts. The file is memory mapped, so only the
+/// index is read eagerly.
+#[derive(Debug)]
+pub struct ArtifactIndex {
+    /// The generation of the json cache file the index was written with
+    generation: String,
+    /// The absolute path of every artifact and the range of its encoding in `data`
+    entries: BTreeMap<PathBuf, Range<usize>>,
+    /// The contents of the index file
+    data: Mmap,
+}
+
+/// The serialized form of the index, paths are relative to the artifacts dir
+#[derive(Default, Serialize, Deserialize)]
+struct IndexEntries {
+    generation: String,
+    entries: BTreeMap<PathBuf, (u64, u64)>,
+}
+
+impl ArtifactIndex {
+    /// Reads the index file, the artifact paths of the index are joined with the `artifacts_dir`
+    pub fn read(path: impl AsRef<Path>, artifacts_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let artifacts_dir = artifacts_dir.as_ref();
+        trace!("reading artifact index at {}", path.display());
+        let file = fs::File::open(path).map_err(|err| SolcError::io(err, path))?;
+        // the file is only ever replaced by renaming, never modified in place
+        let data = unsafe { Mmap::map(&file).map_err(|err| SolcError::io(err, path))? };
+
+        let invalid = || SolcError::msg(format!("invalid artifact index \"{}\"", path.display()));
+        let header_len = ARTIFACT_INDEX_MAGIC.len() + 8;
+        if data.len() < header_len || &data[..ARTIFACT_INDEX_MAGIC.len()] != ARTIFACT_INDEX_MAGIC {
+            return Err(invalid());
+        }
+        let mut index_len = [0; 8];
+        index_len.copy_from_slice(&data[ARTIFACT_INDEX_MAGIC.len()..header_len]);
+        let index_end = usize::try_from(u64::from_le_bytes(index_len))
+            .ok()
+            .and_then(|len| header_len.checked_add(len))
+            .filter(|end| *end <= data.len())
+            .ok_or_else(invalid)?;
+        let IndexEntries { generation, entries } = decode(&data[header_len..index_end])?;
+
+        let entries = entries
+            .into_iter()
+            .map(|(artifact, (offset, len))| {
+                let start = index_end.checked_add(usize::try_from(offset).ok()?)?;
+                let end = start.checked_add(usize::try_from(len).ok()?)?;
+                (end <= data.len()).then(|| (artifacts_dir.join(artifact), start..end))
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?;
+
+        Ok(Self { generation, entries, data })
+    }
+
+    /// Writes an index of the given artifacts and their encodings, see [`Self::encode()`], to the
+    /// `path`, the artifact paths are stored relative to the `artifacts_dir`.
+    ///
+    /// The `generation` identifies the json cache file the index belongs to.
+    pub fn write<'a>(
+        path: impl AsRef<Path>,
+        artifacts_dir: impl AsRef<Path>,
+        generation: &str,
+        artifacts: impl IntoIterator<Item = (&'a Path, Cow<'a, [u8]>)>,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let artifacts_dir = artifacts_dir.as_ref();
+        let mut entries =
+            IndexEntries { generation: generation.to_string(), entries: Default::default() };
+        let mut artifacts_data = Vec::new();
+        for (artifact, encoded) in artifacts {
+            let artifact = artifact.strip_prefix(artifacts_dir).unwrap_or(artifact);
+            let range = (artifacts_data.len() as u64, encoded.len() as u64);
+            entries.entries.insert(artifact.to_path_buf(), range);
+            artifacts_data.extend_from_slice(&encoded);
+        }
+        trace!(
+            "writing artifact index with {} artifacts to {}",
+            entries.entries.len(),
+            path.display()
+        );
+
+        let index = encode(&entries)?;
+        let mut data = Vec::with_capacity(16 + index.len() + artifacts_data.len());
+        data.extend_from_slice(ARTIFACT_INDEX_MAGIC);
+        data.extend_from_slice(&(index.len() as u64).to_le_bytes());
+        data.extend_from_slice(&index);
+        data.extend_from_slice(&artifacts_data);
+
+        utils::create_parent_dir_all(path)?;
+        utils::write_atomic(path, data)?;
+        Ok(())
+    }
+
+    /// Returns the encoding of the artifact that's stored in the index
+    pub fn encode<T: Serialize>(artifact: &T) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(artifact)?)
+    }
+
+    /// Returns the generation of the json cache file the index was written with
+    pub fn generation(&self) -> &str {
+        &self.generation
+    }
+
+    /// Returns `true` if the index contains the artifact with the given absolute path
+    pub fn contains(&self, artifact: &Path) -> bool {
+        self.entries.contains_key(artifact)
+    }
+
+    /// Returns an iterator over the absolute paths of all artifacts of the index
+    pub fn artifacts(&self) -> impl Iterator<Item = &PathBuf> {
+        self.entries.keys()
+    }
+
+    /// Returns the number of artifacts of the index
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the index contains no artifacts
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the encoding of the artifact with the given absolute path
+    pub fn get_raw(&self, artifact: &Path) -> Option<&[u8]> {
+        self.entries.get(artifact).map(|range| &self.data[range.clone()])
+    }
+
+    /// Decodes the artifact with the given absolute path, `None` if the index doesn't contain it
+    pub fn read_artifact<T: DeserializeOwned>(&self, artifact: &Path) -> Result<Option<T>> {
+        Ok(self.get_raw(artifact).map(serde_json::from_slice).transpose()?)
+    }
+}
+
+/// Encodes the value as CBOR
+pub(crate) fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(value, &mut buf)
+        .map_err(|err| SolcError::msg(format!("failed to encode binary: {err}")))?;
+    Ok(buf)
+}
+
+/// Decodes a CBOR value
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    ciborium::from_reader(bytes)
+        .map_err(|err| SolcError::msg(format!("failed to decode binary: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArtifactOutput, CompilerOutput, ConfigurableArtifacts, ConfigurableContractArtifact,
+    };
+
+    #[test]
+    fn can_write_and_read_artifact_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifacts_dir = dir.path().join("out");
+        let index_path = dir.path().join("cache").join(ARTIFACT_INDEX_FILENAME);
+
+        let output: CompilerOutput = utils::read_json_file(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/out/compiler-out-10.json"),
+        )
+        .unwrap();
+        let (file, contracts) = output.contracts.iter().next().unwrap();
+        let (name, contract) = contracts.iter().next().unwrap();
+        let artifact = ConfigurableArtifacts::default().contract_to_artifact(
+            file,
+            name,
+            contract.clone(),
+            output.sources.get(file),
+        );
+        let greeter = artifacts_dir.join("Greeter.sol/Greeter.json");
+        let empty = artifacts_dir.join("Empty.sol/Empty.json");
+        let encoded = ArtifactIndex::encode(&artifact).unwrap();
+        ArtifactIndex::write(
+            &index_path,
+            &artifacts_dir,
+            "generation",
+            [(greeter.as_path(), encoded.into()), (empty.as_path(), Cow::Borrowed(&[][..]))],
+        )
+        .unwrap();
+
+        let index = ArtifactIndex::read(&index_path, &artifacts_dir).unwrap();
+        assert_eq!(index.generation(), "generation");
+        assert_eq!(index.len(), 2);
+        assert!(index.contains(&greeter));
+        assert_eq!(index.get_raw(&empty), Some(&[][..]));
+        let decoded = index.read_artifact::<ConfigurableContractArtifact>(&greeter).unwrap();
+        assert_eq!(decoded, Some(artifact));
+        assert!(index.read_artifact::<ConfigurableContractArtifact>(&empty).is_err());
+        assert!(index
+            .read_artifact::<ConfigurableContractArtifact>(&dir.path().join("A.json"))
+            .unwrap()
+            .is_none());
+
+        fs::write(&index_path, b"FCAIDX01\xff").unwrap();
+        assert!(ArtifactIndex::read(&index_path, &artifacts_dir).is_err());
+    }
+}
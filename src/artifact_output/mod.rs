@@ -28,9 +28,11 @@ use std::{
 
 mod configurable;
 pub(crate) mod files;
+pub(crate) mod index;
 
 use crate::files::MappedContract;
 pub use configurable::*;
+pub use index::{ArtifactIndex, ARTIFACT_INDEX_FILENAME};
 
 /// Represents unique artifact metadata for identifying artifacts on output
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -17,6 +17,7 @@ use std::{borrow::Cow, collections::BTreeMap, convert::TryFrom};
 pub struct Contract {
     /// The Ethereum Contract Metadata.
     /// See <https://docs.soliditylang.org/en/develop/metadata.html>
+    #[serde(default, deserialize_with = "serde_helpers::deserialize_buffered_opt")]
     pub abi: Option<JsonAbi>,
     #[serde(
         default,
@@ -12,6 +12,7 @@ use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     collections::{BTreeMap, HashSet},
     fmt, fs,
+    io::Read,
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
@@ -47,14 +48,18 @@ pub type Contracts = FileToContractsMap<Contract>;
 /// An ordered list of files and their source
 pub type Sources = BTreeMap<PathBuf, Source>;
 
-/// A set of different Solc installations with their version and the sources to be compiled
-pub(crate) type VersionedSources = BTreeMap<Solc, (Version, Sources)>;
+/// A set of different compiler installations and the profile of their settings, see
+/// `CompilerProfiles`, with their version and the sources to be compiled
+pub(crate) type VersionedSources<C = Solc> = BTreeMap<(C, Option<usize>), (Version, Sources)>;
 
-/// A set of different Solc installations with their version and the sources to be compiled
-pub(crate) type VersionedFilteredSources = BTreeMap<Solc, (Version, FilteredSources)>;
+/// A set of different compiler installations and the profile of their settings, see
+/// `CompilerProfiles`, with their version and the sources to be compiled
+pub(crate) type VersionedFilteredSources<C = Solc> =
+    BTreeMap<(C, Option<usize>), (Version, FilteredSources)>;
 
 const SOLIDITY: &str = "Solidity";
 const YUL: &str = "Yul";
+const VYPER: &str = crate::compilers::vyper::input::VYPER;
 
 /// Input type `solc` expects.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -84,13 +89,19 @@ impl CompilerInput {
     /// Creates a new [CompilerInput]s with default settings and the given sources
     ///
     /// A [CompilerInput] expects a language setting, supported by solc are solidity or yul.
-    /// In case the `sources` is a mix of solidity and yul files, 2 CompilerInputs are returned
+    /// In case the `sources` is a mix of solidity and yul files, 2 CompilerInputs are returned.
+    ///
+    /// Vyper files (`.vy`, `.vyi`) are returned as a separate input with the `Vyper` language,
+    /// which can't be compiled by solc, see [`crate::compilers::vyper::Vyper`].
     pub fn with_sources(sources: Sources) -> Vec<Self> {
         let mut solidity_sources = BTreeMap::new();
         let mut yul_sources = BTreeMap::new();
+        let mut vyper_sources = BTreeMap::new();
         for (path, source) in sources {
             if path.extension() == Some(std::ffi::OsStr::new("yul")) {
                 yul_sources.insert(path, source);
+            } else if utils::is_vyper_file(&path) {
+                vyper_sources.insert(path, source);
             } else {
                 solidity_sources.insert(path, source);
             }
@@ -110,6 +121,13 @@ impl CompilerInput {
                 settings: Default::default(),
             });
         }
+        if !vyper_sources.is_empty() {
+            res.push(Self {
+                language: VYPER.to_string(),
+                sources: vyper_sources,
+                settings: Default::default(),
+            });
+        }
         res
     }
 
@@ -212,6 +230,12 @@ impl CompilerInput {
     pub fn is_yul(&self) -> bool {
         self.language == YUL
     }
+
+    /// The flag indicating whether the current [CompilerInput] is
+    /// constructed for the vyper sources
+    pub fn is_vyper(&self) -> bool {
+        self.language == VYPER
+    }
 }
 
 /// A `CompilerInput` representation used for verify
@@ -1425,7 +1449,7 @@ pub struct DocLibraries {
 /// Content of a solidity file
 ///
 /// This contains the actual source code of a file
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Source {
     /// Content of the file
     ///
@@ -1433,12 +1457,40 @@ pub struct Source {
     /// project contains multiple conflicting versions then the same [Source] may be required by
     /// conflicting versions and needs to be duplicated.
     pub content: Arc<String>,
+    /// The last modification date and the size of the file when it was read, `None` if the
+    /// content wasn't read from disk
+    #[serde(skip)]
+    pub(crate) file_stamp: Option<FileStamp>,
+}
+
+/// The last modification date in milliseconds and the size of a file, see
+/// [`Project::trust_mtime`](crate::Project::trust_mtime)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct FileStamp {
+    pub(crate) last_modification_date: u64,
+    pub(crate) size: u64,
+}
+
+impl FileStamp {
+    /// Returns the stamp of the file's `metadata`
+    pub(crate) fn new(metadata: &fs::Metadata) -> Option<Self> {
+        let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?;
+        Some(Self { last_modification_date: modified.as_millis() as u64, size: metadata.len() })
+    }
 }
 
+impl PartialEq for Source {
+    fn eq(&self, other: &Self) -> bool {
+        self.content == other.content
+    }
+}
+
+impl Eq for Source {}
+
 impl Source {
     /// Creates a new instance of [Source] with the given content.
     pub fn new(content: impl Into<String>) -> Self {
-        Self { content: Arc::new(content.into()) }
+        Self { content: Arc::new(content.into()), file_stamp: None }
     }
 
     /// Reads the file's content
@@ -1446,8 +1498,12 @@ impl Source {
     pub fn read(file: impl AsRef<Path>) -> Result<Self, SolcIoError> {
         let file = file.as_ref();
         debug!(file=%file.display());
-        let content = fs::read_to_string(file).map_err(|err| SolcIoError::new(err, file))?;
-        Ok(Self::new(content))
+        let mut handle = fs::File::open(file).map_err(|err| SolcIoError::new(err, file))?;
+        // the stamp is taken before the content is read, so a later change is always detected
+        let file_stamp = handle.metadata().ok().as_ref().and_then(FileStamp::new);
+        let mut content = String::new();
+        handle.read_to_string(&mut content).map_err(|err| SolcIoError::new(err, file))?;
+        Ok(Self { file_stamp, ..Self::new(content) })
     }
 
     /// Recursively finds all source files under the given dir path and reads them all
@@ -1513,9 +1569,10 @@ impl Source {
     /// async version of `Self::read`
     pub async fn async_read(file: impl AsRef<Path>) -> Result<Self, SolcIoError> {
         let file = file.as_ref();
-        Ok(Self::new(
-            tokio::fs::read_to_string(file).await.map_err(|err| SolcIoError::new(err, file))?,
-        ))
+        let file_stamp = tokio::fs::metadata(file).await.ok().as_ref().and_then(FileStamp::new);
+        let content =
+            tokio::fs::read_to_string(file).await.map_err(|err| SolcIoError::new(err, file))?;
+        Ok(Self { file_stamp, ..Self::new(content) })
     }
 
     /// Finds all source files under the given dir path and reads them all
@@ -1947,6 +2004,22 @@ mod tests {
     use alloy_primitives::Address;
     use std::{fs, path::PathBuf};
 
+    #[test]
+    fn source_keeps_stamp_of_read() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("A.sol");
+        fs::write(&file, "contract A {}").unwrap();
+        let source = Source::read(&file).unwrap();
+        let stamp = FileStamp::new(&fs::metadata(&file).unwrap());
+        assert_eq!(source.file_stamp, stamp);
+
+        // a later change doesn't affect the stamp of the content that was read
+        fs::write(&file, "contract A { uint x; }").unwrap();
+        assert_eq!(source.file_stamp.unwrap().size, "contract A {}".len() as u64);
+        assert_eq!(Source::new("contract A {}").file_stamp, None);
+        assert_eq!(source, Source::new("contract A {}"));
+    }
+
     #[test]
     fn can_parse_declaration_error() {
         let s = r#"{
@@ -18,6 +18,17 @@ where
     value.as_deref().map(str::parse).transpose().map_err(serde::de::Error::custom)
 }
 
+/// Deserializes the value via a buffered [`serde_json::Value`], for types that can only be
+/// deserialized from borrowed data, like the `JsonAbi`, so that they can be read from a stream
+pub fn deserialize_buffered_opt<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = Option::<serde_json::Value>::deserialize(d)?;
+    value.map(|value| T::deserialize(&value)).transpose().map_err(serde::de::Error::custom)
+}
+
 pub fn default_for_null<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
     D: Deserializer<'de>,
@@ -42,8 +42,8 @@ pub struct RawBuildInfo {
 
 impl RawBuildInfo {
     /// Serializes a `BuildInfo` object
-    pub fn new(
-        input: &CompilerInput,
+    pub fn new<I: Serialize>(
+        input: &I,
         output: &CompilerOutput,
         version: &Version,
     ) -> serde_json::Result<RawBuildInfo> {
@@ -2,13 +2,17 @@
 
 use crate::{
     artifacts::Sources,
-    config::{ProjectPaths, SolcConfig},
+    buildinfo::RawBuildInfo,
+    compilers::Compiler,
+    config::{CompilerProfiles, ProjectPaths, SolcConfig},
     error::{Result, SolcError},
     filter::{FilteredSource, FilteredSourceInfo, FilteredSources},
     resolver::GraphEdges,
-    utils, ArtifactFile, ArtifactOutput, Artifacts, ArtifactsMap, OutputContext, Project,
-    ProjectPathsConfig, Source,
+    utils, ArtifactFile, ArtifactId, ArtifactIndex, ArtifactOutput, Artifacts, ArtifactsMap,
+    OutputContext, Project, ProjectPathsConfig, Solc, Source,
 };
+use alloy_primitives::hex;
+use md5::Digest;
 use semver::Version;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
@@ -16,7 +20,7 @@ use std::{
         btree_map::{BTreeMap, Entry},
         hash_map, BTreeSet, HashMap, HashSet,
     },
-    fs,
+    fmt, fs,
     path::{Path, PathBuf},
     time::{Duration, UNIX_EPOCH},
 };
@@ -26,11 +30,61 @@ use std::{
 /// `ethers-solc` uses a different format version id, but the actual format is consistent with
 /// hardhat This allows ethers-solc to detect if the cache file was written by hardhat or
 /// `ethers-solc`
-const ETHERS_FORMAT_VERSION: &str = "ethers-rs-sol-cache-3";
+pub const ETHERS_FORMAT_VERSION: &str = "ethers-rs-sol-cache-3";
+
+/// All format versions of the cache file, oldest first.
+///
+/// Caches of older formats are migrated to [`ETHERS_FORMAT_VERSION`] when they're read, see
+/// [`SolFilesCache::read()`].
+pub const ETHERS_FORMAT_VERSIONS: [&str; 3] =
+    ["ethers-rs-sol-cache-1", "ethers-rs-sol-cache-2", ETHERS_FORMAT_VERSION];
 
 /// The file name of the default cache file
 pub const SOLIDITY_FILES_CACHE_FILENAME: &str = "solidity-files-cache.json";
 
+/// The file name of the binary encoding of the cache, see [`CacheFormat::Binary`]
+pub const SOLIDITY_FILES_CACHE_BINARY_FILENAME: &str = "solidity-files-cache.bin";
+
+/// The prefix of a binary cache file, the CBOR "self-described" tag
+const BINARY_CACHE_MAGIC: [u8; 3] = [0xd9, 0xd9, 0xf7];
+
+/// Which files the [`SolFilesCache`] is written to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CacheFormat {
+    /// Only the json cache file, the interoperable format that other tools read
+    #[default]
+    Json,
+    /// The json cache file and, next to it, a compact binary encoding (CBOR) of the cache that's
+    /// faster to read, together with an [`ArtifactIndex`] of all cached artifacts, so that the
+    /// artifacts files don't need to be opened and parsed.
+    ///
+    /// The json cache file remains the source of truth: the binary cache and the index are only
+    /// used as long as they were written together with the json cache file, so a build that
+    /// rewrites only the json cache, for example with the [`CacheFormat::Json`] or by another
+    /// tool, invalidates them. The artifacts are still written as json files to the artifacts dir.
+    ///
+    /// **Note:** all reused artifacts are still decoded once the compilation finished, because
+    /// they're part of the [`ProjectCompileOutput`](crate::ProjectCompileOutput). So a build that
+    /// doesn't compile anything only saves reading the artifact files, not decoding them.
+    Binary,
+}
+
+/// Identifies the contents of a json cache file.
+///
+/// The binary cache and the [`ArtifactIndex`] record the generation of the json cache file they
+/// were written with, so they can be discarded once the json cache file was changed.
+pub(crate) fn cache_generation(json: &[u8]) -> String {
+    hex::encode(md5::Md5::digest(json))
+}
+
+/// The contents of the binary cache file
+#[derive(Serialize, Deserialize)]
+struct BinaryCache<C> {
+    /// The generation of the json cache file this was written with, see [`cache_generation()`]
+    generation: String,
+    cache: C,
+}
+
 /// A multi version cache file
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SolFilesCache {
@@ -78,11 +132,16 @@ impl SolFilesCache {
 
     /// Reads the cache json file from the given path
     ///
+    /// Caches of older [`ETHERS_FORMAT_VERSIONS`] are migrated to the latest format. The
+    /// `ethers-rs-sol-cache-2` format doesn't record the project's paths, use
+    /// [`Self::read_joined()`] to migrate them with the paths of the project.
+    ///
     /// See also [`Self::read_joined()`]
     ///
     /// # Errors
     ///
-    /// If the cache file does not exist
+    /// If the cache file does not exist or was written with an unknown or newer format, see
+    /// [`SolcError::UnsupportedCacheFormat`]
     ///
     /// # Examples
     ///
@@ -96,9 +155,36 @@ impl SolFilesCache {
     /// ```
     #[instrument(skip_all, name = "sol-files-cache::read")]
     pub fn read(path: impl AsRef<Path>) -> Result<Self> {
-        let path = path.as_ref();
+        Self::read_with_paths(path.as_ref(), &Default::default())
+    }
+
+    /// Reads the cache file and migrates it to the latest format, `paths` are the project paths of
+    /// caches that don't record them.
+    fn read_with_paths(path: &Path, paths: &ProjectPaths) -> Result<Self> {
         trace!("reading solfiles cache at {}", path.display());
-        let cache: SolFilesCache = utils::read_json_file(path)?;
+        let content = fs::read(path).map_err(|err| SolcError::io(err, path))?;
+        Self::from_json_slice(&content, path, paths)
+    }
+
+    /// Deserializes the content of the cache file at `path` and migrates it to the latest format
+    fn from_json_slice(content: &[u8], path: &Path, paths: &ProjectPaths) -> Result<Self> {
+        /// Only the format of the cache, the remaining fields are skipped
+        #[derive(Deserialize)]
+        struct Format {
+            #[serde(rename = "_format")]
+            format: String,
+        }
+
+        let Format { format } = serde_json::from_slice(content)?;
+        let cache: SolFilesCache = if format == ETHERS_FORMAT_VERSION {
+            serde_json::from_slice(content)?
+        } else {
+            let mut cache = serde_json::from_slice(content)?;
+            migrate(&mut cache, &format, paths).ok_or_else(|| {
+                SolcError::UnsupportedCacheFormat { path: path.to_path_buf(), format }
+            })?;
+            serde_json::from_value(cache)?
+        };
         trace!("read cache \"{}\" with {} entries", cache.format, cache.files.len());
         Ok(cache)
     }
@@ -121,7 +207,7 @@ impl SolFilesCache {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     pub fn read_joined(paths: &ProjectPathsConfig) -> Result<Self> {
-        let mut cache = SolFilesCache::read(&paths.cache)?;
+        let mut cache = SolFilesCache::read_with_paths(&paths.cache, &paths.paths_relative())?;
         cache.join_entries(&paths.root).join_artifacts_files(&paths.artifacts);
         Ok(cache)
     }
@@ -136,6 +222,42 @@ impl SolFilesCache {
         Ok(())
     }
 
+    /// Writes the [`CacheFormat::Binary`] encoding of the cache to the given path, `generation`
+    /// identifies the json cache file it belongs to, see [`Self::read_binary()`]
+    pub fn write_binary(&self, path: impl AsRef<Path>, generation: &str) -> Result<()> {
+        let path = path.as_ref();
+        trace!("writing cache with {} entries to binary file: \"{}\"", self.len(), path.display());
+        let mut content = BINARY_CACHE_MAGIC.to_vec();
+        content.extend(crate::artifact_output::index::encode(&BinaryCache {
+            generation: generation.to_string(),
+            cache: self,
+        })?);
+        utils::create_parent_dir_all(path)?;
+        utils::write_atomic(path, content)?;
+        Ok(())
+    }
+
+    /// Reads a binary cache file that was written with [`Self::write_binary()`] and returns the
+    /// cache and the generation of the json cache file it belongs to.
+    ///
+    /// Binary caches are only written in the latest format, they're never migrated.
+    pub fn read_binary(path: impl AsRef<Path>) -> Result<(Self, String)> {
+        let path = path.as_ref();
+        trace!("reading binary solfiles cache at {}", path.display());
+        let content = fs::read(path).map_err(|err| SolcError::io(err, path))?;
+        let content = content.strip_prefix(&BINARY_CACHE_MAGIC).ok_or_else(|| {
+            SolcError::msg(format!("invalid binary cache file \"{}\"", path.display()))
+        })?;
+        let BinaryCache::<Self> { generation, cache } =
+            crate::artifact_output::index::decode(content)?;
+        if cache.format != ETHERS_FORMAT_VERSION {
+            let format = cache.format;
+            return Err(SolcError::UnsupportedCacheFormat { path: path.to_path_buf(), format });
+        }
+        trace!("read binary cache with {} entries", cache.files.len());
+        Ok((cache, generation))
+    }
+
     /// Sets the `CacheEntry`'s file paths to `root` adjoined to `self.file`.
     pub fn join_entries(&mut self, root: impl AsRef<Path>) -> &mut Self {
         let root = root.as_ref();
@@ -303,6 +425,28 @@ impl SolFilesCache {
         Ok(Artifacts(artifacts))
     }
 
+    /// Reads all cached artifacts from disk like [`Self::read_artifacts()`], but skips the
+    /// artifacts of entries that can't be read, for example because a build was killed while
+    /// writing them, so that only these entries are recompiled.
+    fn read_intact_artifacts<Artifact: DeserializeOwned + Send + Sync>(
+        &self,
+    ) -> Artifacts<Artifact> {
+        use rayon::prelude::*;
+
+        let artifacts = self
+            .files
+            .par_iter()
+            .filter_map(|(file, entry)| match entry.read_artifact_files() {
+                Ok(files) => Some((format!("{}", file.display()), files)),
+                Err(err) => {
+                    warn!(?file, %err, "discarding unreadable cached artifacts");
+                    None
+                }
+            })
+            .collect::<ArtifactsMap<_>>();
+        Artifacts(artifacts)
+    }
+
     /// Retains only the `CacheEntry` specified by the file + version combination.
     ///
     /// In other words, only keep those cache entries with the paths (keys) that the iterator yields
@@ -347,6 +491,176 @@ impl SolFilesCache {
             }
         }
     }
+
+    /// Returns all artifacts and build infos in the artifacts dir of the `paths` that aren't
+    /// referenced by any entry of the cache anymore, like the artifacts of deleted or renamed
+    /// files.
+    ///
+    /// Only files in the directories of source files, like `out/Greeter.sol/`, are artifacts.
+    /// Their extra output files, like `Greeter.metadata.json` next to `Greeter.json`, are
+    /// referenced by the artifact. Temporary files of interrupted writes are always orphaned,
+    /// files of other types are ignored. The cache dir and the imports cache dir are skipped if
+    /// they're nested in the artifacts dir.
+    ///
+    /// Build infos are only collected if the build ids of all entries are known, because caches
+    /// written before the ids were tracked don't reference any build info.
+    ///
+    /// **NOTE:** this expects the cache's paths to be joined, see [`Self::read_joined()`].
+    pub fn orphaned_files(&self, paths: &ProjectPathsConfig) -> OrphanedFiles {
+        let artifacts = self.entries().flat_map(|entry| entry.artifacts_versions().map(|(_, f)| f));
+        let referenced = artifacts.collect::<HashSet<_>>();
+        let build_ids = self.entries().flat_map(|entry| entry.build_ids.values());
+        let build_ids = build_ids.map(String::as_str).collect::<HashSet<_>>();
+        let untracked_build_ids = self.entries().any(|entry| {
+            entry.artifacts_versions().any(|(version, _)| !entry.build_ids.contains_key(version))
+        });
+
+        let cache_dir = paths.cache.parent().unwrap_or(&paths.root).to_path_buf();
+        let skipped_dirs = [paths.build_infos.clone(), cache_dir, paths.imports_cache_dir()];
+        let mut orphaned = OrphanedFiles::default();
+        for file in walkdir::WalkDir::new(&paths.artifacts)
+            .into_iter()
+            .filter_entry(|e| !skipped_dirs.iter().any(|dir| e.path() == dir))
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(walkdir::DirEntry::into_path)
+        {
+            if utils::is_temp_file(&file) {
+                // left behind by an interrupted write
+                orphaned.artifacts.insert(file);
+            } else if let Some(artifact) = artifact_of_output_file(&file) {
+                if !referenced.contains(&artifact) {
+                    orphaned.artifacts.insert(file);
+                }
+            }
+        }
+
+        for file in walkdir::WalkDir::new(&paths.build_infos)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(walkdir::DirEntry::into_path)
+        {
+            if utils::is_temp_file(&file) {
+                orphaned.build_infos.insert(file);
+            } else if untracked_build_ids {
+                continue;
+            } else if file.extension().map_or(false, |ext| ext == "json") {
+                let id = file.file_stem().unwrap_or_default().to_string_lossy();
+                if !build_ids.contains(id.as_ref()) {
+                    orphaned.build_infos.insert(file);
+                }
+            }
+        }
+        if untracked_build_ids {
+            trace!("skipped build infos, not all build ids of the cache entries are known");
+        }
+
+        orphaned
+    }
+
+    /// Returns all files and artifacts that are affected by a change of the given files, without
+    /// compiling anything.
+    ///
+    /// These are the given files themselves and all files that import them, directly or
+    /// transitively, because all of them are recompiled if one of the given files changes. The
+    /// files can be the keys of the cache entries or paths relative to the project's root.
+    ///
+    /// **NOTE:** this relies on the `paths` of the cache being relative to the project's root,
+    /// which is the case for every cache file written by the [`Project`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use foundry_compilers::{cache::SolFilesCache, Project};
+    ///
+    /// let project = Project::builder().build()?;
+    /// let cache = SolFilesCache::read_joined(&project.paths)?;
+    /// let impact = cache.impact(["src/Greeter.sol"]);
+    /// for test in impact.tests {
+    ///     println!("affected test: {}", test.display());
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn impact<I, P>(&self, files: I) -> Impact
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        // `imports` of the cache entries are source names, so all files are matched by their
+        // source name
+        let changed = files
+            .into_iter()
+            .map(|file| {
+                let file = file.as_ref();
+                self.entry(file).map_or_else(|| file.to_path_buf(), |e| e.source_name.clone())
+            })
+            .collect::<HashSet<_>>();
+
+        let mut impact = Impact::default();
+        for (file, entry) in self.files.iter() {
+            if !changed.contains(&entry.source_name)
+                && !entry.imports.iter().any(|import| changed.contains(import))
+            {
+                continue;
+            }
+
+            let source_name = &entry.source_name;
+            let files = if source_name.starts_with(&self.paths.tests) {
+                &mut impact.tests
+            } else if source_name.starts_with(&self.paths.scripts) {
+                &mut impact.scripts
+            } else if source_name.starts_with(&self.paths.sources) {
+                &mut impact.sources
+            } else {
+                &mut impact.libraries
+            };
+            files.insert(file.clone());
+
+            for (name, artifacts) in entry.artifacts.iter() {
+                impact.artifacts.extend(artifacts.iter().map(|(version, path)| {
+                    ArtifactId {
+                        path: path.clone(),
+                        name: name.clone(),
+                        source: file.clone(),
+                        version: version.clone(),
+                    }
+                    .with_slashed_paths()
+                }));
+            }
+        }
+        impact
+    }
+}
+
+/// The files and artifacts affected by a change, see [`SolFilesCache::impact()`]
+///
+/// All files are the keys of the cache entries they belong to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Impact {
+    /// Affected files in the sources dir
+    pub sources: BTreeSet<PathBuf>,
+    /// Affected files in the tests dir
+    pub tests: BTreeSet<PathBuf>,
+    /// Affected files in the scripts dir
+    pub scripts: BTreeSet<PathBuf>,
+    /// Affected files outside of the project's dirs, like libraries
+    pub libraries: BTreeSet<PathBuf>,
+    /// The artifacts of all affected files, which would be recompiled
+    pub artifacts: BTreeSet<ArtifactId>,
+}
+
+impl Impact {
+    /// Returns `true` if no file is affected
+    pub fn is_empty(&self) -> bool {
+        self.files().next().is_none()
+    }
+
+    /// Returns all affected files
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.sources.iter().chain(&self.tests).chain(&self.scripts).chain(&self.libraries)
+    }
 }
 
 // async variants for read and write
@@ -360,7 +674,9 @@ impl SolFilesCache {
     pub async fn async_write(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = path.as_ref();
         let content = serde_json::to_vec(self)?;
-        tokio::fs::write(path, content).await.map_err(|err| SolcError::io(err, path))
+        let tmp = utils::temp_file_path(path);
+        tokio::fs::write(&tmp, content).await.map_err(|err| SolcError::io(err, &tmp))?;
+        tokio::fs::rename(&tmp, path).await.map_err(|err| SolcError::io(err, path))
     }
 
     async fn asyncify<F, T>(f: F) -> Result<T>
@@ -378,6 +694,138 @@ impl SolFilesCache {
     }
 }
 
+/// Migrates the json `cache` of the given `format` to [`ETHERS_FORMAT_VERSION`] by applying all
+/// migrations of the formats that follow it in order.
+///
+/// Returns `None` if the format is unknown or newer than the latest format.
+fn migrate(cache: &mut serde_json::Value, format: &str, paths: &ProjectPaths) -> Option<()> {
+    let migrations: [fn(&mut serde_json::Value, &ProjectPaths); 2] = [migrate_v1, migrate_v2];
+    let start = ETHERS_FORMAT_VERSIONS.iter().position(|version| *version == format)?;
+    for migration in migrations.into_iter().skip(start) {
+        migration(cache, paths);
+    }
+    trace!("migrated cache from \"{format}\" to \"{ETHERS_FORMAT_VERSION}\"");
+    cache["_format"] = ETHERS_FORMAT_VERSION.into();
+    Some(())
+}
+
+/// Migrates `ethers-rs-sol-cache-1` to `ethers-rs-sol-cache-2`.
+///
+/// The first format is consistent with hardhat: the version pragmas of a file are a list and its
+/// artifacts aren't tracked per solc version. The pragmas are combined into a single requirement,
+/// formatted like a [`VersionReq`](semver::VersionReq).
+///
+/// The artifacts can't be mapped to the solc version they were compiled with, so they are
+/// dropped and all files of the cache are recompiled once after the migration.
+fn migrate_v1(cache: &mut serde_json::Value, _paths: &ProjectPaths) {
+    let Some(files) = cache.get_mut("files").and_then(|files| files.as_object_mut()) else {
+        return;
+    };
+    for entry in files.values_mut().filter_map(|entry| entry.as_object_mut()) {
+        let pragmas = entry.remove("versionPragmas").unwrap_or_default();
+        let pragmas = pragmas
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|pragma| pragma.as_str())
+            .collect::<Vec<_>>();
+        let requirement = (!pragmas.is_empty()).then(|| pragmas.join(", "));
+        entry.insert("versionRequirement".to_string(), requirement.into());
+        entry.insert("artifacts".to_string(), serde_json::Value::Object(Default::default()));
+    }
+}
+
+/// Migrates `ethers-rs-sol-cache-2` to `ethers-rs-sol-cache-3`, which records the paths of the
+/// project. They're assumed to be the `paths` of the project the cache is read for.
+fn migrate_v2(cache: &mut serde_json::Value, paths: &ProjectPaths) {
+    if cache.get("paths").is_none() {
+        cache["paths"] = serde_json::to_value(paths).unwrap_or_default();
+    }
+}
+
+/// The extensions of the source files whose directories in the artifacts dir contain artifacts
+const SOURCE_FILE_EXTENSIONS: [&str; 4] = ["sol", "yul", "vy", "vyi"];
+
+/// The extensions of the extra output files that are written next to an artifact, see
+/// [`ExtraOutputFiles`](crate::ExtraOutputFiles)
+const EXTRA_OUTPUT_FILE_EXTENSIONS: [&str; 10] = [
+    "abi.json",
+    "metadata.json",
+    "iropt",
+    "ir",
+    "ewasm",
+    "asm",
+    "gensources",
+    "sourcemap",
+    "bin",
+    "deployed-bin",
+];
+
+/// Returns the artifact the output file belongs to: the file itself if it's an artifact or the
+/// artifact an extra output file was written for, `None` for files of other types or files that
+/// aren't in the directory of a source file
+fn artifact_of_output_file(file: &Path) -> Option<PathBuf> {
+    let source_dir = file.parent()?.extension()?;
+    if !SOURCE_FILE_EXTENSIONS.iter().any(|ext| source_dir == *ext) {
+        return None;
+    }
+    let name = file.file_name()?.to_str()?;
+    for ext in EXTRA_OUTPUT_FILE_EXTENSIONS {
+        if let Some(stem) = name.strip_suffix(ext).and_then(|stem| stem.strip_suffix('.')) {
+            if !stem.is_empty() {
+                return Some(file.with_file_name(format!("{stem}.json")));
+            }
+        }
+    }
+    name.ends_with(".json").then(|| file.to_path_buf())
+}
+
+/// Artifacts and build infos that aren't referenced by the cache anymore, see
+/// [`SolFilesCache::orphaned_files()`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrphanedFiles {
+    /// Artifacts and their extra output files
+    pub artifacts: BTreeSet<PathBuf>,
+    /// Build info files
+    pub build_infos: BTreeSet<PathBuf>,
+}
+
+impl OrphanedFiles {
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty() && self.build_infos.is_empty()
+    }
+
+    /// Returns an iterator over all orphaned files
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.artifacts.iter().chain(self.build_infos.iter())
+    }
+
+    /// Deletes all orphaned files and the directories of the `artifacts_dir` that are empty
+    /// afterwards
+    pub fn remove(&self, artifacts_dir: &Path) -> Result<()> {
+        for file in self.files() {
+            trace!("removing orphaned file \"{}\"", file.display());
+            match fs::remove_file(file) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(SolcError::io(err, file))
+                }
+                _ => {}
+            }
+        }
+        for file in &self.artifacts {
+            let mut dir = file.parent();
+            while let Some(current) = dir.filter(|dir| *dir != artifacts_dir) {
+                // only succeeds if the dir is empty
+                if fs::remove_dir(current).is_err() {
+                    break;
+                }
+                dir = current.parent();
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Default for SolFilesCache {
     fn default() -> Self {
         SolFilesCache {
@@ -405,6 +853,10 @@ impl<'a> From<&'a ProjectPathsConfig> for SolFilesCache {
 pub struct CacheEntry {
     /// the last modification time of this file
     pub last_modification_date: u64,
+    /// the size of this file in bytes, used together with the `last_modification_date` to detect
+    /// unchanged files without hashing them, see [`Project::trust_mtime`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
     /// hash to identify whether the content of the file changed
     pub content_hash: String,
     /// identifier name see [`crate::utils::source_name()`]
@@ -427,6 +879,10 @@ pub struct CacheEntry {
     /// This map tracks the artifacts by `name -> (Version -> PathBuf)`.
     /// This mimics the default artifacts directory structure
     pub artifacts: BTreeMap<String, BTreeMap<Version, PathBuf>>,
+    /// The ids of the build infos the artifacts of each version were emitted with, if build infos
+    /// were enabled, see [`RawBuildInfo`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub build_ids: BTreeMap<Version, String>,
 }
 
 impl CacheEntry {
@@ -456,8 +912,13 @@ impl CacheEntry {
     /// Reads the last modification date from the file's metadata
     pub fn read_last_modification_date(file: impl AsRef<Path>) -> Result<u64> {
         let file = file.as_ref();
-        let last_modification_date = fs::metadata(file)
-            .map_err(|err| SolcError::io(err, file.to_path_buf()))?
+        let metadata = fs::metadata(file).map_err(|err| SolcError::io(err, file.to_path_buf()))?;
+        Self::modification_date(&metadata, file)
+    }
+
+    /// Returns the last modification date of the file's `metadata` in milliseconds
+    fn modification_date(metadata: &fs::Metadata, file: &Path) -> Result<u64> {
+        let last_modification_date = metadata
             .modified()
             .map_err(|err| SolcError::io(err, file.to_path_buf()))?
             .duration_since(UNIX_EPOCH)
@@ -466,6 +927,18 @@ impl CacheEntry {
         Ok(last_modification_date)
     }
 
+    /// Returns `true` if the file's last modification date and size are still the same as when
+    /// this entry was created, which means that the file is assumed to be unchanged.
+    ///
+    /// This is always `false` for entries that don't record the size of the file.
+    pub fn is_unmodified(&self, file: impl AsRef<Path>) -> bool {
+        let file = file.as_ref();
+        let Some(file_size) = self.file_size else { return false };
+        let Ok(metadata) = fs::metadata(file) else { return false };
+        metadata.len() == file_size
+            && Self::modification_date(&metadata, file).ok() == Some(self.last_modification_date)
+    }
+
     /// Reads all artifact files associated with the `CacheEntry`
     ///
     /// **Note:** all artifact file paths should be absolute.
@@ -499,6 +972,7 @@ impl CacheEntry {
 
     /// Merges another `CacheEntries` artifacts into the existing set
     fn merge_artifacts(&mut self, other: CacheEntry) {
+        self.build_ids.extend(other.build_ids);
         for (name, artifacts) in other.artifacts {
             match self.artifacts.entry(name) {
                 Entry::Vacant(entry) => {
@@ -522,7 +996,8 @@ impl CacheEntry {
         self.artifacts.retain(|_, artifacts| {
             artifacts.retain(|version, _| versions.contains(version));
             !artifacts.is_empty()
-        })
+        });
+        self.build_ids.retain(|version, _| versions.contains(version));
     }
 
     /// Returns `true` if the artifacts set contains the given version
@@ -580,21 +1055,129 @@ impl CacheEntry {
     }
 }
 
+/// Why a file is compiled again instead of reusing its cached artifacts, see
+/// [`ProjectCompileOutput::dirty_reasons()`](crate::ProjectCompileOutput::dirty_reasons)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DirtyReason {
+    /// The file isn't part of the cache yet
+    NewFile,
+    /// The content of the file changed since it was compiled
+    ContentChanged,
+    /// The settings the file is compiled with changed
+    SolcConfigChanged(Vec<SettingsChange>),
+    /// The file wasn't compiled with this solc version yet
+    NewVersion(Version),
+    /// This artifact of the file doesn't exist anymore
+    MissingArtifact(PathBuf),
+    /// A file imported by the file is dirty, directly or indirectly: the chain of imports from the
+    /// file to the dirty import, like `[B.sol, C.sol]` if `A.sol` imports `B.sol` which imports
+    /// the dirty `C.sol`
+    DirtyImport(Vec<PathBuf>),
+}
+
+impl fmt::Display for DirtyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirtyReason::NewFile => write!(f, "new file"),
+            DirtyReason::ContentChanged => write!(f, "content changed"),
+            DirtyReason::SolcConfigChanged(changes) => {
+                write!(f, "solc settings changed")?;
+                for (idx, change) in changes.iter().enumerate() {
+                    write!(f, "{}{change}", if idx == 0 { ": " } else { ", " })?;
+                }
+                Ok(())
+            }
+            DirtyReason::NewVersion(version) => write!(f, "not compiled with solc {version} yet"),
+            DirtyReason::MissingArtifact(artifact) => {
+                write!(f, "missing artifact \"{}\"", artifact.display())
+            }
+            DirtyReason::DirtyImport(chain) => {
+                let chain = chain.iter().map(|file| file.display().to_string()).collect::<Vec<_>>();
+                write!(f, "dirty import {}", chain.join(" -> "))
+            }
+        }
+    }
+}
+
+/// A field of the solc settings that changed, see [`DirtyReason::SolcConfigChanged`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingsChange {
+    /// The path of the field in the json settings, like `optimizer.runs`
+    pub field: String,
+    /// The previous value, `None` if the field wasn't set
+    pub old: Option<serde_json::Value>,
+    /// The new value, `None` if the field isn't set anymore
+    pub new: Option<serde_json::Value>,
+}
+
+impl SettingsChange {
+    /// Returns all fields of the settings that differ between `old` and `new`, sorted by field
+    pub fn diff(old: &SolcConfig, new: &SolcConfig) -> Vec<Self> {
+        fn diff_values(
+            field: String,
+            old: Option<&serde_json::Value>,
+            new: Option<&serde_json::Value>,
+            changes: &mut Vec<SettingsChange>,
+        ) {
+            match (old, new) {
+                (Some(serde_json::Value::Object(old)), Some(serde_json::Value::Object(new))) => {
+                    let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+                    for key in keys {
+                        let field =
+                            if field.is_empty() { key.clone() } else { format!("{field}.{key}") };
+                        diff_values(field, old.get(key), new.get(key), changes);
+                    }
+                }
+                (old, new) if old != new => {
+                    changes.push(SettingsChange { field, old: old.cloned(), new: new.cloned() })
+                }
+                _ => {}
+            }
+        }
+
+        let old = serde_json::to_value(&old.settings).unwrap_or_default();
+        let new = serde_json::to_value(&new.settings).unwrap_or_default();
+        let mut changes = Vec::new();
+        diff_values(String::new(), Some(&old), Some(&new), &mut changes);
+        changes
+    }
+}
+
+impl fmt::Display for SettingsChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<serde_json::Value>| {
+            value.as_ref().map(|value| value.to_string()).unwrap_or_else(|| "unset".to_string())
+        };
+        write!(f, "{} ({} -> {})", self.field, value(&self.old), value(&self.new))
+    }
+}
+
 /// A helper abstraction over the [`SolFilesCache`] used to determine what files need to compiled
 /// and which `Artifacts` can be reused.
 #[derive(Debug)]
-pub(crate) struct ArtifactsCacheInner<'a, T: ArtifactOutput> {
+pub(crate) struct ArtifactsCacheInner<'a, T: ArtifactOutput, C: Compiler = Solc> {
     /// The preexisting cache file.
     pub cache: SolFilesCache,
 
     /// All already existing artifacts.
     pub cached_artifacts: Artifacts<T::Artifact>,
 
+    /// The index of the cached artifacts if the project uses the [`CacheFormat::Binary`], the
+    /// `cached_artifacts` are then only decoded from it once it's known which of them are reused.
+    pub artifact_index: Option<ArtifactIndex>,
+
     /// Relationship between all the files.
     pub edges: GraphEdges,
 
     /// The project.
-    pub project: &'a Project<T>,
+    pub project: &'a Project<T, C>,
+
+    /// The settings of all files, which are tracked by their cache entries.
+    pub profiles: CompilerProfiles,
+
+    /// The profiles files are compiled with by version, if it's not their own, see
+    /// [`ArtifactsCache::set_compile_profiles()`]
+    pub compile_profiles: HashMap<(Version, PathBuf), Option<usize>>,
 
     /// All the files that were filtered because they haven't changed.
     pub filtered: HashMap<PathBuf, (Source, HashSet<Version>)>,
@@ -611,11 +1194,22 @@ pub(crate) struct ArtifactsCacheInner<'a, T: ArtifactOutput> {
 
     /// The file hashes.
     pub content_hashes: HashMap<PathBuf, String>,
+
+    /// Why the dirty files are compiled again, see [`DirtyReason`]
+    pub dirty_reasons: BTreeMap<PathBuf, DirtyReason>,
 }
 
-impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
-    /// Creates a new cache entry for the file
-    fn create_cache_entry(&self, file: &Path, source: &Source) -> CacheEntry {
+impl<'a, T: ArtifactOutput, C: Compiler> ArtifactsCacheInner<'a, T, C> {
+    /// Returns the profile the file is compiled with by the `version`
+    fn compile_profile(&self, file: &Path, version: &Version) -> Option<usize> {
+        match self.compile_profiles.get(&(version.clone(), file.to_path_buf())) {
+            Some(profile) => *profile,
+            None => self.profiles.profile(file),
+        }
+    }
+
+    /// Creates a new cache entry for the file that's compiled with the `version`
+    fn create_cache_entry(&self, file: &Path, source: &Source, version: &Version) -> CacheEntry {
         let imports = self
             .edges
             .imports(file)
@@ -623,16 +1217,22 @@ impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
             .map(|import| utils::source_name(import, self.project.root()).to_path_buf())
             .collect();
 
+        // the stamp of when the source was read, the file may have changed since
         let entry = CacheEntry {
-            last_modification_date: CacheEntry::read_last_modification_date(file)
-                .unwrap_or_default(),
+            last_modification_date: match source.file_stamp {
+                Some(stamp) => stamp.last_modification_date,
+                None => CacheEntry::read_last_modification_date(file).unwrap_or_default(),
+            },
+            // sources that weren't read from disk are never trusted
+            file_size: source.file_stamp.map(|stamp| stamp.size),
             content_hash: source.content_hash(),
             source_name: utils::source_name(file, self.project.root()).into(),
-            solc_config: self.project.solc_config.clone(),
+            solc_config: self.profiles.solc_config(self.compile_profile(file, version)).clone(),
             imports,
             version_requirement: self.edges.version_requirement(file).map(|v| v.to_string()),
             // artifacts remain empty until we received the compiler output
             artifacts: Default::default(),
+            build_ids: Default::default(),
         };
 
         entry
@@ -645,7 +1245,7 @@ impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
         if let Some((_, versions)) = self.dirty_source_files.get_mut(file) {
             versions.insert(version);
         } else {
-            let entry = self.create_cache_entry(file, source);
+            let entry = self.create_cache_entry(file, source, &version);
             self.dirty_source_files.insert(file.to_path_buf(), (entry, HashSet::from([version])));
         }
     }
@@ -685,11 +1285,14 @@ impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
         // separates all source files that fit the criteria (dirty) from those that don't (clean)
         let mut dirty_sources = BTreeMap::new();
         let mut clean_sources = Vec::with_capacity(sources.len());
-        let dirty_files = self.get_dirty_files(&sources, version);
+        let mut dirty_files = self.get_dirty_files(&sources, version);
 
         for (file, source) in sources {
             let source = self.filter_source(file, source, &dirty_files);
             if source.dirty {
+                if let Some(reason) = dirty_files.remove(&source.file) {
+                    self.dirty_reasons.entry(source.file.clone()).or_insert(reason);
+                }
                 // mark all files that are imported by a dirty file
                 imports_of_dirty.extend(self.edges.all_imported_nodes(source.idx));
                 dirty_sources.insert(source.file, FilteredSource::Dirty(source.source));
@@ -720,27 +1323,32 @@ impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
         &self,
         file: PathBuf,
         source: Source,
-        dirty_files: &HashSet<PathBuf>,
+        dirty_files: &HashMap<PathBuf, DirtyReason>,
     ) -> FilteredSourceInfo {
         let idx = self.edges.node_id(&file);
-        let dirty = dirty_files.contains(&file);
+        let dirty = dirty_files.contains_key(&file);
         FilteredSourceInfo { file, source, idx, dirty }
     }
 
-    /// Returns a set of files that are dirty itself or import dirty file directly or indirectly.
-    fn get_dirty_files(&self, sources: &Sources, version: &Version) -> HashSet<PathBuf> {
-        let mut dirty_files = HashSet::new();
+    /// Returns all files that are dirty itself or import dirty file directly or indirectly,
+    /// together with the reason why they're dirty.
+    fn get_dirty_files(
+        &self,
+        sources: &Sources,
+        version: &Version,
+    ) -> HashMap<PathBuf, DirtyReason> {
+        let mut dirty_files = HashMap::new();
 
         // Pre-add all sources that are guaranteed to be dirty
         for file in sources.keys() {
-            if self.is_dirty_impl(file, version) {
-                dirty_files.insert(file.to_path_buf());
+            if let Some(reason) = self.dirty_reason(file, version) {
+                dirty_files.insert(file.to_path_buf(), reason);
             }
         }
 
         // Perform DFS to find direct/indirect importers of dirty files
-        for file in dirty_files.clone().iter() {
-            self.populate_dirty_files(file, &mut dirty_files);
+        for file in dirty_files.keys().cloned().collect::<Vec<_>>() {
+            self.populate_dirty_files(&file, &[], &mut dirty_files);
         }
 
         dirty_files
@@ -748,37 +1356,50 @@ impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
 
     /// Accepts known dirty file and performs DFS over it's importers marking all visited files as
     /// dirty.
+    ///
+    /// `chain` are the files from the `file` to the dirty import that made it dirty.
     #[instrument(level = "trace", skip_all, fields(file = %file.display()))]
-    fn populate_dirty_files(&self, file: &Path, dirty_files: &mut HashSet<PathBuf>) {
-        for file in self.edges.importers(file) {
+    fn populate_dirty_files(
+        &self,
+        file: &Path,
+        chain: &[PathBuf],
+        dirty_files: &mut HashMap<PathBuf, DirtyReason>,
+    ) {
+        let chain =
+            std::iter::once(file.to_path_buf()).chain(chain.iter().cloned()).collect::<Vec<_>>();
+        for importer in self.edges.importers(file) {
             // If file is marked as dirty we either have already visited it or it was marked as
             // dirty initially and will be visited at some point later.
-            if !dirty_files.contains(file) {
-                dirty_files.insert(file.to_path_buf());
-                self.populate_dirty_files(file, dirty_files);
+            if !dirty_files.contains_key(importer) {
+                dirty_files.insert(importer.to_path_buf(), DirtyReason::DirtyImport(chain.clone()));
+                self.populate_dirty_files(importer, &chain, dirty_files);
             }
         }
     }
 
-    fn is_dirty_impl(&self, file: &Path, version: &Version) -> bool {
+    /// Returns why the file needs to be compiled with the `version`, `None` if its cached
+    /// artifacts can be reused
+    fn dirty_reason(&self, file: &Path, version: &Version) -> Option<DirtyReason> {
         let Some(hash) = self.content_hashes.get(file) else {
-            trace!("missing cache entry");
-            return true;
+            trace!("missing content hash");
+            return Some(DirtyReason::NewFile);
         };
 
         let Some(entry) = self.cache.entry(file) else {
-            trace!("missing content hash");
-            return true;
+            trace!("missing cache entry");
+            return Some(DirtyReason::NewFile);
         };
 
         if entry.content_hash != *hash {
             trace!("content hash changed");
-            return true;
+            return Some(DirtyReason::ContentChanged);
         }
 
-        if self.project.solc_config != entry.solc_config {
+        let solc_config = self.profiles.solc_config(self.compile_profile(file, version));
+        if *solc_config != entry.solc_config {
             trace!("solc config changed");
-            return true;
+            let changes = SettingsChange::diff(&entry.solc_config, solc_config);
+            return Some(DirtyReason::SolcConfigChanged(changes));
         }
 
         // only check artifact's existence if the file generated artifacts.
@@ -786,34 +1407,65 @@ impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
         // re-export) do not create artifacts
         if entry.artifacts.is_empty() {
             trace!("no artifacts");
-            return false;
+            return None;
         }
 
         if !entry.contains_version(version) {
             trace!("missing linked artifacts",);
-            return true;
+            return Some(DirtyReason::NewVersion(version.clone()));
         }
 
-        if entry.artifacts_for_version(version).any(|artifact_path| {
-            let missing_artifact = !self.cached_artifacts.has_artifact(artifact_path);
-            if missing_artifact {
-                trace!("missing artifact \"{}\"", artifact_path.display());
-            }
-            missing_artifact
-        }) {
-            return true;
+        if let Some(artifact_path) = entry
+            .artifacts_for_version(version)
+            .find(|artifact_path| !self.has_cached_artifact(artifact_path))
+        {
+            trace!("missing artifact \"{}\"", artifact_path.display());
+            return Some(DirtyReason::MissingArtifact(artifact_path.clone()));
         }
 
         // all things match, can be reused
-        false
+        None
+    }
+
+    /// Returns `true` if the artifact can be reused
+    fn has_cached_artifact(&self, artifact_path: &Path) -> bool {
+        match &self.artifact_index {
+            Some(index) => index.contains(artifact_path) && artifact_path.exists(),
+            None => self.cached_artifacts.has_artifact(artifact_path),
+        }
     }
 
     /// Adds the file's hashes to the set if not set yet
+    ///
+    /// If the project trusts modification dates, see [`Project::trust_mtime`], the cached hash is
+    /// reused for files whose last modification date and size when they were read match the
+    /// cache entry, all other files are hashed.
     fn fill_hashes(&mut self, sources: &Sources) {
+        let trust_mtime = self.project.trust_mtime;
         for (file, source) in sources {
-            if let hash_map::Entry::Vacant(entry) = self.content_hashes.entry(file.clone()) {
-                entry.insert(source.content_hash());
+            let hash_map::Entry::Vacant(hash) = self.content_hashes.entry(file.clone()) else {
+                continue;
+            };
+            // sources that weren't read from disk, like overlay files, have no stamp
+            let stamp = source.file_stamp.filter(|_| trust_mtime);
+            let (Some(stamp), Some(entry)) = (stamp, self.cache.files.get_mut(file)) else {
+                hash.insert(source.content_hash());
+                continue;
+            };
+            if entry.file_size == Some(stamp.size)
+                && entry.last_modification_date == stamp.last_modification_date
+            {
+                hash.insert(entry.content_hash.clone());
+                continue;
+            }
+
+            let content_hash = source.content_hash();
+            if entry.content_hash == content_hash {
+                // the file was touched but not changed, so that the next build can skip it
+                entry.last_modification_date = stamp.last_modification_date;
+                entry.file_size = Some(stamp.size);
             }
+            hash.insert(content_hash);
         }
     }
 }
@@ -821,36 +1473,42 @@ impl<'a, T: ArtifactOutput> ArtifactsCacheInner<'a, T> {
 /// Abstraction over configured caching which can be either non-existent or an already loaded cache
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
-pub(crate) enum ArtifactsCache<'a, T: ArtifactOutput> {
+pub(crate) enum ArtifactsCache<'a, T: ArtifactOutput, C: Compiler = Solc> {
     /// Cache nothing on disk
-    Ephemeral(GraphEdges, &'a Project<T>),
+    Ephemeral(GraphEdges, &'a Project<T, C>, CompilerProfiles),
     /// Handles the actual cached artifacts, detects artifacts that can be reused
-    Cached(ArtifactsCacheInner<'a, T>),
+    Cached(ArtifactsCacheInner<'a, T, C>),
 }
 
-impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
-    pub fn new(project: &'a Project<T>, edges: GraphEdges) -> Result<Self> {
+impl<'a, T: ArtifactOutput, C: Compiler> ArtifactsCache<'a, T, C> {
+    pub fn new(
+        project: &'a Project<T, C>,
+        edges: GraphEdges,
+        profiles: CompilerProfiles,
+    ) -> Result<Self> {
         /// Returns the [SolFilesCache] to use
         ///
         /// Returns a new empty cache if the cache does not exist or `invalidate_cache` is set.
-        fn get_cache<T: ArtifactOutput>(
-            project: &Project<T>,
+        fn get_cache<T: ArtifactOutput, C: Compiler>(
+            project: &Project<T, C>,
             invalidate_cache: bool,
-        ) -> SolFilesCache {
+        ) -> (SolFilesCache, Option<String>) {
             // the currently configured paths
             let paths = project.paths.paths_relative();
 
             if !invalidate_cache && project.cache_path().exists() {
-                if let Ok(cache) = SolFilesCache::read_joined(&project.paths) {
-                    if cache.paths == paths {
+                match read_project_cache(project) {
+                    Ok((cache, generation)) if cache.paths == paths => {
                         // unchanged project paths
-                        return cache;
+                        return (cache, generation);
                     }
+                    Ok(_) => {}
+                    Err(err) => warn!(%err, "failed to read cache, compiling from scratch"),
                 }
             }
 
             // new empty cache
-            SolFilesCache::new(Default::default(), paths)
+            (SolFilesCache::new(Default::default(), paths), None)
         }
 
         let cache = if project.cached {
@@ -860,15 +1518,19 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
             let invalidate_cache = !edges.unresolved_imports().is_empty();
 
             // read the cache file if it already exists
-            let mut cache = get_cache(project, invalidate_cache);
+            let (mut cache, generation) = get_cache(project, invalidate_cache);
 
             cache.remove_missing_files();
 
-            // read all artifacts
-            let cached_artifacts = if project.paths.artifacts.exists() {
+            // read all artifacts, or only their index which is decoded lazily
+            let artifact_index =
+                generation.and_then(|generation| read_artifact_index(project, &generation));
+            let cached_artifacts = if artifact_index.is_some() {
+                Default::default()
+            } else if project.paths.artifacts.exists() {
                 trace!("reading artifacts from cache...");
-                // if we failed to read the whole set of artifacts we use an empty set
-                let artifacts = cache.read_artifacts::<T::Artifact>().unwrap_or_default();
+                // artifacts that can't be read are discarded and their sources recompiled
+                let artifacts = cache.read_intact_artifacts::<T::Artifact>();
                 trace!("read {} artifacts from cache", artifacts.artifact_files().count());
                 artifacts
             } else {
@@ -878,17 +1540,21 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
             let cache = ArtifactsCacheInner {
                 cache,
                 cached_artifacts,
+                artifact_index,
                 edges,
                 project,
+                profiles,
+                compile_profiles: Default::default(),
                 filtered: Default::default(),
                 dirty_source_files: Default::default(),
                 content_hashes: Default::default(),
+                dirty_reasons: Default::default(),
             };
 
             ArtifactsCache::Cached(cache)
         } else {
             // nothing to cache
-            ArtifactsCache::Ephemeral(edges, project)
+            ArtifactsCache::Ephemeral(edges, project, profiles)
         };
 
         Ok(cache)
@@ -897,7 +1563,7 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
     /// Returns the graph data for this project
     pub fn graph(&self) -> &GraphEdges {
         match self {
-            ArtifactsCache::Ephemeral(graph, _) => graph,
+            ArtifactsCache::Ephemeral(graph, _, _) => graph,
             ArtifactsCache::Cached(inner) => &inner.edges,
         }
     }
@@ -906,39 +1572,76 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
     #[allow(unused)]
     #[doc(hidden)]
     // only useful for debugging for debugging purposes
-    pub fn as_cached(&self) -> Option<&ArtifactsCacheInner<'a, T>> {
+    pub fn as_cached(&self) -> Option<&ArtifactsCacheInner<'a, T, C>> {
         match self {
-            ArtifactsCache::Ephemeral(_, _) => None,
+            ArtifactsCache::Ephemeral(..) => None,
             ArtifactsCache::Cached(cached) => Some(cached),
         }
     }
 
     pub fn output_ctx(&self) -> OutputContext<'_> {
         match self {
-            ArtifactsCache::Ephemeral(_, _) => Default::default(),
+            ArtifactsCache::Ephemeral(..) => Default::default(),
             ArtifactsCache::Cached(inner) => OutputContext::new(&inner.cache),
         }
     }
 
-    pub fn project(&self) -> &'a Project<T> {
+    pub fn project(&self) -> &'a Project<T, C> {
         match self {
-            ArtifactsCache::Ephemeral(_, project) => project,
+            ArtifactsCache::Ephemeral(_, project, _) => project,
             ArtifactsCache::Cached(cache) => cache.project,
         }
     }
 
+    /// Returns the settings of all files
+    pub fn profiles(&self) -> &CompilerProfiles {
+        match self {
+            ArtifactsCache::Ephemeral(_, _, profiles) => profiles,
+            ArtifactsCache::Cached(cache) => &cache.profiles,
+        }
+    }
+
+    /// Returns the files of the project's overlay and all files that import them, see
+    /// [`overlaid_files()`]
+    pub fn overlaid_files(&self) -> HashSet<PathBuf> {
+        overlaid_files(self.project(), self.graph())
+    }
+
+    /// Sets the profiles files are compiled with by version.
+    ///
+    /// Files that are imported by files of another profile are compiled with that profile if
+    /// they're not part of a source set of their own profile, their cache entries track the
+    /// settings they're actually compiled with.
+    pub fn set_compile_profiles(
+        &mut self,
+        compile_profiles: HashMap<(Version, PathBuf), Option<usize>>,
+    ) {
+        if let ArtifactsCache::Cached(cache) = self {
+            cache.compile_profiles = compile_profiles;
+        }
+    }
+
     /// Adds the file's hashes to the set if not set yet
     pub fn fill_content_hashes(&mut self, sources: &Sources) {
         match self {
-            ArtifactsCache::Ephemeral(_, _) => {}
+            ArtifactsCache::Ephemeral(..) => {}
             ArtifactsCache::Cached(cache) => cache.fill_hashes(sources),
         }
     }
 
+    /// Returns why the files that were filtered so far are compiled again, empty if caching is
+    /// disabled
+    pub fn dirty_reasons(&self) -> BTreeMap<PathBuf, DirtyReason> {
+        match self {
+            ArtifactsCache::Ephemeral(..) => Default::default(),
+            ArtifactsCache::Cached(cache) => cache.dirty_reasons.clone(),
+        }
+    }
+
     /// Filters out those sources that don't need to be compiled
     pub fn filter(&mut self, sources: Sources, version: &Version) -> FilteredSources {
         match self {
-            ArtifactsCache::Ephemeral(_, _) => sources.into(),
+            ArtifactsCache::Ephemeral(..) => sources.into(),
             ArtifactsCache::Cached(cache) => cache.filter(sources, version),
         }
     }
@@ -951,10 +1654,11 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
     pub fn consume(
         self,
         written_artifacts: &Artifacts<T::Artifact>,
+        build_infos: &BTreeMap<Version, RawBuildInfo>,
         write_to_disk: bool,
     ) -> Result<Artifacts<T::Artifact>> {
         match self {
-            ArtifactsCache::Ephemeral(_, _) => {
+            ArtifactsCache::Ephemeral(..) => {
                 trace!("no cache configured, ephemeral");
                 Ok(Default::default())
             }
@@ -962,14 +1666,32 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
                 let ArtifactsCacheInner {
                     mut cache,
                     mut cached_artifacts,
+                    artifact_index,
                     mut dirty_source_files,
                     filtered,
                     project,
+                    edges,
                     ..
                 } = cache;
 
+                if let Some(index) = &artifact_index {
+                    cached_artifacts =
+                        read_indexed_artifacts(&cache, index, written_artifacts, &filtered);
+                }
+
+                // the output of overlaid files isn't written, so their previous entries still
+                // describe the files on disk
+                let overlaid_entries = overlaid_files(project, &edges)
+                    .into_iter()
+                    .filter_map(|file| {
+                        dirty_source_files.remove(&file);
+                        cache.files.remove_entry(&file)
+                    })
+                    .collect::<Vec<_>>();
+
                 // keep only those files that were previously filtered (not dirty, reused)
                 cache.retain(filtered.iter().map(|(p, (_, v))| (p.as_path(), v)));
+                cache.files.extend(overlaid_entries);
 
                 // add the written artifacts to the cache entries, this way we can keep a mapping
                 // from solidity file to its artifacts
@@ -1026,6 +1748,15 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
                     }
                 }
 
+                // track the build infos the dirty files were compiled with
+                for (entry, versions) in dirty_source_files.values_mut() {
+                    for version in versions.iter() {
+                        if let Some(build_info) = build_infos.get(version) {
+                            entry.build_ids.insert(version.clone(), build_info.id.clone());
+                        }
+                    }
+                }
+
                 // add the new cache entries to the cache file
                 cache
                     .extend(dirty_source_files.into_iter().map(|(file, (entry, _))| (file, entry)));
@@ -1037,7 +1768,7 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
                     cache
                         .strip_entries_prefix(project.root())
                         .strip_artifact_files_prefixes(project.artifacts_path());
-                    cache.write(project.cache_path())?;
+                    write_cache_files(project, &cache, written_artifacts, &cached_artifacts)?;
                 }
 
                 Ok(cached_artifacts)
@@ -1045,3 +1776,300 @@ impl<'a, T: ArtifactOutput> ArtifactsCache<'a, T> {
         }
     }
 }
+
+/// Returns the files of the project's overlay and all files that import them transitively.
+///
+/// Their output depends on contents that aren't on disk, so neither their artifacts nor their cache
+/// entries are written.
+fn overlaid_files<T: ArtifactOutput, C: Compiler>(
+    project: &Project<T, C>,
+    edges: &GraphEdges,
+) -> HashSet<PathBuf> {
+    let mut files = HashSet::new();
+    let mut queue = project.overlay.files().cloned().collect::<Vec<_>>();
+    while let Some(file) = queue.pop() {
+        if !files.contains(&file) {
+            queue.extend(edges.importers(&file).into_iter().cloned());
+            files.insert(file);
+        }
+    }
+    files
+}
+
+/// Reads the cache file of the project with paths adjoined to the project's paths, see
+/// [`SolFilesCache::read_joined()`].
+///
+/// If the project uses the [`CacheFormat::Binary`], this also returns the generation of the json
+/// cache file, and the binary cache is decoded instead of the json cache file if it was written
+/// together with it.
+fn read_project_cache<T: ArtifactOutput, C: Compiler>(
+    project: &Project<T, C>,
+) -> Result<(SolFilesCache, Option<String>)> {
+    if project.cache_format != CacheFormat::Binary {
+        return Ok((SolFilesCache::read_joined(&project.paths)?, None));
+    }
+
+    let path = project.cache_path();
+    trace!("reading solfiles cache at {}", path.display());
+    let content = fs::read(path).map_err(|err| SolcError::io(err, path))?;
+    let generation = cache_generation(&content);
+
+    let binary_path = project.binary_cache_path();
+    let binary = if binary_path.exists() {
+        match SolFilesCache::read_binary(&binary_path) {
+            Ok((cache, binary_generation)) if binary_generation == generation => Some(cache),
+            Ok(_) => {
+                trace!("binary cache is outdated, reading json cache instead");
+                None
+            }
+            Err(err) => {
+                warn!(%err, "failed to read binary cache, reading json cache instead");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let mut cache = match binary {
+        Some(cache) => cache,
+        None => SolFilesCache::from_json_slice(&content, path, &project.paths.paths_relative())?,
+    };
+    cache.join_entries(project.root()).join_artifacts_files(project.artifacts_path());
+    Ok((cache, Some(generation)))
+}
+
+/// Writes the cache file of the project, and if it uses the [`CacheFormat::Binary`] also the binary
+/// cache and the [`ArtifactIndex`].
+///
+/// The paths of the `cache` are expected to be relative. The binary cache and the index of a
+/// project that uses the [`CacheFormat::Json`] are removed, since they're outdated now.
+fn write_cache_files<T: ArtifactOutput, C: Compiler>(
+    project: &Project<T, C>,
+    cache: &SolFilesCache,
+    written_artifacts: &Artifacts<T::Artifact>,
+    cached_artifacts: &Artifacts<T::Artifact>,
+) -> Result<()> {
+    let path = project.cache_path();
+    match project.cache_format {
+        CacheFormat::Json => {
+            cache.write(path)?;
+            for file in [project.binary_cache_path(), project.artifact_index_path()] {
+                match fs::remove_file(&file) {
+                    Ok(()) => trace!("removed outdated \"{}\"", file.display()),
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(SolcError::io(err, file)),
+                }
+            }
+        }
+        CacheFormat::Binary => {
+            let content = serde_json::to_vec(cache)?;
+            let generation = cache_generation(&content);
+            write_artifact_index(project, cache, &generation, written_artifacts, cached_artifacts)?;
+            cache.write_binary(project.binary_cache_path(), &generation)?;
+            trace!(
+                "writing cache with {} entries to json file: \"{}\"",
+                cache.len(),
+                path.display()
+            );
+            utils::create_parent_dir_all(path)?;
+            utils::write_atomic(path, content)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the [`ArtifactIndex`] of the project, if it was written together with the json cache
+/// file of the given `generation`
+fn read_artifact_index<T: ArtifactOutput, C: Compiler>(
+    project: &Project<T, C>,
+    generation: &str,
+) -> Option<ArtifactIndex> {
+    let path = project.artifact_index_path();
+    if !path.exists() {
+        return None;
+    }
+    match ArtifactIndex::read(&path, project.artifacts_path()) {
+        Ok(index) if index.generation() == generation => Some(index),
+        Ok(_) => {
+            trace!("artifact index is outdated, reading artifacts instead");
+            None
+        }
+        Err(err) => {
+            warn!(%err, "failed to read artifact index, reading artifacts instead");
+            None
+        }
+    }
+}
+
+/// Decodes the cached artifacts from the index, except those that are replaced by the
+/// `written_artifacts` of dirty files, see [`ArtifactsCache::consume()`]
+fn read_indexed_artifacts<A: DeserializeOwned + Send + Sync>(
+    cache: &SolFilesCache,
+    index: &ArtifactIndex,
+    written_artifacts: &Artifacts<A>,
+    filtered: &HashMap<PathBuf, (Source, HashSet<Version>)>,
+) -> Artifacts<A> {
+    use rayon::prelude::*;
+
+    let artifacts = cache
+        .files
+        .par_iter()
+        .filter_map(|(file, entry)| {
+            let file_name = format!("{}", file.display());
+            // only the reused versions of recompiled files are kept
+            let recompiled = written_artifacts.as_ref().contains_key(&file_name);
+            let reused = filtered.get(file).map(|(_, versions)| versions);
+            let keep = |version: &Version| {
+                !recompiled || reused.map_or(false, |versions| versions.contains(version))
+            };
+            let mut artifacts = BTreeMap::new();
+            for (name, versioned_files) in &entry.artifacts {
+                let mut files = Vec::new();
+                for (version, artifact_file) in versioned_files {
+                    if !keep(version) {
+                        continue;
+                    }
+                    match index.read_artifact(artifact_file) {
+                        Ok(Some(artifact)) => files.push(ArtifactFile {
+                            artifact,
+                            file: artifact_file.clone(),
+                            version: version.clone(),
+                        }),
+                        Ok(None) => {}
+                        Err(err) => {
+                            warn!(?artifact_file, %err, "failed to decode indexed artifact")
+                        }
+                    }
+                }
+                if !files.is_empty() {
+                    artifacts.insert(name.clone(), files);
+                }
+            }
+            (!artifacts.is_empty()).then_some((file_name, artifacts))
+        })
+        .collect::<ArtifactsMap<_>>();
+    Artifacts(artifacts)
+}
+
+/// Writes the [`ArtifactIndex`] of all artifacts of the cache entries, the artifact paths of the
+/// `cache` are relative to the artifacts dir
+fn write_artifact_index<T: ArtifactOutput, C: Compiler>(
+    project: &Project<T, C>,
+    cache: &SolFilesCache,
+    generation: &str,
+    written_artifacts: &Artifacts<T::Artifact>,
+    cached_artifacts: &Artifacts<T::Artifact>,
+) -> Result<()> {
+    let artifacts_dir = project.artifacts_path();
+    let artifacts = cached_artifacts
+        .artifact_files()
+        .chain(written_artifacts.artifact_files())
+        .map(|artifact| (artifact.file.as_path(), &artifact.artifact))
+        .collect::<HashMap<_, _>>();
+    let mut encoded = Vec::new();
+    for entry in cache.entries() {
+        for (_, artifact_file) in entry.artifacts_versions() {
+            let artifact_file = artifacts_dir.join(artifact_file);
+            if let Some(artifact) = artifacts.get(artifact_file.as_path()) {
+                encoded.push((artifact_file, ArtifactIndex::encode(artifact)?));
+            }
+        }
+    }
+    ArtifactIndex::write(
+        project.artifact_index_path(),
+        artifacts_dir,
+        generation,
+        encoded.iter().map(|(file, encoded)| (file.as_path(), encoded.into())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_format(format: &str, paths: &ProjectPaths) -> Result<SolFilesCache> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/cache-formats");
+        SolFilesCache::read_with_paths(&root.join(format!("{format}.json")), paths)
+    }
+
+    #[test]
+    fn can_find_artifact_of_output_file() {
+        let artifact = Some(PathBuf::from("out/Greeter.sol/Greeter.json"));
+        for name in ["Greeter.json", "Greeter.metadata.json", "Greeter.abi.json", "Greeter.bin"] {
+            let file = Path::new("out/Greeter.sol").join(name);
+            assert_eq!(artifact_of_output_file(&file), artifact);
+        }
+        assert_eq!(artifact_of_output_file(Path::new("out/Greeter.sol/.gitignore")), None);
+        assert_eq!(artifact_of_output_file(Path::new("out/Greeter.sol/.bin")), None);
+        assert_eq!(artifact_of_output_file(Path::new("out/data/config.json")), None);
+        assert_eq!(artifact_of_output_file(Path::new("out/cache/solidity-files-cache.json")), None);
+    }
+
+    #[test]
+    fn can_migrate_cache_formats() {
+        let latest = read_format(ETHERS_FORMAT_VERSION, &Default::default()).unwrap();
+        let paths = latest.paths.clone();
+        let dir = tempfile::tempdir().unwrap();
+
+        for format in ETHERS_FORMAT_VERSIONS {
+            let cache = read_format(format, &paths).unwrap();
+            assert_eq!(cache.format, ETHERS_FORMAT_VERSION, "{format}");
+            assert_eq!(cache.paths, paths, "{format}");
+            assert_eq!(
+                cache.files.keys().collect::<Vec<_>>(),
+                latest.files.keys().collect::<Vec<_>>()
+            );
+
+            let file = dir.path().join(format!("{format}.json"));
+            cache.write(&file).unwrap();
+            assert_eq!(SolFilesCache::read(&file).unwrap(), cache, "{format}");
+        }
+
+        assert_eq!(read_format("ethers-rs-sol-cache-2", &paths).unwrap(), latest);
+
+        // the artifacts of the first format aren't tracked per solc version
+        let cache = read_format("ethers-rs-sol-cache-1", &paths).unwrap();
+        let entry = cache.entry("src/Dapp.sol").unwrap();
+        assert_eq!(entry.version_requirement.as_deref(), Some(">=0.6.6, <0.9.0"));
+        assert!(entry.artifacts.is_empty());
+        assert_eq!(cache.entry("src/Dapp.t.sol").unwrap().version_requirement, None);
+    }
+
+    #[test]
+    fn can_round_trip_binary_cache() {
+        let cache = read_format(ETHERS_FORMAT_VERSION, &Default::default()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(SOLIDITY_FILES_CACHE_BINARY_FILENAME);
+        cache.write_binary(&file, "generation").unwrap();
+        let content = fs::read(&file).unwrap();
+        assert!(content.starts_with(&BINARY_CACHE_MAGIC));
+        assert!(content.len() < serde_json::to_vec(&cache).unwrap().len());
+
+        let (decoded, generation) = SolFilesCache::read_binary(&file).unwrap();
+        assert_eq!(generation, "generation");
+        assert_eq!(decoded, cache);
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&cache).unwrap());
+
+        let mut newer = cache;
+        newer.format = "ethers-rs-sol-cache-4".to_string();
+        newer.write_binary(&file, "generation").unwrap();
+        let err = SolFilesCache::read_binary(&file).unwrap_err();
+        assert!(matches!(err, SolcError::UnsupportedCacheFormat { .. }), "{err}");
+    }
+
+    #[test]
+    fn can_reject_newer_cache_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(SOLIDITY_FILES_CACHE_FILENAME);
+        fs::write(&file, r#"{"_format":"ethers-rs-sol-cache-4","files":{}}"#).unwrap();
+
+        let err = SolFilesCache::read(&file).unwrap_err();
+        assert!(
+            matches!(&err, SolcError::UnsupportedCacheFormat { format, .. } if format == "ethers-rs-sol-cache-4"),
+            "{err}"
+        );
+        assert!(err
+            .to_string()
+            .contains("the latest supported format is \"ethers-rs-sol-cache-3\""));
+    }
+}
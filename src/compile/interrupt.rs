@@ -0,0 +1,311 @@
+//! Timeouts and cancellation of compiler processes
+
+use std::{
+    fmt,
+    io::{self, Read},
+    process::{Child, ExitStatus, Output},
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often a running compiler process is checked for a timeout or cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A token to cancel a compilation.
+///
+/// All clones share the same state, so a clone can be cancelled from another thread while the
+/// project compiles. Running compiler processes are killed and no further compiler jobs are
+/// started.
+///
+/// Tokens compare equal if they are clones of each other.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new token that's not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token and all its clones
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::SeqCst)
+    }
+
+    /// Whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::SeqCst)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+/// When a running compiler process is killed.
+///
+/// A [`Project`](crate::Project) passes its
+/// [`solc_timeout`](crate::Project::solc_timeout) and
+/// [`cancellation_token`](crate::Project::cancellation_token) to every compiler job it starts.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InterruptOptions<'a> {
+    /// The maximum time a single compiler invocation may take
+    pub timeout: Option<Duration>,
+    /// Kills the compiler process once it's cancelled
+    pub cancellation_token: Option<&'a CancellationToken>,
+}
+
+impl<'a> InterruptOptions<'a> {
+    /// Creates new options with the given timeout and token
+    pub fn new(
+        timeout: Option<Duration>,
+        cancellation_token: Option<&'a CancellationToken>,
+    ) -> Self {
+        Self { timeout, cancellation_token }
+    }
+
+    /// Whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.map_or(false, CancellationToken::is_cancelled)
+    }
+}
+
+/// The reason a compiler process was killed before it finished
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interrupt {
+    /// The process exceeded the configured timeout
+    Timeout(Duration),
+    /// The [`CancellationToken`] was cancelled
+    Cancelled,
+}
+
+impl Interrupt {
+    /// Returns the interrupt that applies at this point, if any
+    fn check(
+        timeout: Option<(Duration, Instant)>,
+        token: Option<&CancellationToken>,
+    ) -> Option<Self> {
+        if token.map_or(false, CancellationToken::is_cancelled) {
+            return Some(Interrupt::Cancelled);
+        }
+        match timeout {
+            Some((timeout, deadline)) if Instant::now() >= deadline => {
+                Some(Interrupt::Timeout(timeout))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Interrupt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interrupt::Timeout(timeout) => write!(f, "timed out after {timeout:?}"),
+            Interrupt::Cancelled => f.write_str("was cancelled"),
+        }
+    }
+}
+
+/// Waits for the child to exit and collects its output, like [`Child::wait_with_output()`].
+///
+/// The child is killed if it doesn't finish within the timeout or if the token is cancelled.
+pub(crate) fn wait_with_output(
+    child: Child,
+    interrupt: InterruptOptions<'_>,
+) -> io::Result<Result<Output, Interrupt>> {
+    if interrupt.timeout.is_none() && interrupt.cancellation_token.is_none() {
+        return child.wait_with_output().map(Ok);
+    }
+    let read_to_end = |stdout: &mut dyn Read| {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf).map(|_| buf)
+    };
+    Ok(match wait_with(child, interrupt, read_to_end)? {
+        Ok(output) => {
+            Ok(Output { status: output.status, stdout: output.stdout?, stderr: output.stderr })
+        }
+        Err(interrupt) => Err(interrupt),
+    })
+}
+
+/// The output of a process whose stdout was processed while it ran, see [`wait_with`]
+pub(crate) struct ProcessedOutput<R> {
+    pub(crate) status: ExitStatus,
+    pub(crate) stdout: R,
+    pub(crate) stderr: Vec<u8>,
+}
+
+/// Waits for the child to exit while `read_stdout` consumes its stdout on a separate thread, so
+/// the output can be processed while it's produced.
+///
+/// The child is killed if it doesn't finish within the timeout or if the token is cancelled.
+pub(crate) fn wait_with<F, R>(
+    mut child: Child,
+    interrupt: InterruptOptions<'_>,
+    read_stdout: F,
+) -> io::Result<Result<ProcessedOutput<R>, Interrupt>>
+where
+    F: FnOnce(&mut dyn Read) -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let InterruptOptions { timeout, cancellation_token: token } = interrupt;
+    let timeout = timeout.map(|timeout| (timeout, Instant::now() + timeout));
+
+    // close stdin, so the child doesn't wait for more input
+    drop(child.stdin.take());
+
+    // the pipes need to be read while waiting, otherwise the child blocks once they're full
+    let stdout = child.stdout.take();
+    let stdout = thread::spawn(move || match stdout {
+        Some(mut stdout) => read_stdout(&mut stdout),
+        None => read_stdout(&mut io::empty()),
+    });
+    let stderr = child.stderr.take();
+    let stderr = thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut stderr) = stderr {
+            stderr.read_to_end(&mut buf)?;
+        }
+        Ok::<_, io::Error>(buf)
+    });
+
+    let status = loop {
+        if timeout.is_none() && token.is_none() {
+            break child.wait()?;
+        }
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if let Some(interrupt) = Interrupt::check(timeout, token) {
+            debug!(%interrupt, "killing compiler process");
+            // the process may have exited in the meantime
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(Err(interrupt));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    fn join<T>(handle: thread::JoinHandle<T>) -> T {
+        handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+    let stderr = join(stderr)?;
+    Ok(Ok(ProcessedOutput { status, stdout: join(stdout), stderr }))
+}
+
+/// Same as [`wait_with_output`] but for a [`tokio::process::Child`].
+///
+/// The child is killed if it doesn't finish within the timeout or if the token is cancelled.
+#[cfg(feature = "async")]
+pub(crate) async fn async_wait_with_output(
+    mut child: tokio::process::Child,
+    interrupt: InterruptOptions<'_>,
+) -> io::Result<Result<Output, Interrupt>> {
+    use futures_util::future::{self, Either};
+    use tokio::io::AsyncReadExt;
+
+    if interrupt.timeout.is_none() && interrupt.cancellation_token.is_none() {
+        return child.wait_with_output().await.map(Ok);
+    }
+    let InterruptOptions { timeout, cancellation_token: token } = interrupt;
+    let timeout = timeout.map(|timeout| (timeout, Instant::now() + timeout));
+
+    // close stdin, so the child doesn't wait for more input
+    drop(child.stdin.take());
+
+    let (mut stdout, mut stderr) = (child.stdout.take(), child.stderr.take());
+    let interrupted = {
+        // the pipes need to be read while waiting, otherwise the child blocks once they're full
+        let read_stdout = async {
+            let mut buf = Vec::new();
+            if let Some(stdout) = &mut stdout {
+                stdout.read_to_end(&mut buf).await?;
+            }
+            Ok::<_, io::Error>(buf)
+        };
+        let read_stderr = async {
+            let mut buf = Vec::new();
+            if let Some(stderr) = &mut stderr {
+                stderr.read_to_end(&mut buf).await?;
+            }
+            Ok::<_, io::Error>(buf)
+        };
+        let output = async {
+            let (status, stdout, stderr) =
+                futures_util::try_join!(child.wait(), read_stdout, read_stderr)?;
+            Ok::<_, io::Error>(Output { status, stdout, stderr })
+        };
+        let interrupt = async {
+            loop {
+                if let Some(interrupt) = Interrupt::check(timeout, token) {
+                    return interrupt;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        };
+        match future::select(Box::pin(output), Box::pin(interrupt)).await {
+            Either::Left((output, _)) => return output.map(Ok),
+            Either::Right((interrupt, _)) => interrupt,
+        }
+    };
+
+    debug!(interrupt = %interrupted, "killing compiler process");
+    // the process may have exited in the meantime
+    let _ = child.kill().await;
+    Ok(Err(interrupted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancellation_token_clones_share_state() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert_eq!(token, clone);
+        assert_ne!(token, CancellationToken::new());
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn can_kill_process_after_timeout() {
+        use std::process::{Command, Stdio};
+
+        let start = Instant::now();
+        let child = Command::new("sleep").arg("10").stdout(Stdio::piped()).spawn().unwrap();
+        let interrupt =
+            wait_with_output(child, InterruptOptions::new(Some(Duration::from_millis(100)), None))
+                .unwrap()
+                .unwrap_err();
+        assert_eq!(interrupt, Interrupt::Timeout(Duration::from_millis(100)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let child = Command::new("sleep").arg("10").spawn().unwrap();
+        let interrupt = wait_with_output(child, InterruptOptions::new(None, Some(&token)))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(interrupt, Interrupt::Cancelled);
+
+        let child = Command::new("echo").arg("solc").stdout(Stdio::piped()).spawn().unwrap();
+        let output =
+            wait_with_output(child, InterruptOptions::new(Some(Duration::from_secs(10)), None))
+                .unwrap()
+                .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"solc\n");
+    }
+}
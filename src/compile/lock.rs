@@ -0,0 +1,122 @@
+//! An advisory file lock that serializes builds of the same project across processes
+
+use crate::{
+    error::{Result, SolcError},
+    report, CancellationToken, Interrupt,
+};
+use fs2::FileExt;
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The name of the lock file in the cache dir
+pub const BUILD_LOCK_FILE: &str = ".build.lock";
+
+/// How often a lock that's held by another build is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// An exclusive lock on a project's build, it's released when dropped.
+///
+/// [`ProjectCompiler::compile()`](crate::compile::project::ProjectCompiler::compile) holds this lock while it reads
+/// the [`SolFilesCache`](crate::cache::SolFilesCache), compiles the dirty sources and writes the
+/// artifacts and the cache, so that concurrent builds of the same project, for example from an IDE
+/// and a terminal, don't overwrite each other's files.
+///
+/// The lock is advisory and only respected by other builds that take it as well. It's held by an
+/// open file handle, so it's also released if the process exits without dropping it.
+#[derive(Debug)]
+pub struct BuildLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl BuildLock {
+    /// Acquires the lock of the given lock file, which is created if it doesn't exist yet.
+    ///
+    /// If another build holds the lock, this reports
+    /// [`Reporter::on_build_lock_wait()`](crate::report::Reporter::on_build_lock_wait) and waits
+    /// until the lock is released, the `timeout` elapsed or the `token` was cancelled.
+    pub fn acquire(
+        path: impl Into<PathBuf>,
+        timeout: Option<Duration>,
+        token: Option<&CancellationToken>,
+    ) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| SolcError::io(err, parent))?;
+        }
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|err| SolcError::io(err, &path))?;
+
+        let start = Instant::now();
+        let mut reported = false;
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => break,
+                Err(err) if err.kind() == fs2::lock_contended_error().kind() => {}
+                Err(err) => return Err(SolcError::io(err, &path)),
+            }
+            if !reported {
+                trace!("waiting for build lock \"{}\"", path.display());
+                report::build_lock_wait(&path);
+                reported = true;
+            }
+            if token.map_or(false, CancellationToken::is_cancelled) {
+                return Err(SolcError::interrupted(Interrupt::Cancelled));
+            }
+            if let Some(timeout) = timeout {
+                if start.elapsed() >= timeout {
+                    return Err(SolcError::BuildLockTimeout { path, timeout });
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        trace!("acquired build lock \"{}\" after {:?}", path.display(), start.elapsed());
+
+        Ok(Self { file, path })
+    }
+
+    /// Returns the path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for BuildLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+        trace!("released build lock \"{}\"", self.path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_time_out_waiting_for_build_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join(BUILD_LOCK_FILE);
+        let lock = BuildLock::acquire(&path, None, None).unwrap();
+
+        let timeout = Duration::from_millis(50);
+        let err = BuildLock::acquire(&path, Some(timeout), None).unwrap_err();
+        assert!(matches!(err, SolcError::BuildLockTimeout { timeout: t, .. } if t == timeout));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let err = BuildLock::acquire(&path, None, Some(&token)).unwrap_err();
+        assert!(matches!(err, SolcError::Interrupted { reason: Interrupt::Cancelled, .. }));
+
+        drop(lock);
+        BuildLock::acquire(&path, Some(timeout), None).unwrap();
+    }
+}
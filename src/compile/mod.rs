@@ -1,7 +1,7 @@
 use crate::{
     artifacts::Source,
     error::{Result, SolcError},
-    utils, CompilerInput, CompilerOutput,
+    utils, CompilerInput, CompilerOutput, OutputFilter,
 };
 use once_cell::sync::Lazy;
 use semver::{Version, VersionReq};
@@ -13,6 +13,12 @@ use std::{
     str::FromStr,
 };
 
+pub mod interrupt;
+pub use interrupt::{CancellationToken, Interrupt, InterruptOptions};
+
+pub mod lock;
+pub use lock::BuildLock;
+
 pub mod many;
 
 pub mod output;
@@ -20,6 +26,14 @@ pub use output::{contracts, info, sources};
 
 pub mod project;
 
+pub mod remote_cache;
+#[cfg(feature = "remote-cache")]
+pub use remote_cache::HttpRemoteCache;
+pub use remote_cache::{FileSystemRemoteCache, RemoteCache};
+
+pub mod shared_cache;
+pub use shared_cache::SharedCache;
+
 /// The name of the `solc` binary on the system
 pub const SOLC: &str = "solc";
 
@@ -568,8 +582,71 @@ impl Solc {
     }
 
     /// Compiles with `--standard-json` and returns the raw `stdout` output.
-    #[instrument(name = "compile", level = "debug", skip_all)]
     pub fn compile_output<T: Serialize + std::fmt::Debug>(&self, input: &T) -> Result<Vec<u8>> {
+        self.compile_output_interruptible(input, InterruptOptions::default())
+    }
+
+    /// Compiles with `--standard-json` and returns the raw `stdout` output.
+    ///
+    /// `solc` is killed and [`SolcError::Interrupted`] is returned once the timeout of the
+    /// `interrupt` options elapses or its token is cancelled.
+    #[instrument(name = "compile", level = "debug", skip_all)]
+    pub fn compile_output_interruptible<T: Serialize + std::fmt::Debug>(
+        &self,
+        input: &T,
+        interrupt: InterruptOptions<'_>,
+    ) -> Result<Vec<u8>> {
+        let child = self.spawn_standard_json(input)?;
+
+        let output = interrupt::wait_with_output(child, interrupt)
+            .map_err(self.map_io_err())?
+            .map_err(SolcError::interrupted)?;
+        debug!(%output.status, output.stderr = ?String::from_utf8_lossy(&output.stderr), "finished");
+
+        compile_output(output)
+    }
+
+    /// Compiles with `--standard-json` and deserializes the [`CompilerOutput`] directly from
+    /// `solc`'s stdout, dropping everything the `filter` doesn't keep as it goes.
+    ///
+    /// Unlike [`Self::compile()`], this never holds the raw output in memory. `solc` is killed and
+    /// [`SolcError::Interrupted`] is returned once the timeout of the `interrupt` options elapses
+    /// or its token is cancelled.
+    #[instrument(name = "compile", level = "debug", skip_all)]
+    pub fn compile_streamed<T: Serialize + std::fmt::Debug>(
+        &self,
+        input: &T,
+        filter: &OutputFilter,
+        interrupt: InterruptOptions<'_>,
+    ) -> Result<CompilerOutput> {
+        let child = self.spawn_standard_json(input)?;
+
+        let filter = filter.clone();
+        let interrupt::ProcessedOutput { status, stdout: (output, stdout), stderr } =
+            interrupt::wait_with(child, interrupt, move |stdout| {
+                let mut stdout = CapturedPrefix::new(stdout);
+                let output = filter.deserialize_from(&mut stdout);
+                if output.is_err() {
+                    // keep reading, otherwise solc blocks once the pipe is full
+                    let _ = std::io::copy(&mut stdout, &mut std::io::sink());
+                }
+                (output, stdout.captured)
+            })
+            .map_err(self.map_io_err())?
+            .map_err(SolcError::interrupted)?;
+        debug!(%status, stderr = ?String::from_utf8_lossy(&stderr), "finished");
+
+        if !status.success() {
+            return Err(SolcError::solc_output(&Output { status, stdout, stderr }));
+        }
+        Ok(output?)
+    }
+
+    /// Spawns `solc --standard-json` and writes the `input` to its stdin
+    fn spawn_standard_json<T: Serialize + std::fmt::Debug>(
+        &self,
+        input: &T,
+    ) -> Result<std::process::Child> {
         let mut cmd = Command::new(&self.solc);
         if let Some(base_path) = &self.base_path {
             cmd.current_dir(base_path);
@@ -588,10 +665,7 @@ impl Solc {
         serde_json::to_writer(stdin, input)?;
         debug!("wrote JSON input to stdin");
 
-        let output = child.wait_with_output().map_err(self.map_io_err())?;
-        debug!(%output.status, output.stderr = ?String::from_utf8_lossy(&output.stderr), "finished");
-
-        compile_output(output)
+        Ok(child)
     }
 
     /// Invokes `solc --version` and parses the output as a SemVer [`Version`], stripping the
@@ -643,7 +717,20 @@ impl Solc {
         Ok(serde_json::from_slice(&output)?)
     }
 
+    /// Compiles with `--standard-json` and returns the raw `stdout` output.
     pub async fn async_compile_output<T: Serialize>(&self, input: &T) -> Result<Vec<u8>> {
+        self.async_compile_output_interruptible(input, InterruptOptions::default()).await
+    }
+
+    /// Compiles with `--standard-json` and returns the raw `stdout` output.
+    ///
+    /// `solc` is killed and [`SolcError::Interrupted`] is returned once the timeout of the
+    /// `interrupt` options elapses or its token is cancelled.
+    pub async fn async_compile_output_interruptible<T: Serialize>(
+        &self,
+        input: &T,
+        interrupt: InterruptOptions<'_>,
+    ) -> Result<Vec<u8>> {
         use tokio::io::AsyncWriteExt;
         let content = serde_json::to_vec(input)?;
         let mut cmd = tokio::process::Command::new(&self.solc);
@@ -661,7 +748,12 @@ impl Solc {
         let stdin = child.stdin.as_mut().unwrap();
         stdin.write_all(&content).await.map_err(self.map_io_err())?;
         stdin.flush().await.map_err(self.map_io_err())?;
-        compile_output(child.wait_with_output().await.map_err(self.map_io_err())?)
+
+        let output = interrupt::async_wait_with_output(child, interrupt)
+            .await
+            .map_err(self.map_io_err())?
+            .map_err(SolcError::interrupted)?;
+        compile_output(output)
     }
 
     pub async fn async_version(&self) -> Result<Version> {
@@ -715,6 +807,32 @@ impl Solc {
     }
 }
 
+/// The maximum number of bytes of `solc`'s stdout that [`Solc::compile_streamed()`] keeps for the
+/// error of a failed run, which is usually a short message
+const MAX_CAPTURED_STDOUT: usize = 64 * 1024;
+
+/// A reader that keeps the first [`MAX_CAPTURED_STDOUT`] bytes that were read from the inner
+/// reader
+struct CapturedPrefix<R> {
+    inner: R,
+    captured: Vec<u8>,
+}
+
+impl<R: std::io::Read> CapturedPrefix<R> {
+    fn new(inner: R) -> Self {
+        Self { inner, captured: Vec::new() }
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for CapturedPrefix<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        let keep = read.min(MAX_CAPTURED_STDOUT - self.captured.len());
+        self.captured.extend_from_slice(&buf[..keep]);
+        Ok(read)
+    }
+}
+
 fn compile_output(output: Output) -> Result<Vec<u8>> {
     if output.status.success() {
         Ok(output.stdout)
@@ -762,6 +880,52 @@ mod tests {
         assert_eq!(req, semver_req);
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn can_compile_streamed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let out = Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/out/compiler-out-10.json");
+        let tmp = tempfile::tempdir().unwrap();
+        let stub = tmp.path().join("solc");
+        std::fs::write(&stub, format!("#!/bin/sh\ncat > /dev/null\ncat {}\n", out.display()))
+            .unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let solc = Solc::new(stub);
+        let sources = [("contracts/Greeter.sol".into(), Source::new(""))].into();
+        let input = CompilerInput::with_sources(sources).pop().unwrap();
+
+        let expected = solc.compile(&input).unwrap();
+        let output =
+            solc.compile_streamed(&input, &OutputFilter::default(), Default::default()).unwrap();
+        assert_eq!(output, expected);
+
+        let filter = OutputFilter::default().keep_asts_of(["contracts/Greeter.sol"]);
+        let output = solc.compile_streamed(&input, &filter, Default::default()).unwrap();
+        assert!(output.sources["contracts/Greeter.sol"].ast.is_some());
+        assert!(output.sources["hardhat/console.sol"].ast.is_none());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn keeps_stdout_of_failed_streamed_compile() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let stub = tmp.path().join("solc");
+        std::fs::write(&stub, "#!/bin/sh\ncat > /dev/null\necho 'invalid option'\nexit 1\n")
+            .unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let solc = Solc::new(stub);
+        let sources = [("contracts/Greeter.sol".into(), Source::new(""))].into();
+        let input = CompilerInput::with_sources(sources).pop().unwrap();
+
+        let err = solc
+            .compile_streamed(&input, &OutputFilter::default(), Default::default())
+            .unwrap_err();
+        assert!(matches!(&err, SolcError::SolcError(_, msg) if msg == "invalid option"), "{err}");
+    }
+
     fn solc() -> Solc {
         Solc::default()
     }
@@ -0,0 +1,309 @@
+//! Filters the compiler output while it's deserialized
+
+use crate::{
+    artifacts::{Contract, Contracts, Error, Settings, SourceFile},
+    CompilerOutput,
+};
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    io::{self, Read},
+    path::Path,
+};
+
+/// Determines which parts of the compiler output are kept.
+///
+/// The output of a single `solc` job can be several hundred MB, most of it ASTs and generated
+/// sources. With [`Solc::compile_streamed()`](crate::Solc::compile_streamed) the output is read
+/// directly from `solc`'s stdout and everything that's not kept is skipped without allocating it.
+///
+/// The default filter keeps everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct OutputFilter {
+    /// The source files whose ASTs are kept, all ASTs are kept if `None`
+    asts: Option<BTreeSet<String>>,
+    /// Whether generated sources are dropped
+    skip_generated_sources: bool,
+}
+
+impl OutputFilter {
+    /// Only keeps the ASTs of the given source files, as they're named in the compiler input.
+    ///
+    /// The [`SourceFile`] entries of all other files are still kept, but without their AST.
+    #[must_use]
+    pub fn keep_asts_of<I, S>(mut self, files: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<Path>,
+    {
+        self.asts = Some(files.into_iter().map(|f| f.as_ref().display().to_string()).collect());
+        self
+    }
+
+    /// Drops the generated sources of all bytecode objects
+    #[must_use]
+    pub fn skip_generated_sources(mut self) -> Self {
+        self.skip_generated_sources = true;
+        self
+    }
+
+    /// Returns the filter for a compiler job of a project: only the ASTs of the job's dirty files
+    /// are kept, because artifacts are only emitted for these, and generated sources are only
+    /// kept if they're explicitly selected in the `settings`, for example if the artifacts include
+    /// them. A broader selection like `evm.bytecode` includes them implicitly.
+    pub(crate) fn for_job<I, S>(settings: &Settings, dirty_files: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<Path>,
+    {
+        let generated_sources_selected = settings
+            .output_selection
+            .as_ref()
+            .values()
+            .flat_map(|file| file.values())
+            .flatten()
+            .any(|output| output == "*" || output.ends_with("generatedSources"));
+        let filter = Self::default().keep_asts_of(dirty_files);
+        if generated_sources_selected {
+            filter
+        } else {
+            filter.skip_generated_sources()
+        }
+    }
+
+    /// Whether the AST of the given source file is kept
+    pub fn keeps_ast(&self, file: &str) -> bool {
+        self.asts.as_ref().map_or(true, |asts| asts.contains(file))
+    }
+
+    /// Whether the filter keeps the entire output
+    pub fn keeps_all(&self) -> bool {
+        self.asts.is_none() && !self.skip_generated_sources
+    }
+
+    /// Removes everything the filter doesn't keep from an already deserialized output
+    pub fn apply(&self, output: &mut CompilerOutput) {
+        if self.keeps_all() {
+            return;
+        }
+        for (file, source) in output.sources.iter_mut() {
+            if !self.keeps_ast(file) {
+                source.ast = None;
+            }
+        }
+        for contract in output.contracts.values_mut().flat_map(|c| c.values_mut()) {
+            self.apply_contract(contract);
+        }
+    }
+
+    fn apply_contract(&self, contract: &mut Contract) {
+        if !self.skip_generated_sources {
+            return;
+        }
+        if let Some(evm) = contract.evm.as_mut() {
+            if let Some(bytecode) = evm.bytecode.as_mut() {
+                bytecode.generated_sources = Vec::new();
+            }
+            if let Some(bytecode) =
+                evm.deployed_bytecode.as_mut().and_then(|code| code.bytecode.as_mut())
+            {
+                bytecode.generated_sources = Vec::new();
+            }
+        }
+    }
+
+    /// Deserializes the output from the `reader` and drops everything the filter doesn't keep as
+    /// it goes.
+    pub fn deserialize_from(&self, reader: impl Read) -> serde_json::Result<CompilerOutput> {
+        let mut de = serde_json::Deserializer::from_reader(io::BufReader::new(reader));
+        let output = DeserializeSeed::deserialize(self, &mut de)?;
+        de.end()?;
+        Ok(output)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for &OutputFilter {
+    type Value = CompilerOutput;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(OutputVisitor(self))
+    }
+}
+
+struct OutputVisitor<'a>(&'a OutputFilter);
+
+impl<'de> Visitor<'de> for OutputVisitor<'_> {
+    type Value = CompilerOutput;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("compiler output")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut output = CompilerOutput::default();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "errors" => output.errors = map.next_value::<Vec<Error>>()?,
+                "sources" => output.sources = map.next_value_seed(SourcesSeed(self.0))?,
+                "contracts" => output.contracts = map.next_value_seed(ContractsSeed(self.0))?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Deserializes `sources`, skipping the ASTs that aren't kept
+struct SourcesSeed<'a>(&'a OutputFilter);
+
+impl<'de> DeserializeSeed<'de> for SourcesSeed<'_> {
+    type Value = BTreeMap<String, SourceFile>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SourcesSeed<'_> {
+    type Value = BTreeMap<String, SourceFile>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of source files")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        /// A [`SourceFile`] without its AST, which is skipped
+        #[derive(Deserialize)]
+        struct SourceFileId {
+            id: u32,
+        }
+
+        let mut sources = BTreeMap::new();
+        while let Some(file) = map.next_key::<String>()? {
+            let source = if self.0.keeps_ast(&file) {
+                map.next_value::<SourceFile>()?
+            } else {
+                SourceFile { id: map.next_value::<SourceFileId>()?.id, ast: None }
+            };
+            sources.insert(file, source);
+        }
+        Ok(sources)
+    }
+}
+
+/// Deserializes `contracts`, every contract is filtered right after it's deserialized
+struct ContractsSeed<'a>(&'a OutputFilter);
+
+impl<'de> DeserializeSeed<'de> for ContractsSeed<'_> {
+    type Value = Contracts;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ContractsSeed<'_> {
+    type Value = Contracts;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of contracts by file")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut contracts = Contracts::new();
+        while let Some(file) = map.next_key::<String>()? {
+            let file_contracts = map.next_value_seed(FileContractsSeed(self.0))?;
+            contracts.insert(file, file_contracts);
+        }
+        Ok(contracts)
+    }
+}
+
+struct FileContractsSeed<'a>(&'a OutputFilter);
+
+impl<'de> DeserializeSeed<'de> for FileContractsSeed<'_> {
+    type Value = BTreeMap<String, Contract>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FileContractsSeed<'_> {
+    type Value = BTreeMap<String, Contract>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of contracts by name")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut contracts = BTreeMap::new();
+        while let Some(name) = map.next_key::<String>()? {
+            // only the ABI of the contract is buffered, see `Contract::abi`
+            let mut contract = map.next_value::<Contract>()?;
+            self.0.apply_contract(&mut contract);
+            contracts.insert(name, contract);
+        }
+        Ok(contracts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs::File, path::PathBuf};
+
+    fn output_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/out/compiler-out-10.json")
+    }
+
+    fn output_file() -> File {
+        File::open(output_path()).unwrap()
+    }
+
+    fn read_output() -> CompilerOutput {
+        serde_json::from_str(&std::fs::read_to_string(output_path()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn can_stream_complete_output() {
+        let expected = read_output();
+        let output = OutputFilter::default().deserialize_from(output_file()).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn can_filter_streamed_output() {
+        let mut expected = read_output();
+        let filter = OutputFilter::default()
+            .keep_asts_of(["contracts/Greeter.sol"])
+            .skip_generated_sources();
+        let output = filter.deserialize_from(output_file()).unwrap();
+
+        assert!(output.sources["contracts/Greeter.sol"].ast.is_some());
+        assert!(output.sources["hardhat/console.sol"].ast.is_none());
+        assert_eq!(
+            output.sources["hardhat/console.sol"].id,
+            expected.sources["hardhat/console.sol"].id
+        );
+        let greeter = &output.contracts["contracts/Greeter.sol"]["Greeter"];
+        assert!(greeter
+            .evm
+            .as_ref()
+            .unwrap()
+            .bytecode
+            .as_ref()
+            .unwrap()
+            .generated_sources
+            .is_empty());
+
+        filter.apply(&mut expected);
+        assert_eq!(output, expected);
+    }
+}
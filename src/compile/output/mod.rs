@@ -6,17 +6,25 @@ use crate::{
         Error, Severity,
     },
     buildinfo::RawBuildInfo,
+    cache::DirtyReason,
     info::ContractInfoRef,
+    resolver::DuplicateContract,
     sources::{VersionedSourceFile, VersionedSourceFiles},
-    ArtifactId, ArtifactOutput, Artifacts, CompilerOutput, ConfigurableArtifacts, SolcIoError,
+    utils, ArtifactId, ArtifactOutput, Artifacts, CompilerOutput, ConfigurableArtifacts,
+    SolcIoError,
 };
 use contracts::{VersionedContract, VersionedContracts};
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt, path::Path};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
 use yansi::Paint;
 
 pub mod contracts;
+pub mod filter;
 pub mod info;
 pub mod sources;
 
@@ -34,6 +42,8 @@ pub struct ProjectCompileOutput<T: ArtifactOutput = ConfigurableArtifacts> {
     pub(crate) ignored_error_codes: Vec<u64>,
     /// set minimum level of severity that is treated as an error
     pub(crate) compiler_severity_filter: Severity,
+    /// why the compiled files weren't reused from the cache
+    pub(crate) dirty_reasons: BTreeMap<PathBuf, DirtyReason>,
 }
 
 impl<T: ArtifactOutput> ProjectCompileOutput<T> {
@@ -348,6 +358,9 @@ impl<T: ArtifactOutput> ProjectCompileOutput<T> {
     }
 
     /// Finds the first contract with the given name
+    ///
+    /// The contract is ambiguous if multiple files declare a contract with this name, see
+    /// [`Self::duplicate_contracts()`]
     pub fn find_first(&self, contract_name: impl AsRef<str>) -> Option<&T::Artifact> {
         let contract_name = contract_name.as_ref();
         if let artifact @ Some(_) = self.compiled_artifacts.find_first(contract_name) {
@@ -356,6 +369,19 @@ impl<T: ArtifactOutput> ProjectCompileOutput<T> {
         self.cached_artifacts.find_first(contract_name)
     }
 
+    /// Returns why the files were compiled instead of reusing their cached artifacts, by file.
+    ///
+    /// This is empty if caching is disabled.
+    pub fn dirty_reasons(&self) -> &BTreeMap<PathBuf, DirtyReason> {
+        &self.dirty_reasons
+    }
+
+    /// Returns all contract names with artifacts of more than one file, both cached and
+    /// recompiled
+    pub fn duplicate_contracts(&self) -> Vec<DuplicateContract> {
+        DuplicateContract::find_all(self.artifact_ids().map(|(id, _)| (id.name, id.source)))
+    }
+
     /// Finds the artifact with matching path and name
     ///
     /// # Examples
@@ -563,6 +589,26 @@ impl AggregatedCompilerOutput {
         }
     }
 
+    /// Merges the output of a separate compile pass, like the one for the vyper sources of a
+    /// project, into this output
+    pub fn merge(&mut self, other: AggregatedCompilerOutput) {
+        let AggregatedCompilerOutput { errors, sources, contracts, build_infos } = other;
+        self.errors.extend(errors);
+
+        for (path, source_files) in sources.0 {
+            self.sources.as_mut().entry(path).or_default().extend(source_files);
+        }
+
+        for (file_name, new_contracts) in contracts.0 {
+            let contracts = self.contracts.as_mut().entry(file_name).or_default();
+            for (contract_name, versioned) in new_contracts {
+                contracts.entry(contract_name).or_default().extend(versioned);
+            }
+        }
+
+        self.build_infos.extend(build_infos);
+    }
+
     /// Creates all `BuildInfo` files in the given `build_info_dir`
     ///
     /// There can be multiple `BuildInfo`, since we support multiple versions.
@@ -580,8 +626,7 @@ impl AggregatedCompilerOutput {
             trace!("writing build info file for solc {}", version);
             let file_name = format!("{}.json", build_info.id);
             let file = build_info_dir.join(file_name);
-            std::fs::write(&file, &build_info.build_info)
-                .map_err(|err| SolcIoError::new(err, file))?;
+            utils::write_atomic(&file, &build_info.build_info)?;
         }
         Ok(())
     }
@@ -105,34 +105,46 @@ use crate::{
     artifacts::{Settings, VersionedFilteredSources, VersionedSources},
     buildinfo::RawBuildInfo,
     cache::ArtifactsCache,
-    error::Result,
-    filter::SparseOutputFilter,
+    compilers::{self, vyper::Vyper, Compiler, CompilerInput},
+    config::CompilerProfiles,
+    error::{Result, SolcError},
+    filter::{FilteredSource, SparseOutputFilter},
     output::AggregatedCompilerOutput,
     report,
     resolver::GraphEdges,
-    ArtifactOutput, CompilerInput, Graph, Project, ProjectCompileOutput, ProjectPathsConfig, Solc,
-    Sources,
+    utils, ArtifactOutput, CompilerOutput, Graph, Interrupt, InterruptOptions, OutputFilter,
+    Project, ProjectCompileOutput, ProjectPathsConfig, RemoteCache, SharedCache, Solc, Sources,
 };
 use rayon::prelude::*;
-use std::{collections::btree_map::BTreeMap, path::PathBuf, time::Instant};
+use semver::Version;
+use std::{
+    borrow::Cow,
+    collections::{btree_map::BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 #[derive(Debug)]
-pub struct ProjectCompiler<'a, T: ArtifactOutput> {
+pub struct ProjectCompiler<'a, T: ArtifactOutput, C: Compiler = Solc> {
     /// Contains the relationship of the source files and their imports
     edges: GraphEdges,
-    project: &'a Project<T>,
+    project: &'a Project<T, C>,
     /// how to compile all the sources
-    sources: CompilerSources,
+    sources: CompilerSources<C>,
+    /// The vyper sources, if they're not compiled by the project's compiler
+    vyper_sources: Option<CompilerSources<Vyper>>,
     /// How to select solc [`crate::artifacts::CompilerOutput`] for files
     sparse_output: SparseOutputFilter,
+    /// The settings of all files
+    profiles: CompilerProfiles,
 }
 
-impl<'a, T: ArtifactOutput> ProjectCompiler<'a, T> {
+impl<'a, T: ArtifactOutput, C: Compiler> ProjectCompiler<'a, T, C> {
     /// Create a new `ProjectCompiler` to bootstrap the compilation process of the project's
     /// sources.
     #[cfg(feature = "svm-solc")]
-    pub fn new(project: &'a Project<T>) -> Result<Self> {
-        Self::with_sources(project, project.paths.read_input_files()?)
+    pub fn new(project: &'a Project<T, C>) -> Result<Self> {
+        Self::with_sources(project, project.overlay.read_input_files(&project.paths)?)
     }
 
     /// Bootstraps the compilation process by resolving the dependency graph of all sources and the
@@ -142,9 +154,18 @@ impl<'a, T: ArtifactOutput> ProjectCompiler<'a, T> {
     /// Multiple (`Solc` -> `Sources`) pairs can be compiled in parallel if the `Project` allows
     /// multiple `jobs`, see [`crate::Project::set_solc_jobs()`].
     #[cfg(feature = "svm-solc")]
-    pub fn with_sources(project: &'a Project<T>, sources: Sources) -> Result<Self> {
-        let graph = Graph::resolve_sources(&project.paths, sources)?;
-        let (versions, edges) = graph.into_sources_by_version(project.offline)?;
+    pub fn with_sources(project: &'a Project<T, C>, sources: Sources) -> Result<Self> {
+        let profiles = project.compiler_profiles()?;
+        let graph = Graph::resolve_sources_with_overlay(&project.paths, sources, &project.overlay)?;
+        check_import_cycles(project, &graph)?;
+        check_duplicate_contracts(project, &graph)?;
+        let vyper_sources = Self::vyper_sources(project, &graph)?;
+        let (versions, edges) = graph.into_sources_by_version_with_overrides(
+            &project.compiler,
+            project.offline,
+            &project.version_selection,
+            &project.compiler_overrides,
+        )?;
 
         let sources_by_version = versions.get(project)?;
 
@@ -156,29 +177,93 @@ impl<'a, T: ArtifactOutput> ProjectCompiler<'a, T> {
             CompilerSources::Sequential(sources_by_version)
         };
 
-        Ok(Self { edges, project, sources, sparse_output: Default::default() })
+        Ok(Self {
+            edges,
+            project,
+            sources,
+            vyper_sources,
+            sparse_output: Default::default(),
+            profiles,
+        })
     }
 
-    /// Compiles the sources with a pinned `Solc` instance
-    pub fn with_sources_and_solc(
-        project: &'a Project<T>,
+    /// Compiles the sources with a pinned [`Compiler`] instance.
+    ///
+    /// The version requirements of the project's [`crate::CompilerOverride`]s are ignored, but
+    /// their settings still apply.
+    pub fn with_sources_and_compiler(
+        project: &'a Project<T, C>,
         sources: Sources,
-        solc: Solc,
+        compiler: C,
     ) -> Result<Self> {
-        let version = solc.version()?;
-        let (sources, edges) = Graph::resolve_sources(&project.paths, sources)?.into_sources();
+        let version = compiler.version()?;
+        let profiles = project.compiler_profiles()?;
+        let graph = Graph::resolve_sources_with_overlay(&project.paths, sources, &project.overlay)?;
+        check_import_cycles(project, &graph)?;
+        check_duplicate_contracts(project, &graph)?;
+        let vyper_sources = Self::vyper_sources(project, &graph)?;
+        let (sources_by_profile, edges) =
+            graph.into_sources_by_override(&project.compiler_overrides);
+
+        // make sure the compiler has all required arguments
+        let compiler = compiler.configure(project, &version, edges.include_paths().clone());
+
+        let sources_by_version = sources_by_profile
+            .into_iter()
+            .map(|(profile, mut sources)| {
+                if vyper_sources.is_some() {
+                    sources.retain(|path, _| compilers::is_compiler_source::<C>(path));
+                }
+                ((compiler.clone(), profile), (version.clone(), sources))
+            })
+            .collect();
+        let sources = CompilerSources::Sequential(sources_by_version);
 
-        // make sure `solc` has all required arguments
-        let solc = project.configure_solc_with_version(
-            solc,
-            Some(version.clone()),
-            edges.include_paths().clone(),
-        );
+        Ok(Self {
+            edges,
+            project,
+            sources,
+            vyper_sources,
+            sparse_output: Default::default(),
+            profiles,
+        })
+    }
 
-        let sources_by_version = BTreeMap::from([(solc, (version, sources))]);
-        let sources = CompilerSources::Sequential(sources_by_version);
+    /// Returns the vyper sources of the graph if the project's compiler can't compile them.
+    ///
+    /// These are compiled in a separate pass with the project's [`Vyper`], but share the cache and
+    /// the artifacts with the other sources. Sources whose `# pragma version` doesn't match the
+    /// version of the [`Vyper`] are rejected.
+    fn vyper_sources(
+        project: &Project<T, C>,
+        graph: &Graph,
+    ) -> Result<Option<CompilerSources<Vyper>>> {
+        if C::FILE_EXTENSIONS.contains(&"vy") {
+            return Ok(None);
+        }
+        let sources = graph.vyper_sources();
+        if sources.is_empty() {
+            return Ok(None);
+        }
+
+        let vyper = project.vyper.clone().unwrap_or_default();
+        let version = vyper.version()?;
+        let incompatible = sources
+            .iter()
+            .filter_map(|(file, source)| {
+                let req = Vyper::source_version_req(source).ok()?;
+                let f = utils::source_name(file, project.root()).display();
+                (!req.matches(&version))
+                    .then(|| format!("{f} requires vyper \"{req}\", found \"{version}\""))
+            })
+            .collect::<Vec<_>>();
+        if !incompatible.is_empty() {
+            bail!("Found incompatible vyper versions:\n{}", incompatible.join("\n"));
+        }
+        let vyper = vyper.with_base_path(project.root());
+        trace!(%version, "compiling {} vyper sources with {}", sources.len(), vyper);
 
-        Ok(Self { edges, project, sources, sparse_output: Default::default() })
+        Ok(Some(CompilerSources::Sequential(BTreeMap::from([((vyper, None), (version, sources))]))))
     }
 
     /// Applies the specified filter to be applied when selecting solc output for
@@ -195,6 +280,11 @@ impl<'a, T: ArtifactOutput> ProjectCompiler<'a, T> {
     /// The output of the compile process can be a mix of reused artifacts and freshly compiled
     /// `Contract`s
     ///
+    /// If the cache and the artifacts are written, this holds the project's
+    /// [`BuildLock`](crate::BuildLock) for the entire compilation and waits for other builds of
+    /// the project to release it first. If the cache dir doesn't exist yet, there's no cache to
+    /// read and the lock is only taken once the output is written.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -207,8 +297,20 @@ impl<'a, T: ArtifactOutput> ProjectCompiler<'a, T> {
     pub fn compile(self) -> Result<ProjectCompileOutput<T>> {
         let slash_paths = self.project.slash_paths;
 
+        // serialize builds that write the same cache and artifacts
+        let project = self.project;
+        let writes_cache = project.cached && !project.no_artifacts;
+        let has_cache_dir = project.build_lock_path().parent().map_or(false, Path::exists);
+        let mut lock =
+            if writes_cache && has_cache_dir { Some(project.acquire_build_lock()?) } else { None };
+
         // drive the compiler statemachine to completion
-        let mut output = self.preprocess()?.compile()?.write_artifacts()?.write_cache()?;
+        let compiled = self.preprocess()?.compile()?;
+        if writes_cache && lock.is_none() && !compiled.has_error() {
+            lock = Some(project.acquire_build_lock()?);
+        }
+        let mut output = compiled.write_artifacts()?.write_cache()?;
+        drop(lock);
 
         if slash_paths {
             // ensures we always use `/` paths
@@ -221,19 +323,28 @@ impl<'a, T: ArtifactOutput> ProjectCompiler<'a, T> {
     /// Does basic preprocessing
     ///   - sets proper source unit names
     ///   - check cache
-    fn preprocess(self) -> Result<PreprocessedState<'a, T>> {
+    fn preprocess(self) -> Result<PreprocessedState<'a, T, C>> {
         trace!("preprocessing");
-        let Self { edges, project, mut sources, sparse_output } = self;
+        let Self { edges, project, mut sources, mut vyper_sources, sparse_output, profiles } = self;
 
         // convert paths on windows to ensure consistency with the `CompilerOutput` `solc` emits,
         // which is unix style `/`
         sources.slash_paths();
+        if let Some(vyper_sources) = &mut vyper_sources {
+            vyper_sources.slash_paths();
+        }
 
-        let mut cache = ArtifactsCache::new(project, edges)?;
+        let mut cache = ArtifactsCache::new(project, edges, profiles)?;
         // retain and compile only dirty sources and all their imports
         let sources = sources.filtered(&mut cache);
+        let vyper_sources = vyper_sources.map(|sources| sources.filtered(&mut cache));
+
+        let dirty_reasons = cache.dirty_reasons();
+        if !dirty_reasons.is_empty() {
+            report::dirty_files(&dirty_reasons);
+        }
 
-        Ok(PreprocessedState { sources, cache, sparse_output })
+        Ok(PreprocessedState { sources, vyper_sources, cache, sparse_output })
     }
 }
 
@@ -241,30 +352,58 @@ impl<'a, T: ArtifactOutput> ProjectCompiler<'a, T> {
 ///
 /// The main reason is to debug all states individually
 #[derive(Debug)]
-struct PreprocessedState<'a, T: ArtifactOutput> {
+struct PreprocessedState<'a, T: ArtifactOutput, C: Compiler> {
     /// Contains all the sources to compile.
-    sources: FilteredCompilerSources,
+    sources: FilteredCompilerSources<C>,
+
+    /// The vyper sources to compile, if they're not compiled by the project's compiler
+    vyper_sources: Option<FilteredCompilerSources<Vyper>>,
 
     /// Cache that holds `CacheEntry` objects if caching is enabled and the project is recompiled
-    cache: ArtifactsCache<'a, T>,
+    cache: ArtifactsCache<'a, T, C>,
 
     sparse_output: SparseOutputFilter,
 }
 
-impl<'a, T: ArtifactOutput> PreprocessedState<'a, T> {
+impl<'a, T: ArtifactOutput, C: Compiler> PreprocessedState<'a, T, C> {
     /// advance to the next state by compiling all sources
-    fn compile(self) -> Result<CompiledState<'a, T>> {
+    fn compile(self) -> Result<CompiledState<'a, T, C>> {
         trace!("compiling");
-        let PreprocessedState { sources, cache, sparse_output } = self;
+        let PreprocessedState { sources, vyper_sources, cache, sparse_output } = self;
         let project = cache.project();
+
+        // imports fetched by custom resolvers are compiled from the files they're cached in
+        let import_remappings = cache.graph().import_remappings();
+        let paths = if import_remappings.is_empty() {
+            Cow::Borrowed(&project.paths)
+        } else {
+            let mut paths = project.paths.clone();
+            paths.remappings.extend(import_remappings.iter().cloned());
+            Cow::Owned(paths)
+        };
+
         let mut output = sources.compile(
-            &project.solc_config.settings,
-            &project.paths,
-            sparse_output,
+            cache.profiles(),
+            &paths,
+            &sparse_output,
             cache.graph(),
             project.build_info,
+            project.interrupt_options(),
+            OutputCaches::new(project),
         )?;
 
+        if let Some(vyper_sources) = vyper_sources {
+            output.merge(vyper_sources.compile(
+                cache.profiles(),
+                &paths,
+                &sparse_output,
+                cache.graph(),
+                project.build_info,
+                project.interrupt_options(),
+                OutputCaches::new(project),
+            )?);
+        }
+
         // source paths get stripped before handing them over to solc, so solc never uses absolute
         // paths, instead `--base-path <root dir>` is set. this way any metadata that's derived from
         // data (paths) is relative to the project dir and should be independent of the current OS
@@ -278,22 +417,32 @@ impl<'a, T: ArtifactOutput> PreprocessedState<'a, T> {
 
 /// Represents the state after `solc` was successfully invoked
 #[derive(Debug)]
-struct CompiledState<'a, T: ArtifactOutput> {
+struct CompiledState<'a, T: ArtifactOutput, C: Compiler> {
     output: AggregatedCompilerOutput,
-    cache: ArtifactsCache<'a, T>,
+    cache: ArtifactsCache<'a, T, C>,
 }
 
-impl<'a, T: ArtifactOutput> CompiledState<'a, T> {
+impl<'a, T: ArtifactOutput, C: Compiler> CompiledState<'a, T, C> {
+    /// Returns `true` if the output has errors, in which case nothing is written to disk
+    fn has_error(&self) -> bool {
+        let project = self.cache.project();
+        self.output.has_error(&project.ignored_error_codes, &project.compiler_severity_filter)
+    }
+
     /// advance to the next state by handling all artifacts
     ///
     /// Writes all output contracts to disk if enabled in the `Project` and if the build was
     /// successful
+    ///
+    /// The output of overlaid files and of the files that import them, as well as the build infos
+    /// of the jobs that compiled them, are only kept in memory.
     #[instrument(skip_all, name = "write-artifacts")]
-    fn write_artifacts(self) -> Result<ArtifactsState<'a, T>> {
-        let CompiledState { output, cache } = self;
+    fn write_artifacts(self) -> Result<ArtifactsState<'a, T, C>> {
+        let CompiledState { mut output, cache } = self;
 
         let project = cache.project();
         let ctx = cache.output_ctx();
+        let mut overlaid = OverlaidOutput::default();
         // write all artifacts via the handler but only if the build succeeded and project wasn't
         // configured with `no_artifacts == true`
         let compiled_artifacts = if project.no_artifacts {
@@ -313,6 +462,17 @@ impl<'a, T: ArtifactOutput> CompiledState<'a, T> {
                 &project.paths,
             )
         } else {
+            let overlaid_output = overlaid.split_off(&mut output, &cache.overlaid_files());
+            if !overlaid_output.is_empty() {
+                trace!("keeping the output of {} overlaid files", overlaid_output.sources.len());
+                overlaid.artifacts = project.artifacts_handler().output_to_artifacts(
+                    &overlaid_output.contracts,
+                    &overlaid_output.sources,
+                    Default::default(),
+                    &project.paths,
+                );
+            }
+
             trace!(
                 "handling artifact output for {} contracts and {} sources",
                 output.contracts.len(),
@@ -329,27 +489,82 @@ impl<'a, T: ArtifactOutput> CompiledState<'a, T> {
             // emits all the build infos, if they exist
             output.write_build_infos(project.build_info_path())?;
 
+            output.merge(overlaid_output);
             artifacts
         };
 
-        Ok(ArtifactsState { output, cache, compiled_artifacts })
+        Ok(ArtifactsState { output, cache, compiled_artifacts, overlaid })
+    }
+}
+
+/// The output of overlaid files that isn't written to disk, see [`ArtifactsCache::overlaid_files()`]
+#[derive(Debug)]
+struct OverlaidOutput<A> {
+    /// The artifacts of the overlaid files
+    artifacts: Artifacts<A>,
+    /// The build infos of the jobs that compiled overlaid files
+    build_infos: BTreeMap<Version, RawBuildInfo>,
+}
+
+impl<A> Default for OverlaidOutput<A> {
+    fn default() -> Self {
+        Self { artifacts: Default::default(), build_infos: Default::default() }
+    }
+}
+
+impl<A> OverlaidOutput<A> {
+    /// Moves the contracts and sources of the overlaid `files` out of the `output` and keeps the
+    /// build infos of the jobs that compiled them.
+    fn split_off(
+        &mut self,
+        output: &mut AggregatedCompilerOutput,
+        files: &HashSet<PathBuf>,
+    ) -> AggregatedCompilerOutput {
+        let mut overlaid = AggregatedCompilerOutput::default();
+        if files.is_empty() {
+            return overlaid;
+        }
+        let is_overlaid = |file: &String| files.contains(Path::new(file));
+        let (sources, retained) =
+            std::mem::take(&mut output.sources.0).into_iter().partition(|(f, _)| is_overlaid(f));
+        overlaid.sources.0 = sources;
+        output.sources.0 = retained;
+        let (contracts, retained) =
+            std::mem::take(&mut output.contracts.0).into_iter().partition(|(f, _)| is_overlaid(f));
+        overlaid.contracts.0 = contracts;
+        output.contracts.0 = retained;
+
+        let versions = overlaid
+            .sources
+            .0
+            .values()
+            .flatten()
+            .map(|source| source.version.clone())
+            .collect::<HashSet<_>>();
+        let (build_infos, retained) = std::mem::take(&mut output.build_infos)
+            .into_iter()
+            .partition(|(version, _)| versions.contains(version));
+        self.build_infos = build_infos;
+        output.build_infos = retained;
+        overlaid
     }
 }
 
 /// Represents the state after all artifacts were written to disk
 #[derive(Debug)]
-struct ArtifactsState<'a, T: ArtifactOutput> {
+struct ArtifactsState<'a, T: ArtifactOutput, C: Compiler> {
     output: AggregatedCompilerOutput,
-    cache: ArtifactsCache<'a, T>,
+    cache: ArtifactsCache<'a, T, C>,
     compiled_artifacts: Artifacts<T::Artifact>,
+    overlaid: OverlaidOutput<T::Artifact>,
 }
 
-impl<'a, T: ArtifactOutput> ArtifactsState<'a, T> {
+impl<'a, T: ArtifactOutput, C: Compiler> ArtifactsState<'a, T, C> {
     /// Writes the cache file
     ///
     /// this concludes the [`Project::compile()`] statemachine
     fn write_cache(self) -> Result<ProjectCompileOutput<T>> {
-        let ArtifactsState { output, cache, compiled_artifacts } = self;
+        let ArtifactsState { mut output, cache, mut compiled_artifacts, overlaid } = self;
         let project = cache.project();
         let ignored_error_codes = project.ignored_error_codes.clone();
         let compiler_severity_filter = project.compiler_severity_filter;
@@ -357,28 +572,41 @@ impl<'a, T: ArtifactOutput> ArtifactsState<'a, T> {
         let skip_write_to_disk = project.no_artifacts || has_error;
         trace!(has_error, project.no_artifacts, skip_write_to_disk, cache_path=?project.cache_path(),"prepare writing cache file");
 
-        let cached_artifacts = cache.consume(&compiled_artifacts, !skip_write_to_disk)?;
+        let dirty_reasons = cache.dirty_reasons();
+        // the cache file is written last, it only references artifacts that were fully written
+        let cached_artifacts =
+            cache.consume(&compiled_artifacts, &output.build_infos, !skip_write_to_disk)?;
+        compiled_artifacts.0.extend(overlaid.artifacts.0);
+        output.build_infos.extend(overlaid.build_infos);
+
+        if project.auto_gc && project.cached && !skip_write_to_disk {
+            // the build lock is already held
+            let removed = project.gc_locked()?;
+            trace!("removed {} orphaned files", removed.files().count());
+        }
+
         Ok(ProjectCompileOutput {
             compiler_output: output,
             compiled_artifacts,
             cached_artifacts,
             ignored_error_codes,
             compiler_severity_filter,
+            dirty_reasons,
         })
     }
 }
 
 /// Determines how the `solc <-> sources` pairs are executed
 #[derive(Debug, Clone)]
-enum CompilerSources {
+enum CompilerSources<C: Compiler> {
     /// Compile all these sequentially
-    Sequential(VersionedSources),
+    Sequential(VersionedSources<C>),
     /// Compile all these in parallel using a certain amount of jobs
     #[allow(dead_code)]
-    Parallel(VersionedSources, usize),
+    Parallel(VersionedSources<C>, usize),
 }
 
-impl CompilerSources {
+impl<C: Compiler> CompilerSources<C> {
     /// Converts all `\\` separators to `/`
     ///
     /// This effectively ensures that `solc` can find imported files like `/src/Cheats.sol` in the
@@ -388,7 +616,7 @@ impl CompilerSources {
         {
             use path_slash::PathBufExt;
 
-            fn slash_versioned_sources(v: &mut VersionedSources) {
+            fn slash_versioned_sources<C: Compiler>(v: &mut VersionedSources<C>) {
                 for (_, (_, sources)) in v {
                     *sources = std::mem::take(sources)
                         .into_iter()
@@ -407,22 +635,27 @@ impl CompilerSources {
     }
 
     /// Filters out all sources that don't need to be compiled, see [`ArtifactsCache::filter`]
-    fn filtered<T: ArtifactOutput>(
+    ///
+    /// The cache can belong to a project with a different [`Compiler`], since vyper sources share
+    /// the cache of the project they're part of.
+    fn filtered<T: ArtifactOutput, P: Compiler>(
         self,
-        cache: &mut ArtifactsCache<'_, T>,
-    ) -> FilteredCompilerSources {
-        fn filtered_sources<T: ArtifactOutput>(
-            sources: VersionedSources,
-            cache: &mut ArtifactsCache<'_, T>,
-        ) -> VersionedFilteredSources {
+        cache: &mut ArtifactsCache<'_, T, P>,
+    ) -> FilteredCompilerSources<C> {
+        fn filtered_sources<T: ArtifactOutput, C: Compiler, P: Compiler>(
+            sources: VersionedSources<C>,
+            cache: &mut ArtifactsCache<'_, T, P>,
+        ) -> VersionedFilteredSources<C> {
             // fill all content hashes first so they're available for all source sets
             sources.iter().for_each(|(_, (_, sources))| {
                 cache.fill_content_hashes(sources);
             });
+            let compile_profiles = compile_profiles(&sources, cache.profiles());
+            cache.set_compile_profiles(compile_profiles.clone());
 
-            sources
+            let mut sources = sources
                 .into_iter()
-                .map(|(solc, (version, sources))| {
+                .map(|(compiler, (version, sources))| {
                     trace!("Filtering {} sources for {}", sources.len(), version);
                     let sources = cache.filter(sources, &version);
                     trace!(
@@ -430,9 +663,51 @@ impl CompilerSources {
                         sources.dirty().count(),
                         sources.dirty_files().collect::<Vec<_>>()
                     );
-                    (solc, (version, sources))
+                    (compiler, (version, sources))
                 })
-                .collect()
+                .collect();
+            dedup_dirty_sources(&mut sources, &compile_profiles);
+            sources
+        }
+
+        /// Files can be part of multiple source sets with the same version but different settings
+        /// if they're imported by files with different profiles. They're compiled with the set of
+        /// their own profile, or the first set otherwise. In all other sets they're only included
+        /// to compile the files that import them.
+        fn compile_profiles<C: Compiler>(
+            sources: &VersionedSources<C>,
+            profiles: &CompilerProfiles,
+        ) -> HashMap<(Version, PathBuf), Option<usize>> {
+            let mut owners = HashMap::new();
+            for ((_, profile), (version, sources)) in sources.iter() {
+                for file in sources.keys() {
+                    let owner = owners.entry((version.clone(), file.clone())).or_insert(*profile);
+                    if *profile == profiles.profile(file) {
+                        *owner = *profile;
+                    }
+                }
+            }
+            owners
+        }
+
+        /// Only selects the output of dirty files in the set of the profile they're compiled with,
+        /// see [`compile_profiles`]
+        fn dedup_dirty_sources<C: Compiler>(
+            sources: &mut VersionedFilteredSources<C>,
+            compile_profiles: &HashMap<(Version, PathBuf), Option<usize>>,
+        ) {
+            if sources.keys().all(|(_, profile)| profile.is_none()) {
+                return;
+            }
+
+            for ((_, profile), (version, sources)) in sources.iter_mut() {
+                for (file, source) in sources.0.iter_mut() {
+                    let owner = compile_profiles.get(&(version.clone(), file.clone()));
+                    if source.is_dirty() && owner.map_or(false, |owner| owner != profile) {
+                        *source = FilteredSource::Clean(source.source().clone());
+                    }
+                }
+            }
         }
 
         match self {
@@ -448,36 +723,54 @@ impl CompilerSources {
 
 /// Determines how the `solc <-> sources` pairs are executed
 #[derive(Debug, Clone)]
-enum FilteredCompilerSources {
+enum FilteredCompilerSources<C: Compiler> {
     /// Compile all these sequentially
-    Sequential(VersionedFilteredSources),
+    Sequential(VersionedFilteredSources<C>),
     /// Compile all these in parallel using a certain amount of jobs
-    Parallel(VersionedFilteredSources, usize),
+    Parallel(VersionedFilteredSources<C>, usize),
 }
 
-impl FilteredCompilerSources {
-    /// Compiles all the files with `Solc`
+impl<C: Compiler> FilteredCompilerSources<C> {
+    /// Compiles all the files with their assigned [`Compiler`]
+    #[allow(clippy::too_many_arguments)]
     fn compile(
         self,
-        settings: &Settings,
+        profiles: &CompilerProfiles,
         paths: &ProjectPathsConfig,
-        sparse_output: SparseOutputFilter,
+        sparse_output: &SparseOutputFilter,
         graph: &GraphEdges,
         create_build_info: bool,
+        interrupt: InterruptOptions<'_>,
+        caches: OutputCaches<'_>,
     ) -> Result<AggregatedCompilerOutput> {
         match self {
-            FilteredCompilerSources::Sequential(input) => {
-                compile_sequential(input, settings, paths, sparse_output, graph, create_build_info)
-            }
-            FilteredCompilerSources::Parallel(input, j) => {
-                compile_parallel(input, j, settings, paths, sparse_output, graph, create_build_info)
-            }
+            FilteredCompilerSources::Sequential(input) => compile_sequential(
+                input,
+                profiles,
+                paths,
+                sparse_output,
+                graph,
+                create_build_info,
+                interrupt,
+                caches,
+            ),
+            FilteredCompilerSources::Parallel(input, j) => compile_parallel(
+                input,
+                j,
+                profiles,
+                paths,
+                sparse_output,
+                graph,
+                create_build_info,
+                interrupt,
+                caches,
+            ),
         }
     }
 
     #[cfg(test)]
     #[cfg(all(feature = "project-util", feature = "svm-solc"))]
-    fn sources(&self) -> &VersionedFilteredSources {
+    fn sources(&self) -> &VersionedFilteredSources<C> {
         match self {
             FilteredCompilerSources::Sequential(v) => v,
             FilteredCompilerSources::Parallel(v, _) => v,
@@ -486,72 +779,75 @@ impl FilteredCompilerSources {
 }
 
 /// Compiles the input set sequentially and returns an aggregated set of the solc `CompilerOutput`s
-fn compile_sequential(
-    input: VersionedFilteredSources,
-    settings: &Settings,
+#[allow(clippy::too_many_arguments)]
+fn compile_sequential<C: Compiler>(
+    input: VersionedFilteredSources<C>,
+    profiles: &CompilerProfiles,
     paths: &ProjectPathsConfig,
-    sparse_output: SparseOutputFilter,
+    sparse_output: &SparseOutputFilter,
     graph: &GraphEdges,
     create_build_info: bool,
+    interrupt: InterruptOptions<'_>,
+    caches: OutputCaches<'_>,
 ) -> Result<AggregatedCompilerOutput> {
     let mut aggregated = AggregatedCompilerOutput::default();
     trace!("compiling {} jobs sequentially", input.len());
-    for (solc, (version, filtered_sources)) in input {
+    for ((compiler, profile), (version, filtered_sources)) in input {
         if filtered_sources.is_empty() {
             // nothing to compile
-            trace!("skip solc {} {} for empty sources set", solc.as_ref().display(), version);
+            trace!("skip {} {:?} {} for empty sources set", C::NAME, compiler, version);
             continue;
         }
-        trace!(
-            "compiling {} sources with solc \"{}\" {:?}",
-            filtered_sources.len(),
-            solc.as_ref().display(),
-            solc.args
-        );
+        trace!("compiling {} sources with {} {:?}", filtered_sources.len(), C::NAME, compiler);
 
         let dirty_files: Vec<PathBuf> = filtered_sources.dirty_files().cloned().collect();
 
         // depending on the composition of the filtered sources, the output selection can be
         // optimized
+        let settings = &profiles.solc_config(profile).settings;
         let mut opt_settings = settings.clone();
         let sources = sparse_output.sparse_sources(filtered_sources, &mut opt_settings, graph);
 
-        for input in CompilerInput::with_sources(sources) {
-            let actually_dirty = input
-                .sources
-                .keys()
-                .filter(|f| dirty_files.contains(f))
+        let input_settings = compiler.settings(&opt_settings, &sources, paths);
+        for input in compiler.create_inputs(sources, &input_settings, &version, paths) {
+            // the source paths of the input are relative to the project root
+            let actually_dirty = dirty_files
+                .iter()
+                .filter(|f| input.sources().contains_key(f.strip_prefix(&paths.root).unwrap_or(f)))
                 .cloned()
                 .collect::<Vec<_>>();
             if actually_dirty.is_empty() {
                 // nothing to compile for this particular language, all dirty files are in the other
                 // language set
                 trace!(
-                    "skip solc {} {} compilation of {} compiler input due to empty source set",
-                    solc.as_ref().display(),
+                    "skip {} {:?} {} compilation of {} compiler input due to empty source set",
+                    C::NAME,
+                    compiler,
                     version,
-                    input.language
+                    input.language()
                 );
                 continue;
             }
-            let input = input
-                .settings(opt_settings.clone())
-                .normalize_evm_version(&version)
-                .with_remappings(paths.remappings.clone())
-                .with_base_path(&paths.root)
-                .sanitized(&version);
 
             trace!(
-                "calling solc `{}` with {} sources {:?}",
+                "calling {} `{}` with {} sources {:?}",
+                C::NAME,
                 version,
-                input.sources.len(),
-                input.sources.keys()
+                input.sources().len(),
+                input.sources().keys()
             );
 
-            let start = Instant::now();
-            report::solc_spawn(&solc, &version, &input, &actually_dirty);
-            let output = solc.compile(&input)?;
-            report::solc_success(&solc, &version, &output, &start.elapsed());
+            ensure_not_cancelled(interrupt, &version, &actually_dirty)?;
+            let filter = output_filter(&opt_settings, &actually_dirty, paths, create_build_info);
+            let output = compile_job(
+                &compiler,
+                &version,
+                &input,
+                &actually_dirty,
+                &filter,
+                interrupt,
+                caches,
+            )?;
             trace!("compiled input, output has error: {}", output.has_error());
             trace!("received compiler output: {:?}", output.contracts.keys());
 
@@ -568,23 +864,26 @@ fn compile_sequential(
 }
 
 /// compiles the input set using `num_jobs` threads
-fn compile_parallel(
-    input: VersionedFilteredSources,
+#[allow(clippy::too_many_arguments)]
+fn compile_parallel<C: Compiler>(
+    input: VersionedFilteredSources<C>,
     num_jobs: usize,
-    settings: &Settings,
+    profiles: &CompilerProfiles,
     paths: &ProjectPathsConfig,
-    sparse_output: SparseOutputFilter,
+    sparse_output: &SparseOutputFilter,
     graph: &GraphEdges,
     create_build_info: bool,
+    interrupt: InterruptOptions<'_>,
+    caches: OutputCaches<'_>,
 ) -> Result<AggregatedCompilerOutput> {
     debug_assert!(num_jobs > 1);
     trace!("compile {} sources in parallel using up to {} solc jobs", input.len(), num_jobs);
 
     let mut jobs = Vec::with_capacity(input.len());
-    for (solc, (version, filtered_sources)) in input {
+    for ((compiler, profile), (version, filtered_sources)) in input {
         if filtered_sources.is_empty() {
             // nothing to compile
-            trace!("skip solc {} {} for empty sources set", solc.as_ref().display(), version);
+            trace!("skip {} {:?} {} for empty sources set", C::NAME, compiler, version);
             continue;
         }
 
@@ -592,36 +891,33 @@ fn compile_parallel(
 
         // depending on the composition of the filtered sources, the output selection can be
         // optimized
+        let settings = &profiles.solc_config(profile).settings;
         let mut opt_settings = settings.clone();
         let sources = sparse_output.sparse_sources(filtered_sources, &mut opt_settings, graph);
 
-        for input in CompilerInput::with_sources(sources) {
-            let actually_dirty = input
-                .sources
-                .keys()
-                .filter(|f| dirty_files.contains(f))
+        let input_settings = compiler.settings(settings, &sources, paths);
+        for input in compiler.create_inputs(sources, &input_settings, &version, paths) {
+            // the source paths of the input are relative to the project root
+            let actually_dirty = dirty_files
+                .iter()
+                .filter(|f| input.sources().contains_key(f.strip_prefix(&paths.root).unwrap_or(f)))
                 .cloned()
                 .collect::<Vec<_>>();
             if actually_dirty.is_empty() {
                 // nothing to compile for this particular language, all dirty files are in the other
                 // language set
                 trace!(
-                    "skip solc {} {} compilation of {} compiler input due to empty source set",
-                    solc.as_ref().display(),
+                    "skip {} {:?} {} compilation of {} compiler input due to empty source set",
+                    C::NAME,
+                    compiler,
                     version,
-                    input.language
+                    input.language()
                 );
                 continue;
             }
 
-            let job = input
-                .settings(settings.clone())
-                .normalize_evm_version(&version)
-                .with_remappings(paths.remappings.clone())
-                .with_base_path(&paths.root)
-                .sanitized(&version);
-
-            jobs.push((solc.clone(), version.clone(), job, actually_dirty))
+            let filter = output_filter(settings, &actually_dirty, paths, create_build_info);
+            jobs.push((compiler.clone(), version.clone(), input, actually_dirty, filter))
         }
     }
 
@@ -630,28 +926,34 @@ fn compile_parallel(
     // localkey. This way we keep access to the reporter in the rayon pool
     let scoped_report = report::get_default(|reporter| reporter.clone());
 
-    // start a rayon threadpool that will execute all `Solc::compile()` processes
+    // start a rayon threadpool that will execute all `Compiler::compile()` processes
     let pool = rayon::ThreadPoolBuilder::new().num_threads(num_jobs).build().unwrap();
 
     let outputs = pool.install(move || {
         jobs.into_par_iter()
-            .map(move |(solc, version, input, actually_dirty)| {
+            .map(move |(compiler, version, input, actually_dirty, filter)| {
                 // set the reporter on this thread
                 let _guard = report::set_scoped(&scoped_report);
 
                 trace!(
-                    "calling solc `{}` {:?} with {} sources: {:?}",
+                    "calling {} `{}` {:?} with {} sources: {:?}",
+                    C::NAME,
                     version,
-                    solc.args,
-                    input.sources.len(),
-                    input.sources.keys()
+                    compiler,
+                    input.sources().len(),
+                    input.sources().keys()
                 );
-                let start = Instant::now();
-                report::solc_spawn(&solc, &version, &input, &actually_dirty);
-                solc.compile(&input).map(move |output| {
-                    report::solc_success(&solc, &version, &output, &start.elapsed());
-                    (version, input, output)
-                })
+                ensure_not_cancelled(interrupt, &version, &actually_dirty)?;
+                let output = compile_job(
+                    &compiler,
+                    &version,
+                    &input,
+                    &actually_dirty,
+                    &filter,
+                    interrupt,
+                    caches,
+                )?;
+                Ok((version, input, output))
             })
             .collect::<Result<Vec<_>>>()
     })?;
@@ -669,6 +971,205 @@ fn compile_parallel(
     Ok(aggregated)
 }
 
+/// Compiles a single input, unless one of the `caches` already contains its output
+fn compile_job<C: Compiler>(
+    compiler: &C,
+    version: &Version,
+    input: &C::Input,
+    dirty_files: &[PathBuf],
+    filter: &OutputFilter,
+    interrupt: InterruptOptions<'_>,
+    caches: OutputCaches<'_>,
+) -> Result<CompilerOutput> {
+    let key = if caches.is_empty() {
+        None
+    } else {
+        let key = SharedCache::key(C::NAME, version, input, filter)?;
+        if let Some(output) = caches.get(C::NAME, version, dirty_files, &key) {
+            return Ok(output);
+        }
+        Some(key)
+    };
+
+    let start = Instant::now();
+    compiler.report_spawn(version, input, dirty_files);
+    let output = compiler
+        .compile_filtered(input, filter, interrupt)
+        .map_err(|err| err.with_job(version, dirty_files))?;
+    compiler.report_success(version, &output, &start.elapsed());
+
+    if let Some(key) = key {
+        caches.insert(&key, &output);
+    }
+    Ok(output)
+}
+
+/// The caches that are consulted before a compiler job is started.
+///
+/// A broken cache never fails the compilation, failures are only logged and treated as misses.
+#[derive(Clone, Copy, Debug, Default)]
+struct OutputCaches<'a> {
+    shared: Option<&'a SharedCache>,
+    remote: Option<&'a dyn RemoteCache>,
+}
+
+impl<'a> OutputCaches<'a> {
+    fn new<T: ArtifactOutput, C: Compiler>(project: &'a Project<T, C>) -> Self {
+        Self { shared: project.shared_cache.as_ref(), remote: project.remote_cache.as_deref() }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.shared.is_none() && self.remote.is_none()
+    }
+
+    /// Looks up the output for the `key`, first in the shared cache and then in the remote cache.
+    ///
+    /// Outputs found in the remote cache are also stored in the shared cache.
+    fn get(
+        &self,
+        compiler_name: &str,
+        version: &Version,
+        dirty_files: &[PathBuf],
+        key: &str,
+    ) -> Option<CompilerOutput> {
+        if let Some(cache) = self.shared {
+            if let Some(output) = cache.get(key) {
+                trace!("found output in shared cache: {key}");
+                report::shared_cache_hit(compiler_name, version, dirty_files);
+                return Some(output);
+            }
+            report::shared_cache_miss(compiler_name, version, dirty_files);
+        }
+
+        let cache = self.remote?;
+        let output = cache.get(key).and_then(|output| {
+            Ok(output
+                .map(|output| serde_json::from_slice::<CompilerOutput>(&output))
+                .transpose()?)
+        });
+        match output {
+            Ok(Some(output)) => {
+                trace!("found output in remote cache: {key}");
+                report::remote_cache_hit(compiler_name, version, dirty_files);
+                if let Some(shared) = self.shared {
+                    if let Err(err) = shared.insert(key, &output) {
+                        warn!(%err, "failed to store output in shared cache");
+                    }
+                }
+                Some(output)
+            }
+            Ok(None) => {
+                report::remote_cache_miss(compiler_name, version, dirty_files);
+                None
+            }
+            Err(err) => {
+                warn!(%err, "failed to fetch output from remote cache");
+                report::remote_cache_miss(compiler_name, version, dirty_files);
+                None
+            }
+        }
+    }
+
+    /// Stores the output for the `key` in all caches, outputs with errors are never stored
+    fn insert(&self, key: &str, output: &CompilerOutput) {
+        if output.has_error() {
+            return;
+        }
+        if let Some(cache) = self.shared {
+            if let Err(err) = cache.insert(key, output) {
+                warn!(%err, "failed to store output in shared cache");
+            }
+        }
+        if let Some(cache) = self.remote {
+            let stored = serde_json::to_vec(output)
+                .map_err(SolcError::from)
+                .and_then(|output| cache.put(key, &output));
+            if let Err(err) = stored {
+                warn!(%err, "failed to store output in remote cache");
+            }
+        }
+    }
+}
+
+/// Returns the filter for the output of a compiler job.
+///
+/// Artifacts are only emitted for the dirty files, so only their ASTs are kept, unless build infos
+/// are created, which contain the entire output.
+fn output_filter(
+    settings: &Settings,
+    dirty_files: &[PathBuf],
+    paths: &ProjectPathsConfig,
+    create_build_info: bool,
+) -> OutputFilter {
+    if create_build_info {
+        return OutputFilter::default();
+    }
+    // the source paths of the output are relative to the project root
+    OutputFilter::for_job(
+        settings,
+        dirty_files.iter().map(|f| f.strip_prefix(&paths.root).unwrap_or(f)),
+    )
+}
+
+/// Reports the import cycles between the project's source files, if configured.
+///
+/// Returns [`SolcError::ImportCycles`] if they're configured as errors.
+fn check_import_cycles<T: ArtifactOutput, C: Compiler>(
+    project: &Project<T, C>,
+    graph: &Graph,
+) -> Result<()> {
+    let Some(severity) = project.import_cycles else { return Ok(()) };
+    let cycles = graph
+        .import_cycles()
+        .into_iter()
+        .filter(|cycle| cycle.iter().all(|file| file.starts_with(&project.paths.sources)))
+        .collect::<Vec<_>>();
+    if cycles.is_empty() {
+        return Ok(());
+    }
+    report::import_cycles(&cycles, severity);
+    if severity.is_error() {
+        return Err(SolcError::ImportCycles(cycles));
+    }
+    Ok(())
+}
+
+fn check_duplicate_contracts<T: ArtifactOutput, C: Compiler>(
+    project: &Project<T, C>,
+    graph: &Graph,
+) -> Result<()> {
+    let Some(severity) = project.duplicate_contracts else { return Ok(()) };
+    let duplicates = graph
+        .duplicate_contracts()
+        .into_iter()
+        .filter_map(|mut duplicate| {
+            duplicate.files.retain(|file| file.starts_with(&project.paths.sources));
+            (duplicate.files.len() > 1).then_some(duplicate)
+        })
+        .collect::<Vec<_>>();
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+    report::duplicate_contracts(&duplicates, severity);
+    if severity.is_error() {
+        return Err(SolcError::DuplicateContracts(duplicates));
+    }
+    Ok(())
+}
+
+/// Returns [`SolcError::Interrupted`] if the compilation was cancelled before the compiler job for
+/// the `dirty_files` is started.
+fn ensure_not_cancelled(
+    interrupt: InterruptOptions<'_>,
+    version: &Version,
+    dirty_files: &[PathBuf],
+) -> Result<()> {
+    if interrupt.is_cancelled() {
+        return Err(SolcError::interrupted(Interrupt::Cancelled).with_job(version, dirty_files));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 #[cfg(all(feature = "project-util", feature = "svm-solc"))]
 mod tests {
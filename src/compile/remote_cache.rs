@@ -0,0 +1,182 @@
+//! Remote caches for compiler outputs, for example to share outputs between CI runners.
+//!
+//! A [`RemoteCache`] stores the serialized [`CompilerOutput`](crate::CompilerOutput) of compiler
+//! jobs under the same keys as the [`SharedCache`](crate::SharedCache), see
+//! [`SharedCache::key()`](crate::SharedCache::key). Remote caches are strictly best effort: if a
+//! cache can't be reached or returns garbage, the job is compiled locally.
+
+use crate::{
+    error::{Result, SolcError},
+    utils,
+};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// A remote store for compiler outputs.
+///
+/// Implementations only move opaque bytes, (de)serializing the outputs is done by the caller.
+///
+/// Configure it with [`ProjectBuilder::remote_cache()`](crate::ProjectBuilder::remote_cache).
+pub trait RemoteCache: fmt::Debug + Send + Sync {
+    /// Returns the output stored for the `key` or `None` if there is none
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Stores the `output` for the `key`
+    fn put(&self, key: &str, output: &[u8]) -> Result<()>;
+}
+
+/// A [`RemoteCache`] in a directory, for example on a network share.
+///
+/// Every output is stored as `<dir>/<key>.json`.
+#[derive(Clone, Debug)]
+pub struct FileSystemRemoteCache {
+    dir: PathBuf,
+}
+
+impl FileSystemRemoteCache {
+    /// Creates a new cache in the given directory, which is created once the first output is
+    /// stored
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The directory all outputs are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+impl RemoteCache for FileSystemRemoteCache {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read(&path).map(Some).map_err(|err| SolcError::io(err, path))
+    }
+
+    fn put(&self, key: &str, output: &[u8]) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|err| SolcError::io(err, &self.dir))?;
+        // readers never see a partial output
+        Ok(utils::write_atomic(self.path(key), output)?)
+    }
+}
+
+/// A [`RemoteCache`] that's accessed over HTTP.
+///
+/// Outputs are fetched with `GET <url>/<key>` and stored with `PUT <url>/<key>`. A `404` response
+/// to a `GET` is a miss, any other non-success status is an error.
+///
+/// Requests are sent with an async client that's driven by the current Tokio runtime, if there is
+/// one, so the cache can be used by projects that are compiled from within a runtime.
+#[cfg(feature = "remote-cache")]
+#[derive(Clone, Debug)]
+pub struct HttpRemoteCache {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "remote-cache")]
+impl HttpRemoteCache {
+    /// The default timeout of every request
+    pub const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+    /// Creates a new cache at the given base url
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        Self::with_timeout(url, Self::DEFAULT_TIMEOUT)
+    }
+
+    /// Creates a new cache at the given base url whose requests time out after `timeout`
+    pub fn with_timeout(url: impl Into<String>, timeout: std::time::Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            // every request may be driven by a different runtime, connections can't be reused
+            .pool_max_idle_per_host(0)
+            .build()
+            .map_err(|err| format_err!("failed to create HTTP client: {err}"))?;
+        Ok(Self::with_client(url, client))
+    }
+
+    /// Creates a new cache at the given base url that uses the given client, for example to send
+    /// authentication headers with every request
+    pub fn with_client(url: impl Into<String>, client: reqwest::Client) -> Self {
+        let url = url.into().trim_end_matches('/').to_string();
+        Self { url, client }
+    }
+
+    /// The base url of the cache
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn entry_url(&self, key: &str) -> String {
+        format!("{}/{key}", self.url)
+    }
+
+    async fn async_get(&self, url: &str) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|err| format_err!("GET {url} failed: {err}"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            bail!("GET {url} failed: {}", response.status())
+        }
+        let body = response.bytes().await.map_err(|err| format_err!("GET {url} failed: {err}"))?;
+        Ok(Some(body.to_vec()))
+    }
+
+    async fn async_put(&self, url: &str, output: &[u8]) -> Result<()> {
+        let response = self
+            .client
+            .put(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(output.to_vec())
+            .send()
+            .await
+            .map_err(|err| format_err!("PUT {url} failed: {err}"))?;
+        if !response.status().is_success() {
+            bail!("PUT {url} failed: {}", response.status())
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "remote-cache")]
+impl RemoteCache for HttpRemoteCache {
+    // `reqwest::blocking::Client` does not behave well inside of a Tokio runtime, see
+    // https://github.com/seanmonstar/reqwest/issues/1017
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        utils::RuntimeOrHandle::new().block_on(self.async_get(&self.entry_url(key)))
+    }
+
+    fn put(&self, key: &str, output: &[u8]) -> Result<()> {
+        utils::RuntimeOrHandle::new().block_on(self.async_put(&self.entry_url(key), output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_store_outputs_in_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = FileSystemRemoteCache::new(tmp.path().join("remote"));
+        assert_eq!(cache.get("a").unwrap(), None);
+
+        cache.put("a", b"{}").unwrap();
+        assert_eq!(cache.get("a").unwrap(), Some(b"{}".to_vec()));
+        assert_eq!(cache.get("b").unwrap(), None);
+    }
+}
@@ -0,0 +1,376 @@
+//! A content-addressed cache for compiler outputs that's shared across projects.
+//!
+//! Every [`Project`](crate::Project) tracks its own sources in its
+//! [`SolFilesCache`](crate::cache::SolFilesCache), so two projects that compile the same
+//! dependencies with the same settings both invoke the compiler. The [`SharedCache`] stores the
+//! [`CompilerOutput`] of every compiler job in a global directory, keyed by a hash of the
+//! sanitized compiler input and the compiler version, so that any project that produces the exact
+//! same input gets the stored output instead of spawning the compiler again.
+
+use crate::{
+    error::{Result, SolcError},
+    utils, CompilerOutput, OutputFilter,
+};
+use alloy_primitives::hex;
+use fs2::FileExt;
+use md5::Digest;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Version of the layout of the cache directory, part of every key
+const SHARED_CACHE_FORMAT: &str = "foundry-compilers-shared-cache-1";
+
+/// The file in the cache directory that tracks the size and the last use of every entry
+const SHARED_CACHE_INDEX: &str = "index.json";
+
+/// The file in the cache directory that serializes updates of the index across processes
+const SHARED_CACHE_INDEX_LOCK: &str = "index.lock";
+
+/// The directory in the cache directory that contains the stored outputs
+const SHARED_CACHE_ENTRIES: &str = "entries";
+
+/// The default maximum size of the cache directory: 5 GiB
+pub const DEFAULT_SHARED_CACHE_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// A compiler output cache in a directory that can be shared by multiple projects.
+///
+/// Entries are evicted in least recently used order once the total size of all stored outputs
+/// exceeds [`SharedCache::max_size()`]. Outputs with errors are never stored.
+///
+/// Configure it with [`ProjectBuilder::shared_cache()`](crate::ProjectBuilder::shared_cache).
+/// Hits and misses are reported via [`Reporter::on_shared_cache_hit()`] and
+/// [`Reporter::on_shared_cache_miss()`].
+///
+/// [`Reporter::on_shared_cache_hit()`]: crate::report::Reporter::on_shared_cache_hit
+/// [`Reporter::on_shared_cache_miss()`]: crate::report::Reporter::on_shared_cache_miss
+#[derive(Clone, Debug)]
+pub struct SharedCache {
+    /// The directory all entries are stored in
+    dir: PathBuf,
+    /// The maximum total size of all entries in bytes
+    max_size: u64,
+}
+
+impl SharedCache {
+    /// Creates a new cache in the given directory, which is created once the first entry is stored
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), max_size: DEFAULT_SHARED_CACHE_SIZE }
+    }
+
+    /// Returns the default location of the shared cache: `<cache dir>/foundry-compilers`, for
+    /// example `~/.cache/foundry-compilers` on linux
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("foundry-compilers"))
+    }
+
+    /// Sets the maximum total size of all stored outputs in bytes
+    #[must_use]
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The directory all entries are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The maximum total size of all stored outputs in bytes
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Returns the key for the output of the `compiler` at `version` for the given `input`,
+    /// filtered by the `filter`.
+    ///
+    /// The input is expected to be sanitized and to only contain paths relative to the project
+    /// root, otherwise identical sources in different projects produce different keys.
+    pub fn key<I: Serialize>(
+        compiler: &str,
+        version: &Version,
+        input: &I,
+        filter: &OutputFilter,
+    ) -> Result<String> {
+        let mut hasher = md5::Md5::new();
+        hasher.update(SHARED_CACHE_FORMAT);
+        hasher.update(compiler);
+        hasher.update(version.to_string());
+        serde_json::to_writer(&mut hasher, input)?;
+        serde_json::to_writer(&mut hasher, filter)?;
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Returns the stored output for the `key`, if any.
+    ///
+    /// An entry that can't be read is treated as missing.
+    pub fn get(&self, key: &str) -> Option<CompilerOutput> {
+        let path = self.entry_path(key);
+        if !path.exists() {
+            return None;
+        }
+        let output = match utils::read_json_file(&path) {
+            Ok(output) => output,
+            Err(err) => {
+                warn!(?path, %err, "failed to read shared cache entry");
+                return None;
+            }
+        };
+        if let Err(err) = self.update_index(|index| {
+            if let Some(entry) = index.entries.get_mut(key) {
+                entry.last_used = now();
+            }
+        }) {
+            warn!(%err, "failed to update shared cache index");
+        }
+        Some(output)
+    }
+
+    /// Stores the output for the `key` and evicts the least recently used entries if the cache
+    /// exceeds its maximum size.
+    ///
+    /// Outputs that contain errors or that exceed the maximum size by themselves are not stored.
+    pub fn insert(&self, key: &str, output: &CompilerOutput) -> Result<()> {
+        if output.has_error() {
+            return Ok(());
+        }
+        let content = serde_json::to_vec(output)?;
+        let size = content.len() as u64;
+        if size > self.max_size {
+            trace!(key, size, "output exceeds the maximum size of the shared cache");
+            return Ok(());
+        }
+
+        let entries = self.dir.join(SHARED_CACHE_ENTRIES);
+        fs::create_dir_all(&entries).map_err(|err| SolcError::io(err, &entries))?;
+        let path = self.entry_path(key);
+        // write to a temporary file first, so other processes never read a partial entry
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&tmp, content).map_err(|err| SolcError::io(err, &tmp))?;
+        fs::rename(&tmp, &path).map_err(|err| SolcError::io(err, &path))?;
+
+        self.update_index(|index| {
+            index.entries.insert(key.to_string(), IndexEntry { size, last_used: now() });
+            index.evict(&self.dir, self.max_size);
+        })
+    }
+
+    /// The total size of all stored outputs in bytes
+    pub fn size(&self) -> u64 {
+        self.read_index().entries.values().map(|entry| entry.size).sum()
+    }
+
+    /// The number of stored outputs
+    pub fn len(&self) -> usize {
+        self.read_index().entries.len()
+    }
+
+    /// Whether the cache has no stored outputs
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all stored outputs.
+    ///
+    /// The lock file of the index is kept, other processes may still hold it.
+    pub fn clear(&self) -> Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+        let _lock = self.lock_index()?;
+        let entries = self.dir.join(SHARED_CACHE_ENTRIES);
+        if entries.exists() {
+            fs::remove_dir_all(&entries).map_err(|err| SolcError::io(err, &entries))?;
+        }
+        let index = self.dir.join(SHARED_CACHE_INDEX);
+        if index.exists() {
+            fs::remove_file(&index).map_err(|err| SolcError::io(err, &index))?;
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        entry_path(&self.dir, key)
+    }
+
+    fn read_index(&self) -> CacheIndex {
+        let path = self.dir.join(SHARED_CACHE_INDEX);
+        if !path.exists() {
+            return CacheIndex::default();
+        }
+        utils::read_json_file(&path).unwrap_or_else(|err| {
+            warn!(?path, %err, "failed to read shared cache index");
+            CacheIndex::default()
+        })
+    }
+
+    /// Blocks until the exclusive lock of the index is acquired, it's released when dropped
+    fn lock_index(&self) -> Result<IndexLock> {
+        fs::create_dir_all(&self.dir).map_err(|err| SolcError::io(err, &self.dir))?;
+        let path = self.dir.join(SHARED_CACHE_INDEX_LOCK);
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|err| SolcError::io(err, &path))?;
+        file.lock_exclusive().map_err(|err| SolcError::io(err, &path))?;
+        Ok(IndexLock(file))
+    }
+
+    /// Applies `f` to the index and writes it back while holding the lock of the index, so that
+    /// concurrent updates of other processes aren't lost
+    fn update_index(&self, f: impl FnOnce(&mut CacheIndex)) -> Result<()> {
+        let _lock = self.lock_index()?;
+        let mut index = self.read_index();
+        f(&mut index);
+        if !self.dir.join(SHARED_CACHE_ENTRIES).exists() {
+            // the cache was cleared in the meantime
+            return Ok(());
+        }
+        utils::write_json_file(&index, self.dir.join(SHARED_CACHE_INDEX), 64 * 1024)
+    }
+}
+
+/// An exclusive lock on the index of a [`SharedCache`]
+struct IndexLock(File);
+
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.0);
+    }
+}
+
+/// Tracks the size and the last use of every entry of the [`SharedCache`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexEntry {
+    /// The size of the stored output in bytes
+    size: u64,
+    /// Unix timestamp of the last use in milliseconds
+    last_used: u64,
+}
+
+impl CacheIndex {
+    /// Removes the least recently used entries until the total size is at most `max_size`
+    fn evict(&mut self, dir: &Path, max_size: u64) {
+        let mut size: u64 = self.entries.values().map(|entry| entry.size).sum();
+        if size <= max_size {
+            return;
+        }
+        let mut by_last_use = self
+            .entries
+            .iter()
+            .map(|(key, entry)| (entry.last_used, key.clone()))
+            .collect::<Vec<_>>();
+        by_last_use.sort_unstable();
+        for (_, key) in by_last_use {
+            if size <= max_size {
+                break;
+            }
+            let entry = self.entries.remove(&key).expect("exists");
+            size -= entry.size;
+            trace!(key, "evicting shared cache entry");
+            let _ = fs::remove_file(entry_path(dir, &key));
+        }
+    }
+}
+
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(SHARED_CACHE_ENTRIES).join(format!("{key}.json"))
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::SourceFile;
+
+    fn output(id: u32) -> CompilerOutput {
+        let mut output = CompilerOutput::default();
+        output.sources.insert(format!("src/{id}.sol"), SourceFile { id, ast: None });
+        output
+    }
+
+    #[test]
+    fn can_store_and_evict_outputs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let entry_size = serde_json::to_vec(&output(1)).unwrap().len() as u64;
+        let cache = SharedCache::new(tmp.path().join("cache")).with_max_size(2 * entry_size);
+        assert!(cache.is_empty());
+        assert!(cache.get("a").is_none());
+
+        cache.insert("a", &output(1)).unwrap();
+        cache.insert("b", &output(2)).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), 2 * entry_size);
+
+        // `a` is now used more recently than `b`
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(cache.get("a").unwrap(), output(1));
+
+        cache.insert("c", &output(3)).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap(), output(1));
+        assert_eq!(cache.get("c").unwrap(), output(3));
+
+        cache.clear().unwrap();
+        assert!(cache.is_empty());
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn can_update_index_from_separate_instances() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("cache");
+        std::thread::scope(|s| {
+            for id in 0..8 {
+                // every instance stands in for another process that uses the same directory
+                let cache = SharedCache::new(&dir);
+                s.spawn(move || cache.insert(&id.to_string(), &output(id)).unwrap());
+            }
+        });
+        let cache = SharedCache::new(&dir);
+        assert_eq!(cache.len(), 8);
+        assert!((0..8).all(|id| cache.get(&id.to_string()).is_some()));
+
+        cache.clear().unwrap();
+        assert!(cache.is_empty());
+        assert!(dir.join(SHARED_CACHE_INDEX_LOCK).exists());
+        cache.insert("a", &output(1)).unwrap();
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn key_depends_on_input_version_and_filter() {
+        let version = Version::new(0, 8, 24);
+        let filter = OutputFilter::default();
+        let key = SharedCache::key("solc", &version, &"input", &filter).unwrap();
+        assert_eq!(key, SharedCache::key("solc", &version, &"input", &filter).unwrap());
+        assert_ne!(key, SharedCache::key("solc", &version, &"other", &filter).unwrap());
+        assert_ne!(
+            key,
+            SharedCache::key("solc", &Version::new(0, 8, 23), &"input", &filter).unwrap()
+        );
+        assert_ne!(
+            key,
+            SharedCache::key("solc", &version, &"input", &filter.clone().skip_generated_sources())
+                .unwrap()
+        );
+    }
+}
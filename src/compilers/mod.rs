@@ -0,0 +1,142 @@
+//! Abstraction over the compilers a [`Project`] can drive.
+//!
+//! The project pipeline (graph resolution, caching, reporting and artifact output) only interacts
+//! with a compiler through the [`Compiler`] trait. [`Solc`](crate::Solc) is the default
+//! implementation.
+
+use crate::{
+    artifacts::{Settings, Sources},
+    error::Result,
+    report, utils, ArtifactOutput, CompilerOutput, IncludePaths, InterruptOptions, OutputFilter,
+    Project, ProjectPathsConfig, Source,
+};
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub mod solc;
+pub mod vyper;
+
+/// A compiler version that is either installed (available locally) or can be installed.
+pub use crate::compile::SolcVersion as CompilerVersion;
+
+/// The input of a single compiler invocation, for example the standard JSON input of `solc`.
+pub trait CompilerInput: Serialize + fmt::Debug + Clone + Send + Sync {
+    /// Returns all sources included in this input
+    fn sources(&self) -> &Sources;
+
+    /// Returns the language of the sources, e.g. `Solidity`
+    fn language(&self) -> &str;
+}
+
+/// A compiler that can be driven by a [`Project`].
+///
+/// An implementation covers version detection, construction of the inputs for a set of sources,
+/// invocation and parsing of the output, which is converted into a [`CompilerOutput`] so the
+/// project can handle the contracts of all compilers alike.
+pub trait Compiler: fmt::Debug + Clone + Ord + Send + Sync + 'static {
+    /// The input this compiler is invoked with
+    type Input: CompilerInput;
+
+    /// The settings of a single input, derived from the project's [`Settings`]
+    type Settings: fmt::Debug + Clone + Send + Sync;
+
+    /// The output this compiler produces for an input
+    type Output: Into<CompilerOutput>;
+
+    /// Name of the compiler, used in reports and error messages
+    const NAME: &'static str;
+
+    /// Extensions of the source files this compiler can compile
+    const FILE_EXTENSIONS: &'static [&'static str];
+
+    /// Returns the version of this compiler
+    fn version(&self) -> Result<Version>;
+
+    /// Parses the version requirement declared by the given source, if any
+    fn source_version_req(source: &Source) -> Option<VersionReq>;
+
+    /// Returns the sorted list of all versions of this compiler that are known to this instance.
+    ///
+    /// If `offline` is set, only installed versions are returned.
+    fn available_versions(&self, offline: bool) -> Vec<CompilerVersion>;
+
+    /// Returns the compiler for the given version, installing it first if necessary
+    fn for_version(&self, version: &CompilerVersion, offline: bool) -> Result<Self>;
+
+    /// Applies the project's configuration to this compiler instance, for example the paths a
+    /// compiler is allowed to read from.
+    fn configure<T: ArtifactOutput>(
+        self,
+        _project: &Project<T, Self>,
+        _version: &Version,
+        _include_paths: IncludePaths,
+    ) -> Self {
+        self
+    }
+
+    /// Derives the settings this compiler is invoked with from the project's [`Settings`] for
+    /// the given sources.
+    fn settings(
+        &self,
+        settings: &Settings,
+        sources: &Sources,
+        paths: &ProjectPathsConfig,
+    ) -> Self::Settings;
+
+    /// Creates all inputs required to compile the given sources with the given settings.
+    ///
+    /// The compiler may split the sources into multiple inputs, for example by language.
+    fn create_inputs(
+        &self,
+        sources: Sources,
+        settings: &Self::Settings,
+        version: &Version,
+        paths: &ProjectPathsConfig,
+    ) -> Vec<Self::Input>;
+
+    /// Invokes the compiler with the given input and parses its output.
+    ///
+    /// The compiler process is killed once the timeout of the `interrupt` options elapses or its
+    /// token is cancelled.
+    fn compile(&self, input: &Self::Input, interrupt: InterruptOptions<'_>)
+        -> Result<Self::Output>;
+
+    /// Invokes the compiler with the given input and drops everything from its output the
+    /// `filter` doesn't keep.
+    ///
+    /// By default the output is filtered once it's complete, compilers that can filter their output
+    /// while it's deserialized should override this.
+    fn compile_filtered(
+        &self,
+        input: &Self::Input,
+        filter: &OutputFilter,
+        interrupt: InterruptOptions<'_>,
+    ) -> Result<CompilerOutput> {
+        let mut output = self.compile(input, interrupt)?.into();
+        filter.apply(&mut output);
+        Ok(output)
+    }
+
+    /// Notifies the reporter that this compiler is about to compile the given input
+    fn report_spawn(&self, version: &Version, _input: &Self::Input, dirty_files: &[PathBuf]) {
+        report::compiler_spawn(Self::NAME, version, dirty_files);
+    }
+
+    /// Notifies the reporter that this compiler successfully compiled an input
+    fn report_success(&self, version: &Version, _output: &CompilerOutput, duration: &Duration) {
+        report::compiler_success(Self::NAME, version, duration);
+    }
+}
+
+/// Returns `true` if the given file is compiled by `C` when compiling a [`Project`].
+///
+/// Vyper sources are compiled by a separate [`Vyper`](vyper::Vyper) pass, unless `C` compiles
+/// them itself.
+pub(crate) fn is_compiler_source<C: Compiler>(path: &Path) -> bool {
+    !utils::is_vyper_file(path) || C::FILE_EXTENSIONS.contains(&"vy")
+}
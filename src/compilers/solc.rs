@@ -0,0 +1,171 @@
+//! [`Compiler`] implementation for [`Solc`].
+
+use super::{Compiler, CompilerInput, CompilerVersion};
+use crate::{
+    artifacts::{Settings, Sources},
+    error::{Result, SolcError},
+    report, ArtifactOutput, CompilerOutput, IncludePaths, InterruptOptions, OutputFilter, Project,
+    ProjectPathsConfig, Solc, Source,
+};
+use semver::{Version, VersionReq};
+use std::{path::PathBuf, time::Duration};
+
+impl CompilerInput for crate::CompilerInput {
+    fn sources(&self) -> &Sources {
+        &self.sources
+    }
+
+    fn language(&self) -> &str {
+        &self.language
+    }
+}
+
+impl Compiler for Solc {
+    type Input = crate::CompilerInput;
+
+    type Settings = Settings;
+
+    type Output = CompilerOutput;
+
+    const NAME: &'static str = "solc";
+
+    const FILE_EXTENSIONS: &'static [&'static str] = &["sol", "yul"];
+
+    fn version(&self) -> Result<Version> {
+        Solc::version(self)
+    }
+
+    fn source_version_req(source: &Source) -> Option<VersionReq> {
+        Solc::source_version_req(source).ok()
+    }
+
+    fn available_versions(&self, offline: bool) -> Vec<CompilerVersion> {
+        #[cfg(feature = "svm-solc")]
+        if !offline {
+            return Solc::all_versions();
+        }
+        #[cfg(not(feature = "svm-solc"))]
+        let _ = offline;
+        Solc::installed_versions()
+    }
+
+    fn for_version(&self, version: &CompilerVersion, offline: bool) -> Result<Self> {
+        // we take the installer lock here to ensure installation checking is done in sync
+        #[cfg(all(test, feature = "svm-solc"))]
+        let _lock = crate::compile::take_solc_installer_lock();
+
+        let solc = if !version.is_installed() {
+            if offline {
+                return Err(SolcError::msg(format!(
+                    "missing solc \"{version}\" installation in offline mode"
+                )));
+            }
+            // install missing solc
+            #[cfg(feature = "svm-solc")]
+            {
+                Solc::blocking_install(version.as_ref())?
+            }
+            #[cfg(not(feature = "svm-solc"))]
+            {
+                return Err(SolcError::msg(format!("solc \"{version}\" is not installed")));
+            }
+        } else {
+            // find installed svm
+            Solc::find_svm_installed_version(version.to_string())?.ok_or_else(|| {
+                SolcError::msg(format!("solc \"{version}\" should have been installed"))
+            })?
+        };
+
+        #[cfg(feature = "svm-solc")]
+        if offline {
+            trace!("skip verifying solc checksum for {} in offline mode", solc.solc.display());
+        } else {
+            trace!("verifying solc checksum for {}", solc.solc.display());
+            if let Err(err) = solc.verify_checksum() {
+                trace!(?err, "corrupted solc version, redownloading  \"{}\"", version);
+                Solc::blocking_install(version.as_ref())?;
+                trace!("reinstalled solc: \"{}\"", version);
+            }
+        }
+
+        Ok(solc)
+    }
+
+    fn configure<T: ArtifactOutput>(
+        self,
+        project: &Project<T, Self>,
+        version: &Version,
+        include_paths: IncludePaths,
+    ) -> Self {
+        project.configure_solc_with_version(self, Some(version.clone()), include_paths)
+    }
+
+    fn settings(
+        &self,
+        settings: &Settings,
+        _sources: &Sources,
+        _paths: &ProjectPathsConfig,
+    ) -> Self::Settings {
+        settings.clone()
+    }
+
+    fn create_inputs(
+        &self,
+        sources: Sources,
+        settings: &Self::Settings,
+        version: &Version,
+        paths: &ProjectPathsConfig,
+    ) -> Vec<Self::Input> {
+        create_solc_inputs(sources, settings, version, paths)
+    }
+
+    fn compile(
+        &self,
+        input: &Self::Input,
+        interrupt: InterruptOptions<'_>,
+    ) -> Result<CompilerOutput> {
+        let output = self.compile_output_interruptible(input, interrupt)?;
+        Ok(serde_json::from_slice(&output)?)
+    }
+
+    fn compile_filtered(
+        &self,
+        input: &Self::Input,
+        filter: &OutputFilter,
+        interrupt: InterruptOptions<'_>,
+    ) -> Result<CompilerOutput> {
+        Solc::compile_streamed(self, input, filter, interrupt)
+    }
+
+    fn report_spawn(&self, version: &Version, input: &Self::Input, dirty_files: &[PathBuf]) {
+        report::solc_spawn(self, version, input, dirty_files);
+    }
+
+    fn report_success(&self, version: &Version, output: &CompilerOutput, duration: &Duration) {
+        report::solc_success(self, version, output, duration);
+    }
+}
+
+/// Creates the standard JSON inputs of `solc` for the given sources, without vyper sources.
+///
+/// Shared by all [`Compiler`]s that are invoked like `solc`.
+pub(crate) fn create_solc_inputs(
+    sources: Sources,
+    settings: &Settings,
+    version: &Version,
+    paths: &ProjectPathsConfig,
+) -> Vec<crate::CompilerInput> {
+    crate::CompilerInput::with_sources(sources)
+        .into_iter()
+        // vyper sources are compiled separately
+        .filter(|input| !input.is_vyper())
+        .map(|input| {
+            input
+                .settings(settings.clone())
+                .normalize_evm_version(version)
+                .with_remappings(paths.remappings.clone())
+                .with_base_path(&paths.root)
+                .sanitized(version)
+        })
+        .collect()
+}
@@ -0,0 +1,242 @@
+//! The vyper standard JSON input
+
+use crate::{
+    artifacts::{serde_helpers, EvmVersion, Settings, Sources},
+    compilers::CompilerInput,
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+/// The `language` of a vyper standard JSON input
+pub const VYPER: &str = "Vyper";
+
+/// The extension of vyper interface files
+pub const VYPER_INTERFACE_EXTENSION: &str = "vyi";
+
+/// The outputs requested for every selected source file
+const VYPER_OUTPUT_SELECTION: &[&str] = &[
+    "abi",
+    "evm.bytecode.object",
+    "evm.bytecode.opcodes",
+    "evm.deployedBytecode.object",
+    "evm.deployedBytecode.opcodes",
+    "evm.deployedBytecode.sourceMap",
+    "evm.methodIdentifiers",
+];
+
+/// The first vyper version that accepts an optimization mode instead of a boolean flag
+const VYPER_OPTIMIZATION_MODE: Version = Version::new(0, 3, 10);
+
+/// The first vyper version that supports `search_paths`
+const VYPER_SEARCH_PATHS: Version = Version::new(0, 4, 0);
+
+/// Input type `vyper` expects.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VyperInput {
+    pub language: String,
+    pub sources: Sources,
+    /// Interface files (`.vyi`), these don't produce any output
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub interfaces: Sources,
+    pub settings: VyperSettings,
+}
+
+impl VyperInput {
+    /// Creates a new [VyperInput] for the given sources, interface files are moved to
+    /// [VyperInput::interfaces]
+    pub fn new(sources: Sources, settings: VyperSettings) -> Self {
+        let (interfaces, sources) = sources
+            .into_iter()
+            .partition(|(path, _)| path.extension() == Some(VYPER_INTERFACE_EXTENSION.as_ref()));
+        Self { language: VYPER.to_string(), sources, interfaces, settings }
+    }
+
+    /// This will remove/adjust values in the `VyperInput` that are not compatible with this
+    /// version
+    pub fn sanitize(&mut self, version: &Version) {
+        self.settings.sanitize(version)
+    }
+
+    /// Consumes the type and returns a [VyperInput::sanitized] version
+    pub fn sanitized(mut self, version: &Version) -> Self {
+        self.settings.sanitize(version);
+        self
+    }
+
+    /// Removes the `base` path from all source files, interfaces and settings.
+    ///
+    /// Vyper is invoked from the `base` directory, see [`Vyper::base_path`](super::Vyper)
+    pub fn with_base_path(mut self, base: impl AsRef<Path>) -> Self {
+        let base = base.as_ref();
+        let strip = |sources: Sources| {
+            sources
+                .into_iter()
+                .map(|(path, s)| (path.strip_prefix(base).map(Into::into).unwrap_or(path), s))
+                .collect()
+        };
+        self.sources = strip(std::mem::take(&mut self.sources));
+        self.interfaces = strip(std::mem::take(&mut self.interfaces));
+        self.settings = self.settings.with_base_path(base);
+        self
+    }
+}
+
+impl CompilerInput for VyperInput {
+    fn sources(&self) -> &Sources {
+        &self.sources
+    }
+
+    fn language(&self) -> &str {
+        &self.language
+    }
+}
+
+/// The optimization mode of vyper `>=0.3.10`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VyperOptimizationMode {
+    Gas,
+    Codesize,
+    None,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VyperSettings {
+    #[serde(
+        default,
+        with = "serde_helpers::display_from_str_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub evm_version: Option<EvmVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimize: Option<VyperOptimizationMode>,
+    /// `file -> outputs`, only selected files produce output
+    #[serde(default)]
+    pub output_selection: BTreeMap<String, Vec<String>>,
+    /// Additional directories vyper searches for imported modules, requires vyper `>=0.4.0`
+    #[serde(default, rename = "search_paths", skip_serializing_if = "Option::is_none")]
+    pub search_paths: Option<BTreeSet<PathBuf>>,
+}
+
+impl VyperSettings {
+    /// Derives the vyper settings from the project's [Settings] for the given sources.
+    ///
+    /// The EVM version and an enabled optimizer are carried over. Interface files never produce
+    /// output, any other file is only selected for output if the output selection of the solc
+    /// settings doesn't prune it, for example because it's a clean file that's only included as an
+    /// import of a dirty file.
+    pub fn new(settings: &Settings, sources: &Sources, search_paths: BTreeSet<PathBuf>) -> Self {
+        let output_selection = sources
+            .keys()
+            .filter(|file| file.extension() != Some(VYPER_INTERFACE_EXTENSION.as_ref()))
+            .filter(|file| {
+                settings
+                    .output_selection
+                    .as_ref()
+                    .get(&file.display().to_string())
+                    .map(|selection| !selection.is_empty())
+                    .unwrap_or(true)
+            })
+            .map(|file| {
+                let outputs = VYPER_OUTPUT_SELECTION.iter().map(|s| s.to_string()).collect();
+                (file.display().to_string(), outputs)
+            })
+            .collect();
+
+        // the solc optimizer is disabled by default, whereas vyper optimizes for gas by default, so
+        // only an enabled optimizer is carried over
+        let optimize =
+            settings.optimizer.enabled.unwrap_or_default().then_some(VyperOptimizationMode::Gas);
+
+        Self {
+            evm_version: settings.evm_version,
+            optimize,
+            output_selection,
+            search_paths: Some(search_paths),
+        }
+    }
+
+    /// This will remove/adjust values in the settings that are not compatible with this version.
+    pub fn sanitize(&mut self, version: &Version) {
+        if *version < VYPER_OPTIMIZATION_MODE {
+            self.optimize = None;
+        }
+        if *version < VYPER_SEARCH_PATHS {
+            self.search_paths = None;
+        }
+    }
+
+    /// Strips the `base` from all paths of the output selection and the search paths
+    pub fn with_base_path(mut self, base: impl AsRef<Path>) -> Self {
+        let base = base.as_ref();
+        self.output_selection = std::mem::take(&mut self.output_selection)
+            .into_iter()
+            .map(|(file, outputs)| {
+                let file = Path::new(&file)
+                    .strip_prefix(base)
+                    .map(|p| p.display().to_string())
+                    .unwrap_or(file);
+                (file, outputs)
+            })
+            .collect();
+        if let Some(search_paths) = self.search_paths.take() {
+            self.search_paths = Some(
+                search_paths
+                    .into_iter()
+                    .map(|p| match p.strip_prefix(base) {
+                        Ok(p) if p.as_os_str().is_empty() => PathBuf::from("."),
+                        Ok(p) => p.into(),
+                        Err(_) => p,
+                    })
+                    .collect(),
+            );
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{artifacts::output_selection::OutputSelection, Source};
+
+    #[test]
+    fn can_create_vyper_input() {
+        let root = Path::new("/project");
+        let sources = Sources::from([
+            (root.join("src/Counter.vy"), Source::new("")),
+            (root.join("src/Clean.vy"), Source::new("")),
+            (root.join("src/ICounter.vyi"), Source::new("")),
+        ]);
+
+        let mut settings = Settings { evm_version: Some(EvmVersion::Paris), ..Default::default() };
+        settings.optimizer.enable();
+        settings.output_selection.0.insert(
+            "/project/src/Clean.vy".to_string(),
+            OutputSelection::empty_file_output_select(),
+        );
+
+        let vyper_settings =
+            VyperSettings::new(&settings, &sources, BTreeSet::from([root.join("lib")]));
+        let input = VyperInput::new(sources, vyper_settings)
+            .with_base_path(root)
+            .sanitized(&Version::new(0, 3, 10));
+
+        assert_eq!(input.language, VYPER);
+        assert_eq!(input.sources.keys().collect::<Vec<_>>(), ["src/Clean.vy", "src/Counter.vy"]);
+        assert_eq!(input.interfaces.keys().collect::<Vec<_>>(), ["src/ICounter.vyi"]);
+        assert_eq!(input.settings.output_selection.keys().collect::<Vec<_>>(), ["src/Counter.vy"]);
+        assert_eq!(input.settings.optimize, Some(VyperOptimizationMode::Gas));
+        // `search_paths` are only supported by vyper 0.4
+        assert!(input.settings.search_paths.is_none());
+
+        let json = serde_json::to_value(&input).unwrap();
+        assert_eq!(json["settings"]["evmVersion"], "paris");
+        assert_eq!(json["settings"]["optimize"], "gas");
+    }
+}
@@ -0,0 +1,283 @@
+//! Support for compiling [vyper](https://docs.vyperlang.org) sources (`.vy`, `.vyi`) via the
+//! [standard JSON interface](https://docs.vyperlang.org/en/stable/compiling-a-contract.html#vyper-json).
+//!
+//! A [`Project`] compiles its vyper sources with the [`Vyper`] configured via
+//! [`ProjectBuilder::vyper()`](crate::ProjectBuilder::vyper) and maps the output into the same
+//! artifacts and cache entries as its solidity sources.
+
+use super::{Compiler, CompilerVersion};
+use crate::{
+    artifacts::{Settings, Sources},
+    compile::interrupt,
+    error::{Result, SolcError},
+    utils, ArtifactOutput, CompilerOutput, IncludePaths, InterruptOptions, Project,
+    ProjectPathsConfig, Solc, Source,
+};
+use semver::{Version, VersionReq};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fmt,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    str::FromStr,
+};
+
+pub mod input;
+pub use input::{VyperInput, VyperOptimizationMode, VyperSettings};
+
+pub mod output;
+pub use output::VyperOutput;
+
+/// The name of the `vyper` binary on the system
+pub const VYPER: &str = "vyper";
+
+/// Abstraction over the `vyper` executable
+///
+/// By default the vyper path is configured as follows, with descending priority:
+///   1. `VYPER_PATH` environment variable
+///   2. `vyper` otherwise
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Vyper {
+    /// Path to the `vyper` executable
+    pub path: PathBuf,
+    /// The directory vyper is invoked from, source paths of the input are relative to it
+    pub base_path: Option<PathBuf>,
+}
+
+impl Default for Vyper {
+    fn default() -> Self {
+        if let Ok(vyper) = std::env::var("VYPER_PATH") {
+            return Vyper::new(vyper);
+        }
+        Vyper::new(VYPER)
+    }
+}
+
+impl fmt::Display for Vyper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())
+    }
+}
+
+impl Vyper {
+    /// A new instance which points to `vyper`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Vyper { path: path.into(), base_path: None }
+    }
+
+    /// Sets the directory vyper is invoked from
+    pub fn with_base_path(mut self, base_path: impl Into<PathBuf>) -> Self {
+        self.base_path = Some(base_path.into());
+        self
+    }
+
+    /// Parses the given source looking for the `# pragma version` definition and
+    /// returns the corresponding SemVer version requirement.
+    pub fn source_version_req(source: &Source) -> Result<VersionReq> {
+        let version =
+            utils::find_vyper_version_pragma(&source.content).ok_or(SolcError::PragmaNotFound)?;
+        Self::version_req(version.as_str())
+    }
+
+    /// Returns the corresponding SemVer version requirement for the vyper version pragma.
+    ///
+    /// Vyper also accepts the PEP 440 operators `~=` and `==`, which are translated into their
+    /// SemVer equivalents.
+    pub fn version_req(version: &str) -> Result<VersionReq> {
+        let version = version.trim().replace("~=", "~").replace("==", "=");
+        Solc::version_req(&version)
+    }
+
+    /// Compiles with `--standard-json` and converts the output into a [`CompilerOutput`].
+    pub fn compile(&self, input: &VyperInput) -> Result<CompilerOutput> {
+        self.compile_as::<VyperOutput>(input).map(Into::into)
+    }
+
+    /// Compiles with `--standard-json` and deserializes the output as the given `D`.
+    pub fn compile_as<D: DeserializeOwned>(&self, input: &VyperInput) -> Result<D> {
+        let output = self.compile_output(input)?;
+
+        // Only run UTF-8 validation once.
+        let output = std::str::from_utf8(&output).map_err(|_| SolcError::InvalidUtf8)?;
+
+        Ok(serde_json::from_str(output)?)
+    }
+
+    /// Compiles with `--standard-json` and returns the raw `stdout` output.
+    pub fn compile_output(&self, input: &VyperInput) -> Result<Vec<u8>> {
+        self.compile_output_interruptible(input, InterruptOptions::default())
+    }
+
+    /// Compiles with `--standard-json` and returns the raw `stdout` output.
+    ///
+    /// `vyper` is killed and [`SolcError::Interrupted`] is returned once the timeout of the
+    /// `interrupt` options elapses or its token is cancelled.
+    #[instrument(name = "compile", level = "debug", skip_all)]
+    pub fn compile_output_interruptible(
+        &self,
+        input: &VyperInput,
+        interrupt: InterruptOptions<'_>,
+    ) -> Result<Vec<u8>> {
+        let mut cmd = Command::new(&self.path);
+        if let Some(base_path) = &self.base_path {
+            cmd.current_dir(base_path);
+        }
+        cmd.arg("--standard-json");
+        cmd.stdin(Stdio::piped()).stderr(Stdio::piped()).stdout(Stdio::piped());
+
+        trace!(?input);
+        debug!(?cmd, "compiling");
+
+        let mut child = cmd.spawn().map_err(self.map_io_err())?;
+        debug!("spawned");
+
+        let stdin = child.stdin.as_mut().unwrap();
+        serde_json::to_writer(stdin, input)?;
+        debug!("wrote JSON input to stdin");
+
+        let output = interrupt::wait_with_output(child, interrupt)
+            .map_err(self.map_io_err())?
+            .map_err(SolcError::interrupted)?;
+        debug!(%output.status, output.stderr = ?String::from_utf8_lossy(&output.stderr), "finished");
+
+        if output.status.success() {
+            Ok(output.stdout)
+        } else {
+            Err(SolcError::solc_output(&output))
+        }
+    }
+
+    /// Invokes `vyper --version` and parses the output as a SemVer [`Version`].
+    #[instrument(level = "debug", skip_all)]
+    pub fn version(&self) -> Result<Version> {
+        let mut cmd = Command::new(&self.path);
+        cmd.arg("--version").stdin(Stdio::piped()).stderr(Stdio::piped()).stdout(Stdio::piped());
+        debug!(?cmd, "getting vyper version");
+        let output = cmd.output().map_err(self.map_io_err())?;
+        trace!(?output);
+        let version = version_from_output(output)?;
+        debug!(%version);
+        Ok(version)
+    }
+
+    fn map_io_err(&self) -> impl FnOnce(std::io::Error) -> SolcError + '_ {
+        move |err| SolcError::io(err, &self.path)
+    }
+}
+
+fn version_from_output(output: Output) -> Result<Version> {
+    if output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let version = stdout
+            .lines()
+            .rfind(|l| !l.trim().is_empty())
+            .ok_or_else(|| SolcError::msg("Version not found in vyper output"))?;
+        Ok(Version::from_str(version.trim())?)
+    } else {
+        Err(SolcError::solc_output(&output))
+    }
+}
+
+impl AsRef<Path> for Vyper {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<T: Into<PathBuf>> From<T> for Vyper {
+    fn from(vyper: T) -> Self {
+        Vyper::new(vyper.into())
+    }
+}
+
+impl Compiler for Vyper {
+    type Input = VyperInput;
+
+    type Settings = VyperSettings;
+
+    type Output = VyperOutput;
+
+    const NAME: &'static str = "vyper";
+
+    const FILE_EXTENSIONS: &'static [&'static str] = &["vy", "vyi"];
+
+    fn version(&self) -> Result<Version> {
+        Vyper::version(self)
+    }
+
+    fn source_version_req(source: &Source) -> Option<VersionReq> {
+        Vyper::source_version_req(source).ok()
+    }
+
+    /// Vyper installations are not managed, so this is the version of this [`Vyper`], if it's
+    /// installed.
+    fn available_versions(&self, _offline: bool) -> Vec<CompilerVersion> {
+        self.version().map(|v| vec![CompilerVersion::Installed(v)]).unwrap_or_default()
+    }
+
+    fn for_version(&self, version: &CompilerVersion, _offline: bool) -> Result<Self> {
+        let installed = self.version()?;
+        if installed != *version.as_ref() {
+            return Err(SolcError::msg(format!(
+                "vyper \"{version}\" is not installed, found \"{installed}\" at \"{self}\""
+            )));
+        }
+        Ok(self.clone())
+    }
+
+    fn configure<T: ArtifactOutput>(
+        self,
+        project: &Project<T, Self>,
+        _version: &Version,
+        _include_paths: IncludePaths,
+    ) -> Self {
+        self.with_base_path(project.root())
+    }
+
+    fn settings(
+        &self,
+        settings: &Settings,
+        sources: &Sources,
+        paths: &ProjectPathsConfig,
+    ) -> Self::Settings {
+        let search_paths = std::iter::once(paths.root.clone())
+            .chain(paths.libraries.iter().cloned())
+            .collect::<BTreeSet<_>>();
+        VyperSettings::new(settings, sources, search_paths)
+    }
+
+    fn create_inputs(
+        &self,
+        sources: Sources,
+        settings: &Self::Settings,
+        version: &Version,
+        paths: &ProjectPathsConfig,
+    ) -> Vec<Self::Input> {
+        vec![VyperInput::new(sources, settings.clone())
+            .with_base_path(&paths.root)
+            .sanitized(version)]
+    }
+
+    fn compile(&self, input: &Self::Input, interrupt: InterruptOptions<'_>) -> Result<VyperOutput> {
+        let output = self.compile_output_interruptible(input, interrupt)?;
+        Ok(serde_json::from_slice(&output)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_vyper_version_req() {
+        assert_eq!(Vyper::version_req("^0.3.10").unwrap(), VersionReq::parse("^0.3.10").unwrap());
+        assert_eq!(Vyper::version_req("0.3.10").unwrap(), VersionReq::parse("=0.3.10").unwrap());
+        assert_eq!(Vyper::version_req("==0.3.10").unwrap(), VersionReq::parse("=0.3.10").unwrap());
+        assert_eq!(Vyper::version_req("~=0.4.0").unwrap(), VersionReq::parse("~0.4.0").unwrap());
+        assert_eq!(
+            Vyper::version_req(">=0.3.7 <0.4.0").unwrap(),
+            VersionReq::parse(">=0.3.7, <0.4.0").unwrap()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! The vyper standard JSON output
+
+use crate::{
+    artifacts::{
+        contract::Contract, BytecodeObject, DeployedBytecode, Error, Evm, FileToContractsMap,
+        Severity, SourceFile,
+    },
+    CompilerOutput,
+};
+use alloy_json_abi::JsonAbi;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Output type `vyper` writes, see also [`CompilerOutput`]
+///
+/// The output is converted into a [`CompilerOutput`], so that vyper contracts are handled like
+/// any other contract.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VyperOutput {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<VyperCompilationError>,
+    #[serde(default)]
+    pub contracts: FileToContractsMap<VyperContract>,
+    #[serde(default)]
+    pub sources: BTreeMap<String, VyperSourceFile>,
+}
+
+/// A vyper error or warning
+///
+/// Unlike solc, vyper reports source locations as line and column, these are only preserved in
+/// the formatted message.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VyperCompilationError {
+    #[serde(default)]
+    pub r#type: Option<String>,
+    #[serde(default)]
+    pub component: Option<String>,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(default)]
+    pub formatted_message: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VyperContract {
+    pub abi: Option<JsonAbi>,
+    #[serde(default)]
+    pub evm: Option<VyperEvm>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VyperEvm {
+    #[serde(default)]
+    pub bytecode: Option<VyperBytecode>,
+    #[serde(default)]
+    pub deployed_bytecode: Option<VyperBytecode>,
+    #[serde(default)]
+    pub method_identifiers: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VyperBytecode {
+    pub object: BytecodeObject,
+    #[serde(default)]
+    pub opcodes: Option<String>,
+    /// A solc compatible source map string, newer vyper versions emit an object instead which is
+    /// not retained
+    #[serde(default)]
+    pub source_map: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VyperSourceFile {
+    pub id: u32,
+}
+
+impl From<VyperCompilationError> for Error {
+    fn from(error: VyperCompilationError) -> Self {
+        let VyperCompilationError { r#type, component, severity, message, formatted_message } =
+            error;
+        Error {
+            source_location: None,
+            secondary_source_locations: Vec::new(),
+            r#type: r#type.unwrap_or_else(|| "VyperException".to_string()),
+            component: component.unwrap_or_else(|| "compiler".to_string()),
+            severity,
+            error_code: None,
+            message,
+            formatted_message,
+        }
+    }
+}
+
+impl From<VyperBytecode> for crate::artifacts::Bytecode {
+    fn from(bytecode: VyperBytecode) -> Self {
+        let VyperBytecode { object, opcodes, source_map } = bytecode;
+        crate::artifacts::Bytecode {
+            function_debug_data: Default::default(),
+            object,
+            opcodes,
+            source_map: source_map.and_then(|map| map.as_str().map(str::to_string)),
+            generated_sources: Vec::new(),
+            link_references: Default::default(),
+        }
+    }
+}
+
+impl From<VyperContract> for Contract {
+    fn from(contract: VyperContract) -> Self {
+        let evm = contract.evm.map(|evm| Evm {
+            assembly: None,
+            legacy_assembly: None,
+            bytecode: evm.bytecode.map(Into::into),
+            deployed_bytecode: evm.deployed_bytecode.map(|bytecode| DeployedBytecode {
+                bytecode: Some(bytecode.into()),
+                immutable_references: Default::default(),
+            }),
+            // vyper emits `0x` prefixed selectors, solc doesn't
+            method_identifiers: evm
+                .method_identifiers
+                .into_iter()
+                .map(|(sig, id)| (sig, format!("{:0>8}", id.trim_start_matches("0x"))))
+                .collect(),
+            gas_estimates: None,
+        });
+        Contract {
+            abi: contract.abi,
+            metadata: None,
+            userdoc: Default::default(),
+            devdoc: Default::default(),
+            ir: None,
+            storage_layout: Default::default(),
+            evm,
+            ewasm: None,
+            ir_optimized: None,
+        }
+    }
+}
+
+impl From<VyperOutput> for CompilerOutput {
+    fn from(output: VyperOutput) -> Self {
+        let VyperOutput { errors, contracts, sources } = output;
+        CompilerOutput {
+            errors: errors.into_iter().map(Into::into).collect(),
+            contracts: contracts
+                .into_iter()
+                .map(|(file, contracts)| {
+                    (file, contracts.into_iter().map(|(name, c)| (name, c.into())).collect())
+                })
+                .collect(),
+            sources: sources
+                .into_iter()
+                .map(|(file, source)| (file, SourceFile { id: source.id, ast: None }))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn can_convert_vyper_output() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data/vyper-sample");
+        let output = std::fs::read_to_string(root.join("output.json")).unwrap();
+        let output: VyperOutput = serde_json::from_str(&output).unwrap();
+        let output = CompilerOutput::from(output);
+
+        assert!(!output.has_error());
+        let counter = output.contracts["src/Counter.vy"]["Counter"].clone();
+        assert!(counter.abi.is_some());
+        let evm = counter.evm.unwrap();
+        assert!(evm.bytecode.unwrap().object.as_bytes().is_some());
+        assert!(evm.deployed_bytecode.unwrap().bytes().is_some());
+        assert_eq!(evm.method_identifiers["increment()"], "d09de08a");
+        assert_eq!(output.sources["src/Counter.vy"].id, 0);
+    }
+
+    #[test]
+    fn can_convert_vyper_errors() {
+        let output = r#"{
+            "errors": [{
+                "type": "SyntaxException",
+                "component": "compiler",
+                "severity": "error",
+                "message": "invalid syntax",
+                "formattedMessage": "line 1:0 invalid syntax",
+                "sourceLocation": { "file": "src/Bad.vy", "lineno": 1, "col_offset": 0 }
+            }]
+        }"#;
+        let output: CompilerOutput = serde_json::from_str::<VyperOutput>(output).unwrap().into();
+        assert!(output.has_error());
+        assert_eq!(output.errors[0].r#type, "SyntaxException");
+    }
+}
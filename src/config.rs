@@ -4,9 +4,13 @@ use crate::{
     error::{Result, SolcError, SolcIoError},
     flatten::collect_ordered_deps,
     remappings::Remapping,
-    resolver::{Graph, SolImportAlias},
+    resolver::{
+        imports::cached_import_path, FilesystemResolver, Graph, ImportResolver, RemappingResolver,
+        ResolvedImport, SolImportAlias,
+    },
     utils, Source, Sources,
 };
+use semver::VersionReq;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeSet,
@@ -14,6 +18,7 @@ use std::{
     fs,
     ops::{Deref, DerefMut},
     path::{Component, Path, PathBuf},
+    sync::Arc,
 };
 
 /// Where to find all files or where to write them
@@ -37,6 +42,15 @@ pub struct ProjectPathsConfig {
     pub libraries: Vec<PathBuf>,
     /// The compiler remappings
     pub remappings: Vec<Remapping>,
+    /// Custom resolvers for imports that can't be resolved with the remappings or from the
+    /// filesystem, consulted in order, see [`ImportResolver`]
+    #[serde(skip)]
+    pub import_resolvers: Vec<Arc<dyn ImportResolver>>,
+    /// Whether imports are only resolved from the [`Self::imports_cache_dir()`], the
+    /// [`Self::import_resolvers`] aren't consulted if it's set. It's set by
+    /// [`ProjectBuilder::offline()`](crate::ProjectBuilder::offline)
+    #[serde(skip)]
+    pub offline: bool,
 }
 
 impl ProjectPathsConfig {
@@ -225,51 +239,74 @@ impl ProjectPathsConfig {
         import: &Path,
         include_paths: &mut IncludePaths,
     ) -> Result<PathBuf> {
-        let component = import
-            .components()
-            .next()
-            .ok_or_else(|| SolcError::msg(format!("Empty import path {}", import.display())))?;
+        if import.components().next().is_none() {
+            bail!("Empty import path {}", import.display());
+        }
 
-        if component == Component::CurDir || component == Component::ParentDir {
-            // if the import is relative we assume it's already part of the processed input
-            // file set
-            utils::normalize_solidity_import_path(cwd, import).map_err(|err| {
-                SolcError::msg(format!("failed to resolve relative import \"{err:?}\""))
-            })
-        } else {
-            // resolve library file
-            let resolved = self.resolve_library_import(cwd.as_ref(), import.as_ref());
-
-            if resolved.is_none() {
-                // absolute paths in solidity are a thing for example `import
-                // "src/interfaces/IConfig.sol"` which could either point to `cwd +
-                // src/interfaces/IConfig.sol`, or make use of a remapping (`src/=....`)
-                if let Some(lib) = self.find_library_ancestor(cwd) {
-                    if let Some((include_path, import)) =
-                        utils::resolve_absolute_library(lib, cwd, import)
-                    {
-                        // track the path for this absolute import inside a nested library
-                        include_paths.insert(include_path);
-                        return Ok(import);
-                    }
-                }
-                // also try to resolve absolute imports from the project paths
-                for path in [&self.root, &self.sources, &self.tests, &self.scripts] {
-                    if cwd.starts_with(path) {
-                        if let Ok(import) = utils::normalize_solidity_import_path(path, import) {
-                            return Ok(import);
-                        }
-                    }
-                }
+        let builtin: [&dyn ImportResolver; 2] = [&RemappingResolver, &FilesystemResolver];
+        for resolver in builtin {
+            if let Some(file) = self.apply_resolved_import(resolver, cwd, import, include_paths)? {
+                return Ok(file);
             }
+        }
 
-            resolved.ok_or_else(|| {
-                SolcError::msg(format!(
-                    "failed to resolve library import \"{:?}\"",
-                    import.display()
-                ))
-            })
+        if !self.import_resolvers.is_empty() {
+            // imports that were fetched before are resolved from the cache
+            let cached = self.imports_cache_dir().join(cached_import_path(import));
+            if cached.is_file() {
+                return Ok(cached);
+            }
+            if self.offline {
+                bail!("import \"{}\" isn't cached and can't be fetched offline", import.display());
+            }
+        }
+        for resolver in &self.import_resolvers {
+            if let Some(file) =
+                self.apply_resolved_import(&**resolver, cwd, import, include_paths)?
+            {
+                return Ok(file);
+            }
         }
+
+        Err(SolcError::msg(format!("failed to resolve library import \"{:?}\"", import.display())))
+    }
+
+    /// Resolves the `import` with the `resolver` and returns the file it refers to, if any
+    fn apply_resolved_import(
+        &self,
+        resolver: &dyn ImportResolver,
+        cwd: &Path,
+        import: &Path,
+        include_paths: &mut IncludePaths,
+    ) -> Result<Option<PathBuf>> {
+        let file = match resolver.resolve(self, cwd, import)? {
+            Some(ResolvedImport::File(file)) => file,
+            Some(ResolvedImport::LibraryFile { file, include_path }) => {
+                include_paths.insert(include_path);
+                file
+            }
+            Some(ResolvedImport::Content(content)) => self.write_cached_import(import, &content)?,
+            None => return Ok(None),
+        };
+        Ok(Some(file))
+    }
+
+    /// Returns the dir in which the contents of imports that were fetched by
+    /// [`Self::import_resolvers`] are cached, `imports` next to the cache file
+    pub fn imports_cache_dir(&self) -> PathBuf {
+        self.cache.parent().unwrap_or(&self.root).join("imports")
+    }
+
+    /// Writes the fetched content of the `import` to the [`Self::imports_cache_dir()`], unless it
+    /// didn't change, and returns the path of the file
+    fn write_cached_import(&self, import: &Path, content: &str) -> Result<PathBuf> {
+        let file = self.imports_cache_dir().join(cached_import_path(import));
+        if fs::read_to_string(&file).ok().as_deref() != Some(content) {
+            trace!("caching fetched import \"{}\" at {}", import.display(), file.display());
+            utils::create_parent_dir_all(&file)?;
+            utils::write_atomic(&file, content)?;
+        }
+        Ok(file)
     }
 
     /// Attempts to resolve an `import` from the given working directory.
@@ -279,6 +316,45 @@ impl ProjectPathsConfig {
         self.resolve_import_and_include_paths(cwd, import, &mut Default::default())
     }
 
+    /// Attempts to resolve a vyper `import` from the given working directory.
+    ///
+    /// Vyper imports name modules rather than files: the `import` is the module path without
+    /// extension, e.g. `lib/ownable` for `import lib.ownable`, which
+    /// resolves to the first existing `.vy` or `.vyi` file. Relative imports are resolved from
+    /// `cwd`, all others are looked up in the project root, the library dirs and then in `cwd`.
+    pub fn resolve_vyper_import(&self, cwd: &Path, import: &Path) -> Result<PathBuf> {
+        let component = import
+            .components()
+            .next()
+            .ok_or_else(|| SolcError::msg(format!("Empty import path {}", import.display())))?;
+
+        let search_paths = if component == Component::CurDir || component == Component::ParentDir {
+            vec![cwd]
+        } else {
+            std::iter::once(self.root.as_path())
+                .chain(self.libraries.iter().map(|lib| lib.as_path()))
+                .chain(std::iter::once(cwd))
+                .collect()
+        };
+
+        for dir in search_paths {
+            for ext in ["vy", "vyi"] {
+                let mut candidate = import.as_os_str().to_owned();
+                candidate.push(".");
+                candidate.push(ext);
+                if let Ok(path) = utils::normalize_solidity_import_path(dir, candidate) {
+                    return Ok(path);
+                }
+            }
+        }
+
+        Err(SolcError::msg(format!(
+            "failed to resolve vyper import \"{}\" from \"{}\"",
+            import.display(),
+            cwd.display()
+        )))
+    }
+
     /// Attempts to find the path to the real solidity file that's imported via the given `import`
     /// path by applying the configured remappings and checking the library dirs
     ///
@@ -329,10 +405,17 @@ impl ProjectPathsConfig {
     /// `@openzeppelin/contracts/contracts/token/ERC20/IERC20.sol` we check for this edge case
     /// here so that both styles work out of the box.
     pub fn resolve_library_import(&self, cwd: &Path, import: &Path) -> Option<PathBuf> {
+        self.resolve_remapped_import(cwd, import)
+            .or_else(|| utils::resolve_library(&self.libraries, import))
+    }
+
+    /// Resolves the `import` with the first matching remapping that applies to `cwd`, whether the
+    /// remapped file exists or not
+    pub fn resolve_remapped_import(&self, cwd: &Path, import: &Path) -> Option<PathBuf> {
         // if the import path starts with the name of the remapping then we get the resolved path by
         // removing the name and adding the remainder to the path of the remapping
         let cwd = cwd.strip_prefix(&self.root).unwrap_or(cwd);
-        if let Some(path) = self
+        let path = self
             .remappings
             .iter()
             .filter(|r| {
@@ -357,12 +440,8 @@ impl ProjectPathsConfig {
                     }
                     lib_path
                 })
-            })
-        {
-            Some(self.root.join(path))
-        } else {
-            utils::resolve_library(&self.libraries, import)
-        }
+            })?;
+        Some(self.root.join(path))
     }
 
     /// Attempts to autodetect the artifacts directory based on the given root path
@@ -721,6 +800,8 @@ impl ProjectPathsConfigBuilder {
                 .unwrap_or_else(|| libraries.iter().flat_map(Remapping::find_many).collect()),
             libraries,
             root,
+            import_resolvers: Vec::new(),
+            offline: false,
         }
     }
 
@@ -810,6 +891,132 @@ impl SolcConfigBuilder {
     }
 }
 
+/// Overrides the solc version and settings of all files whose path matches a glob pattern, for
+/// example to compile legacy contracts with an older solc and a different optimizer config.
+///
+/// Patterns are matched against the path relative to the project root, `*` doesn't match `/`
+/// but `**` does: `src/legacy/**` matches all files in `src/legacy` and its subdirectories.
+///
+/// Configure it with [`ProjectBuilder::compiler_override()`](crate::ProjectBuilder::compiler_override).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompilerOverride {
+    pattern: glob::Pattern,
+    version: Option<VersionReq>,
+    settings: Option<serde_json::Value>,
+}
+
+impl CompilerOverride {
+    /// Creates a new override for all files that match the glob `pattern`
+    pub fn new(pattern: &str) -> Result<Self> {
+        let pattern = glob::Pattern::new(pattern)
+            .map_err(|err| SolcError::msg(format!("invalid glob pattern \"{pattern}\": {err}")))?;
+        Ok(Self { pattern, version: None, settings: None })
+    }
+
+    /// Restricts the solc versions of the matching files to the given requirement, in addition to
+    /// their version pragmas
+    #[must_use]
+    pub fn version(mut self, version: VersionReq) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Patches the project's [`Settings`] of the matching files with the given [JSON merge
+    /// patch](https://www.rfc-editor.org/rfc/rfc7396), for example
+    /// `json!({ "optimizer": { "runs": 1000 }, "viaIR": null })`
+    #[must_use]
+    pub fn settings(mut self, patch: serde_json::Value) -> Self {
+        self.settings = Some(patch);
+        self
+    }
+
+    /// The glob pattern of the override
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// The additional version requirement of the matching files
+    pub fn version_req(&self) -> Option<&VersionReq> {
+        self.version.as_ref()
+    }
+
+    /// Whether the given path, relative to the project root, matches the override
+    pub fn is_match(&self, path: &Path) -> bool {
+        let options = glob::MatchOptions { require_literal_separator: true, ..Default::default() };
+        self.pattern.matches_path_with(path, options)
+    }
+
+    /// Returns the index of the first override that matches the file
+    pub(crate) fn find<'a>(
+        overrides: impl IntoIterator<Item = &'a CompilerOverride>,
+        root: &Path,
+        file: &Path,
+    ) -> Option<usize> {
+        let file = utils::source_name(file, root);
+        overrides.into_iter().position(|o| o.is_match(file))
+    }
+
+    /// Returns the config with the settings patch applied
+    pub fn apply(&self, config: &SolcConfig) -> Result<SolcConfig> {
+        let Some(patch) = &self.settings else { return Ok(config.clone()) };
+        let mut settings = serde_json::to_value(&config.settings)?;
+        merge_patch(&mut settings, patch);
+        let settings = serde_json::from_value(settings).map_err(|err| {
+            SolcError::msg(format!("invalid settings override for \"{}\": {err}", self.pattern()))
+        })?;
+        Ok(SolcConfig { settings })
+    }
+}
+
+/// Applies a JSON merge patch, see <https://www.rfc-editor.org/rfc/rfc7396>
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(Default::default());
+    }
+    let target = target.as_object_mut().expect("is object; qed.");
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key).or_insert(serde_json::Value::Null), value);
+        }
+    }
+}
+
+/// The [`SolcConfig`] of the project together with the configs of all its [`CompilerOverride`]s.
+///
+/// Every file belongs to the profile of the first override that matches it, or to the default
+/// profile, `None`, if there's none.
+#[derive(Clone, Debug)]
+pub(crate) struct CompilerProfiles {
+    root: PathBuf,
+    default: SolcConfig,
+    overrides: Vec<(CompilerOverride, SolcConfig)>,
+}
+
+impl CompilerProfiles {
+    /// Applies all overrides to the config
+    pub fn new(root: &Path, config: &SolcConfig, overrides: &[CompilerOverride]) -> Result<Self> {
+        let overrides =
+            overrides.iter().map(|o| Ok((o.clone(), o.apply(config)?))).collect::<Result<_>>()?;
+        Ok(Self { root: root.to_path_buf(), default: config.clone(), overrides })
+    }
+
+    /// Returns the profile of the given file
+    pub fn profile(&self, file: &Path) -> Option<usize> {
+        CompilerOverride::find(self.overrides.iter().map(|(o, _)| o), &self.root, file)
+    }
+
+    /// Returns the config of the given profile
+    pub fn solc_config(&self, profile: Option<usize>) -> &SolcConfig {
+        profile.map_or(&self.default, |idx| &self.overrides[idx].1)
+    }
+}
+
 /// Container for all `--include-path` arguments for Solc, see also
 /// [Solc docs](https://docs.soliditylang.org/en/v0.8.9/using-the-compiler.html#base-path-and-import-remapping).
 ///
@@ -1083,4 +1290,46 @@ mod tests {
             dependency.join("A.sol")
         );
     }
+
+    #[test]
+    fn can_match_compiler_overrides() {
+        let legacy = CompilerOverride::new("src/legacy/**").unwrap();
+        assert!(legacy.is_match(Path::new("src/legacy/A.sol")));
+        assert!(legacy.is_match(Path::new("src/legacy/nested/A.sol")));
+        assert!(!legacy.is_match(Path::new("src/A.sol")));
+
+        let flat = CompilerOverride::new("src/*.sol").unwrap();
+        assert!(flat.is_match(Path::new("src/A.sol")));
+        assert!(!flat.is_match(Path::new("src/legacy/A.sol")));
+
+        let root = Path::new("/root");
+        let overrides = [flat, legacy];
+        assert_eq!(CompilerOverride::find(&overrides, root, Path::new("/root/src/A.sol")), Some(0));
+        assert_eq!(
+            CompilerOverride::find(&overrides, root, Path::new("/root/src/legacy/A.sol")),
+            Some(1)
+        );
+        assert_eq!(CompilerOverride::find(&overrides, root, Path::new("/root/lib/A.sol")), None);
+
+        assert!(CompilerOverride::new("src/[").is_err());
+    }
+
+    #[test]
+    fn can_patch_settings() {
+        let mut config = SolcConfig::builder().build();
+        config.settings.via_ir = Some(true);
+        let patched = CompilerOverride::new("**")
+            .unwrap()
+            .settings(serde_json::json!({ "optimizer": { "runs": 1 }, "viaIR": null }))
+            .apply(&config)
+            .unwrap();
+        assert_eq!(patched.settings.optimizer.runs, Some(1));
+        assert_eq!(patched.settings.optimizer.enabled, config.settings.optimizer.enabled);
+        assert_eq!(patched.settings.via_ir, None);
+
+        let invalid = CompilerOverride::new("**")
+            .unwrap()
+            .settings(serde_json::json!({ "optimizer": { "runs": "many" } }));
+        assert!(invalid.apply(&config).is_err());
+    }
 }
@@ -1,7 +1,9 @@
+use crate::compile::interrupt::Interrupt;
 use semver::Version;
 use std::{
     io,
     path::{Path, PathBuf},
+    time::Duration,
 };
 use thiserror::Error;
 
@@ -50,17 +52,43 @@ pub enum SolcError {
     Resolve(SolcIoError),
     #[error("file cannot be resolved due to mismatch of file name case: {error}.\nFound existing file: {existing_file:?}\nPlease check the case of the import.")]
     ResolveCaseSensitiveFileName { error: SolcIoError, existing_file: PathBuf },
+    /// An import of a file couldn't be resolved, with suggestions how to fix it, see
+    /// [`UnresolvedImport`](crate::resolver::UnresolvedImport)
     #[error(
         "{0}\n\t\
          --> {1}\n\t\
-         {2}"
+         {2}{}",
+        fmt_suggestions(.3)
     )]
-    FailedResolveImport(Box<SolcError>, PathBuf, PathBuf),
+    FailedResolveImport(Box<SolcError>, PathBuf, PathBuf, Box<[crate::resolver::ImportSuggestion]>),
     #[cfg(feature = "svm-solc")]
     #[error(transparent)]
     SvmError(#[from] svm::SolcVmError),
     #[error("no contracts found at \"{0}\"")]
     NoContracts(String),
+    /// A compiler process was killed because it timed out or the compilation was cancelled
+    #[error("{}", fmt_interrupted(.reason, .version.as_ref(), .dirty_files))]
+    Interrupted { reason: Interrupt, version: Option<Version>, dirty_files: Vec<PathBuf> },
+    /// Import cycles between source files, see
+    /// [`ProjectBuilder::import_cycles()`](crate::ProjectBuilder::import_cycles)
+    #[error("{}", crate::report::format_import_cycles(.0))]
+    ImportCycles(Vec<Vec<PathBuf>>),
+    /// Contract names that are declared in multiple source files, see
+    /// [`ProjectBuilder::duplicate_contracts()`](crate::ProjectBuilder::duplicate_contracts)
+    #[error("{}", crate::report::format_duplicate_contracts(.0))]
+    DuplicateContracts(Vec<crate::resolver::DuplicateContract>),
+    /// The cache file was written with a format that's unknown or newer than
+    /// [`ETHERS_FORMAT_VERSION`](crate::cache::ETHERS_FORMAT_VERSION)
+    #[error(
+        "cache file \"{}\" has the unsupported format \"{format}\", the latest supported format is \"{}\"",
+        .path.display(),
+        crate::cache::ETHERS_FORMAT_VERSION
+    )]
+    UnsupportedCacheFormat { path: PathBuf, format: String },
+    /// Another build of the project held the build lock for longer than the configured timeout,
+    /// see [`ProjectBuilder::build_lock_timeout()`](crate::ProjectBuilder::build_lock_timeout)
+    #[error("timed out after {timeout:?} waiting for another build to release the lock \"{}\"", .path.display())]
+    BuildLockTimeout { path: PathBuf, timeout: Duration },
     /// General purpose message.
     #[error("{0}")]
     Message(String),
@@ -92,12 +120,58 @@ impl SolcError {
         SolcError::SolcError(output.status, trimmed.into())
     }
 
+    /// Create an error for an interrupted compiler process, see [`SolcError::with_job`].
+    pub(crate) fn interrupted(reason: Interrupt) -> Self {
+        SolcError::Interrupted { reason, version: None, dirty_files: Vec::new() }
+    }
+
+    /// Adds the compiler version and the dirty files of the job to an [`SolcError::Interrupted`]
+    /// error that doesn't have them yet.
+    pub(crate) fn with_job(self, job_version: &Version, job_dirty_files: &[PathBuf]) -> Self {
+        match self {
+            SolcError::Interrupted { reason, version, mut dirty_files } => {
+                if dirty_files.is_empty() {
+                    dirty_files = job_dirty_files.to_vec();
+                }
+                let version = version.or_else(|| Some(job_version.clone()));
+                SolcError::Interrupted { reason, version, dirty_files }
+            }
+            err => err,
+        }
+    }
+
     /// General purpose message.
     pub fn msg(msg: impl std::fmt::Display) -> Self {
         SolcError::Message(msg.to_string())
     }
 }
 
+fn fmt_suggestions(suggestions: &[crate::resolver::ImportSuggestion]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    format!("\n\t{}", crate::resolver::format_suggestions(suggestions))
+}
+
+fn fmt_interrupted(
+    reason: &Interrupt,
+    version: Option<&Version>,
+    dirty_files: &[PathBuf],
+) -> String {
+    let mut msg = String::from("compiler");
+    if let Some(version) = version {
+        msg.push_str(&format!(" {version}"));
+    }
+    msg.push_str(&format!(" {reason}"));
+    if !dirty_files.is_empty() {
+        msg.push_str(" while compiling:");
+        for file in dirty_files {
+            msg.push_str(&format!("\n\t{}", file.display()));
+        }
+    }
+    msg
+}
+
 #[derive(Debug, Error)]
 #[error("\"{}\": {io}", self.path.display())]
 pub struct SolcIoError {
@@ -25,16 +25,21 @@ pub use artifact_output::*;
 
 pub mod resolver;
 pub use hh::{HardhatArtifact, HardhatArtifacts};
-pub use resolver::Graph;
+pub use resolver::{Graph, ImportResolver, ResolvedImport, SourceOverlay};
+
+pub mod compilers;
+use compilers::{vyper::Vyper, Compiler};
 
 mod compile;
 pub use compile::{
-    output::{AggregatedCompilerOutput, ProjectCompileOutput},
+    output::{filter::OutputFilter, AggregatedCompilerOutput, ProjectCompileOutput},
     *,
 };
 
 mod config;
-pub use config::{AllowedLibPaths, PathStyle, ProjectPaths, ProjectPathsConfig, SolcConfig};
+pub use config::{
+    AllowedLibPaths, CompilerOverride, PathStyle, ProjectPaths, ProjectPathsConfig, SolcConfig,
+};
 
 pub mod remappings;
 use crate::artifacts::{Source, SourceFile, StandardJsonCompilerInput};
@@ -46,34 +51,61 @@ pub use filter::{FileFilter, TestFileFilter};
 
 use crate::{
     artifacts::Sources,
-    cache::SolFilesCache,
-    config::IncludePaths,
+    cache::{CacheFormat, SolFilesCache, SOLIDITY_FILES_CACHE_BINARY_FILENAME},
+    config::{CompilerProfiles, IncludePaths},
     error::{SolcError, SolcIoError},
+    resolver::VersionSelectionStrategy,
     sources::{VersionedSourceFile, VersionedSourceFiles},
 };
 use artifacts::{contract::Contract, Severity};
 use compile::output::contracts::VersionedContracts;
 use error::Result;
 use semver::Version;
-use std::path::{Path, PathBuf};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 /// Utilities for creating, mocking and testing of (temporary) projects
 #[cfg(feature = "project-util")]
 pub mod project_util;
 
 /// Represents a project workspace and handles `solc` compiling of all contracts in that workspace.
+///
+/// The compiler is `solc` by default but can be any [`Compiler`].
 #[derive(Debug)]
-pub struct Project<T: ArtifactOutput = ConfigurableArtifacts> {
+pub struct Project<T: ArtifactOutput = ConfigurableArtifacts, C: Compiler = Solc> {
     /// The layout of the project
     pub paths: ProjectPathsConfig,
-    /// Where to find solc
-    pub solc: Solc,
+    /// The compiler to use if auto detection is disabled
+    pub compiler: C,
+    /// The compiler for vyper sources, if `None` vyper is looked up via [`Vyper::default()`] once
+    /// the project contains vyper sources
+    pub vyper: Option<Vyper>,
     /// How solc invocation should be configured.
     pub solc_config: SolcConfig,
+    /// Overrides of the solc version and settings for files that match a glob, the first matching
+    /// override applies
+    pub compiler_overrides: Vec<CompilerOverride>,
     /// Whether caching is enabled
     pub cached: bool,
     /// Whether to output build information with each solc call.
     pub build_info: bool,
+    /// Whether to delete orphaned artifacts and build infos after every compilation that writes
+    /// the cache, see [`Project::gc()`]
+    pub auto_gc: bool,
+    /// How the cache file is written
+    pub cache_format: CacheFormat,
+    /// Whether sources whose last modification date and size didn't change since they were cached
+    /// are assumed to be unchanged, without hashing their content.
+    ///
+    /// This only skips hashing: all sources are still read and parsed to resolve the import
+    /// graph, so builds that don't change anything are only marginally faster. A file that's
+    /// modified without changing its size or modification date, for example by a tool that
+    /// restores the modification date, isn't recompiled.
+    pub trust_mtime: bool,
     /// Whether writing artifacts to disk is enabled
     pub no_artifacts: bool,
     /// Whether writing artifacts to disk is enabled
@@ -90,7 +122,31 @@ pub struct Project<T: ArtifactOutput = ConfigurableArtifacts> {
     pub include_paths: IncludePaths,
     /// Maximum number of `solc` processes to run simultaneously.
     solc_jobs: usize,
-    /// Offline mode, if set, network access (download solc) is disallowed
+    /// The maximum time a single compiler invocation may take before it's killed
+    pub solc_timeout: Option<Duration>,
+    /// Cancels the compilation: running `solc` processes are killed and no new jobs are started
+    pub cancellation_token: Option<CancellationToken>,
+    /// The maximum time to wait for another build of the project to release the [`BuildLock`],
+    /// waits until it's released if `None`
+    pub build_lock_timeout: Option<Duration>,
+    /// The compiler output cache shared with other projects, if any
+    pub shared_cache: Option<SharedCache>,
+    /// The remote compiler output cache, if any. It's consulted after the shared cache and failures
+    /// to reach it are ignored.
+    pub remote_cache: Option<Arc<dyn RemoteCache>>,
+    /// How import cycles between files in the sources dir are reported, they're not checked if
+    /// `None`. Cycles with [`Severity::Error`] fail the compilation.
+    pub import_cycles: Option<Severity>,
+    /// How contract names that are declared in multiple files of the sources dir are reported,
+    /// they're not checked if `None`. Duplicates with [`Severity::Error`] fail the compilation.
+    pub duplicate_contracts: Option<Severity>,
+    /// How the solc versions of the sources are selected if they're auto detected
+    pub version_selection: VersionSelectionStrategy,
+    /// The contents of files that take precedence over the files on disk, the output of overlaid
+    /// files and their importers isn't written to the cache or the artifacts dir
+    pub overlay: SourceOverlay,
+    /// Offline mode, if set, network access (download solc) is disallowed, see also
+    /// [`ProjectPathsConfig::offline`]
     pub offline: bool,
     /// Windows only config value to ensure the all paths use `/` instead of `\\`, same as `solc`
     ///
@@ -134,7 +190,7 @@ impl Project {
     }
 }
 
-impl<T: ArtifactOutput> Project<T> {
+impl<T: ArtifactOutput, C: Compiler> Project<T, C> {
     /// Returns the path to the artifacts directory
     pub fn artifacts_path(&self) -> &PathBuf {
         &self.paths.artifacts
@@ -150,6 +206,42 @@ impl<T: ArtifactOutput> Project<T> {
         &self.paths.cache
     }
 
+    /// Returns when the compiler processes of this project are killed, see
+    /// [`Self::solc_timeout`] and [`Self::cancellation_token`]
+    pub fn interrupt_options(&self) -> InterruptOptions<'_> {
+        InterruptOptions::new(self.solc_timeout, self.cancellation_token.as_ref())
+    }
+
+    /// Returns the path to the [`BuildLock`] file next to the cache file
+    pub fn build_lock_path(&self) -> PathBuf {
+        let cache_dir = self.paths.cache.parent().unwrap_or(&self.paths.root);
+        cache_dir.join(compile::lock::BUILD_LOCK_FILE)
+    }
+
+    /// Acquires the project's [`BuildLock`], waiting at most [`Self::build_lock_timeout`] for
+    /// another build to release it
+    pub fn acquire_build_lock(&self) -> Result<BuildLock> {
+        BuildLock::acquire(
+            self.build_lock_path(),
+            self.build_lock_timeout,
+            self.cancellation_token.as_ref(),
+        )
+    }
+
+    /// Returns the path to the binary encoding of the cache next to the cache file, it's only
+    /// written if the [`CacheFormat::Binary`] is used
+    pub fn binary_cache_path(&self) -> PathBuf {
+        let cache_dir = self.paths.cache.parent().unwrap_or(&self.paths.root);
+        cache_dir.join(SOLIDITY_FILES_CACHE_BINARY_FILENAME)
+    }
+
+    /// Returns the path to the [`ArtifactIndex`] file next to the cache file, it's only written if
+    /// the [`CacheFormat::Binary`] is used
+    pub fn artifact_index_path(&self) -> PathBuf {
+        let cache_dir = self.paths.cache.parent().unwrap_or(&self.paths.root);
+        cache_dir.join(ARTIFACT_INDEX_FILENAME)
+    }
+
     /// Returns the path to the `build-info` directory nested in the artifacts dir
     pub fn build_info_path(&self) -> &PathBuf {
         &self.paths.build_infos
@@ -160,6 +252,17 @@ impl<T: ArtifactOutput> Project<T> {
         &self.paths.root
     }
 
+    /// Returns the compiler of the project
+    #[deprecated(note = "use `Project::compiler` instead")]
+    pub fn solc(&self) -> &C {
+        &self.compiler
+    }
+
+    /// Returns the settings of the project together with the settings of all its overrides
+    pub(crate) fn compiler_profiles(&self) -> Result<CompilerProfiles> {
+        CompilerProfiles::new(self.root(), &self.solc_config, &self.compiler_overrides)
+    }
+
     /// Returns the handler that takes care of processing all artifacts
     pub fn artifacts_handler(&self) -> &T {
         &self.artifacts
@@ -171,52 +274,68 @@ impl<T: ArtifactOutput> Project<T> {
         SolFilesCache::read_joined(&self.paths)
     }
 
-    /// Applies the configured arguments to the given `Solc`
+    /// Returns all artifacts and build infos in the artifacts dir that aren't referenced by the
+    /// existing cache anymore, without deleting them.
     ///
-    /// See [Self::configure_solc_with_version()]
-    pub(crate) fn configure_solc(&self, solc: Solc) -> Solc {
-        let version = solc.version().ok();
-        self.configure_solc_with_version(solc, version, Default::default())
+    /// Returns nothing if caching is disabled or there's no cache file yet.
+    ///
+    /// See also [SolFilesCache::orphaned_files()]
+    pub fn orphaned_files(&self) -> Result<cache::OrphanedFiles> {
+        if !self.cached || !self.cache_path().exists() {
+            return Ok(Default::default());
+        }
+        Ok(self.read_cache_file()?.orphaned_files(&self.paths))
     }
 
-    /// Applies the configured arguments to the given `Solc`
+    /// Deletes all artifacts and build infos that aren't referenced by the existing cache anymore,
+    /// like the artifacts of deleted or renamed files, and returns them.
     ///
-    /// This will set the `--allow-paths` to the paths configured for the `Project`, if any.
+    /// Use [`Self::orphaned_files()`] to list them without deleting anything.
     ///
-    /// If a version is provided and it is applicable it will also set `--base-path` and
-    /// `--include-path` This will set the `--allow-paths` to the paths configured for the
-    /// `Project`, if any.
-    /// This also accepts additional `include_paths`
-    pub(crate) fn configure_solc_with_version(
-        &self,
-        mut solc: Solc,
-        version: Option<Version>,
-        mut include_paths: IncludePaths,
-    ) -> Solc {
-        if !solc.args.iter().any(|arg| arg == "--allow-paths") {
-            if let Some([allow, libs]) = self.allowed_paths.args() {
-                solc = solc.arg(allow).arg(libs);
-            }
-        }
-        if let Some(version) = version {
-            if SUPPORTS_BASE_PATH.matches(&version) {
-                let base_path = format!("{}", self.root().display());
-                if !base_path.is_empty() {
-                    solc = solc.with_base_path(self.root());
-                    if SUPPORTS_INCLUDE_PATH.matches(&version) {
-                        include_paths.extend(self.include_paths.paths().cloned());
-                        // `--base-path` and `--include-path` conflict if set to the same path, so
-                        // as a precaution, we ensure here that the `--base-path` is not also used
-                        // for `--include-path`
-                        include_paths.remove(self.root());
-                        solc = solc.args(include_paths.args());
-                    }
-                }
-            } else {
-                solc.base_path.take();
-            }
+    /// # Example
+    ///
+    /// ```no_run
+    /// use foundry_compilers::Project;
+    ///
+    /// let project = Project::builder().build()?;
+    /// let removed = project.gc()?;
+    /// for file in removed.files() {
+    ///     println!("removed {}", file.display());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// This holds the project's [`BuildLock`] while the files are collected and deleted, so that
+    /// it doesn't delete the artifacts of a concurrent build that weren't cached yet.
+    pub fn gc(&self) -> Result<cache::OrphanedFiles> {
+        if !self.cached || !self.cache_path().exists() {
+            return Ok(Default::default());
         }
-        solc
+        let _lock = self.acquire_build_lock()?;
+        self.gc_locked()
+    }
+
+    /// Deletes the orphaned files, the caller must hold the project's [`BuildLock`]
+    pub(crate) fn gc_locked(&self) -> Result<cache::OrphanedFiles> {
+        let orphaned = self.orphaned_files()?;
+        orphaned.remove(self.artifacts_path())?;
+        Ok(orphaned)
+    }
+
+    /// Returns all files and artifacts of the existing cache that would be recompiled if the
+    /// given files changed.
+    ///
+    /// See also [SolFilesCache::impact()]
+    pub fn impact<I, P>(&self, files: I) -> Result<cache::Impact>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let cache = self.read_cache_file()?;
+        let files = files
+            .into_iter()
+            .map(|file| utils::source_name(file.as_ref(), self.root()).to_path_buf());
+        Ok(cache.impact(files))
     }
 
     /// Sets the maximum number of parallel `solc` processes to run simultaneously.
@@ -281,7 +400,7 @@ impl<T: ArtifactOutput> Project<T> {
     /// ```
     #[instrument(skip_all, name = "compile")]
     pub fn compile(&self) -> Result<ProjectCompileOutput<T>> {
-        let sources = self.paths.read_input_files()?;
+        let sources = self.overlay.read_input_files(&self.paths)?;
         trace!("found {} sources to compile: {:?}", sources.len(), sources.keys());
 
         #[cfg(feature = "svm-solc")]
@@ -290,14 +409,14 @@ impl<T: ArtifactOutput> Project<T> {
             return self.svm_compile(sources);
         }
 
-        self.compile_with_version(&self.solc, sources)
+        self.compile_with_version(&self.compiler, sources)
     }
 
     /// Compiles a set of contracts using `svm` managed solc installs
     ///
     /// This will autodetect the appropriate `Solc` version(s) to use when compiling the provided
-    /// `Sources`. Solc auto-detection follows semver rules, see also
-    /// `Graph::get_input_node_versions`
+    /// `Sources`. Solc auto-detection follows semver rules and the configured
+    /// [`VersionSelectionStrategy`], see also [`Self::select_versions()`]
     ///
     /// # Errors
     ///
@@ -336,7 +455,7 @@ impl<T: ArtifactOutput> Project<T> {
     #[cfg(feature = "svm-solc")]
     pub fn compile_file(&self, file: impl Into<PathBuf>) -> Result<ProjectCompileOutput<T>> {
         let file = file.into();
-        let source = Source::read(&file)?;
+        let source = self.overlay.read(&file)?;
         project::ProjectCompiler::with_sources(self, Sources::from([(file, source)]))?.compile()
     }
 
@@ -357,15 +476,14 @@ impl<T: ArtifactOutput> Project<T> {
         I: IntoIterator<Item = P>,
         P: Into<PathBuf>,
     {
-        let sources = Source::read_all(files)?;
+        let sources = self.overlay.read_all(files)?;
 
         #[cfg(feature = "svm-solc")]
         if self.auto_detect {
             return project::ProjectCompiler::with_sources(self, sources)?.compile();
         }
 
-        let solc = self.configure_solc(self.solc.clone());
-        self.compile_with_version(&solc, sources)
+        self.compile_with_version(&self.compiler, sources)
     }
 
     /// Convenience function to compile only files that match the provided [FileFilter].
@@ -396,8 +514,9 @@ impl<T: ArtifactOutput> Project<T> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn compile_sparse(&self, filter: Box<dyn FileFilter>) -> Result<ProjectCompileOutput<T>> {
-        let sources =
-            Source::read_all(self.paths.input_files().into_iter().filter(|p| filter.is_match(p)))?;
+        let sources = self.overlay.read_all(
+            self.overlay.input_files(&self.paths).into_iter().filter(|p| filter.is_match(p)),
+        )?;
 
         #[cfg(feature = "svm-solc")]
         if self.auto_detect {
@@ -406,12 +525,12 @@ impl<T: ArtifactOutput> Project<T> {
                 .compile();
         }
 
-        project::ProjectCompiler::with_sources_and_solc(self, sources, self.solc.clone())?
+        project::ProjectCompiler::with_sources_and_compiler(self, sources, self.compiler.clone())?
             .with_sparse_output(filter)
             .compile()
     }
 
-    /// Compiles the given source files with the exact `Solc` executable
+    /// Compiles the given source files with the exact compiler executable
     ///
     /// First all libraries for the sources are resolved by scanning all their imports.
     /// If caching is enabled for the `Project`, then all unchanged files are filtered from the
@@ -432,15 +551,23 @@ impl<T: ArtifactOutput> Project<T> {
     /// ```
     pub fn compile_with_version(
         &self,
-        solc: &Solc,
+        compiler: &C,
         sources: Sources,
     ) -> Result<ProjectCompileOutput<T>> {
-        project::ProjectCompiler::with_sources_and_solc(self, sources, solc.clone())?.compile()
+        project::ProjectCompiler::with_sources_and_compiler(self, sources, compiler.clone())?
+            .compile()
     }
 
     /// Removes the project's artifacts and cache file
     ///
-    /// If the cache file was the only file in the folder, this also removes the empty folder.
+    /// This holds the project's [`BuildLock`] while the files are removed. If the cache file and
+    /// the lock file were the only files in the folder, this also removes the lock file and the
+    /// empty folder.
+    ///
+    /// If the lock can't be acquired because another build holds it for longer than
+    /// [`Self::build_lock_timeout`] or the [`Self::cancellation_token`] was cancelled, this fails
+    /// with an [`io::ErrorKind::TimedOut`] or [`io::ErrorKind::Interrupted`] error of the lock file
+    /// that wraps the [`SolcError`].
     ///
     /// # Examples
     ///
@@ -459,17 +586,48 @@ impl<T: ArtifactOutput> Project<T> {
     /// ```
     pub fn cleanup(&self) -> std::result::Result<(), SolcIoError> {
         trace!("clean up project");
+        let lock_path = self.build_lock_path();
+        // only wait for builds that wrote to the cache dir
+        let mut lock = if lock_path.parent().map_or(false, Path::exists) {
+            Some(self.acquire_build_lock().map_err(|err| {
+                let kind = match err {
+                    SolcError::Io(err) => return err,
+                    SolcError::BuildLockTimeout { .. } => io::ErrorKind::TimedOut,
+                    _ => io::ErrorKind::Interrupted,
+                };
+                SolcIoError::new(io::Error::new(kind, err), lock_path.clone())
+            })?)
+        } else {
+            None
+        };
+        let imports_cache_dir = self.paths.imports_cache_dir();
+        if imports_cache_dir.exists() {
+            std::fs::remove_dir_all(&imports_cache_dir)
+                .map_err(|err| SolcIoError::new(err, imports_cache_dir.clone()))?;
+            trace!("removed imports cache dir \"{}\"", imports_cache_dir.display());
+        }
         if self.cache_path().exists() {
             std::fs::remove_file(self.cache_path())
                 .map_err(|err| SolcIoError::new(err, self.cache_path()))?;
+            for file in [self.binary_cache_path(), self.artifact_index_path()] {
+                if file.exists() {
+                    std::fs::remove_file(&file)
+                        .map_err(|err| SolcIoError::new(err, file.clone()))?;
+                }
+            }
             if let Some(cache_folder) = self.cache_path().parent() {
-                // remove the cache folder if the cache file was the only file
-                if cache_folder
+                // remove the cache folder if the cache file was the only file, besides the lock
+                let is_empty = cache_folder
                     .read_dir()
                     .map_err(|err| SolcIoError::new(err, cache_folder))?
-                    .next()
-                    .is_none()
-                {
+                    .filter_map(|entry| entry.ok())
+                    .all(|entry| entry.path() == lock_path);
+                if is_empty {
+                    drop(lock.take());
+                    if lock_path.exists() {
+                        std::fs::remove_file(&lock_path)
+                            .map_err(|err| SolcIoError::new(err, lock_path.clone()))?;
+                    }
                     std::fs::remove_dir(cache_folder)
                         .map_err(|err| SolcIoError::new(err, cache_folder))?;
                 }
@@ -496,6 +654,19 @@ impl<T: ArtifactOutput> Project<T> {
         self.paths.flatten(target)
     }
 
+    /// Returns the solc versions the [`VersionSelectionStrategy`] of the project selects for the
+    /// input files, together with the reason for each choice
+    #[cfg(feature = "svm-solc")]
+    pub fn select_versions(&self) -> Result<resolver::VersionSelection> {
+        let graph = Graph::resolve_with_overlay(&self.paths, &self.overlay)?;
+        graph.select_versions(
+            &self.compiler,
+            self.offline,
+            &self.version_selection,
+            &self.compiler_overrides,
+        )
+    }
+
     /// Returns standard-json-input to compile the target contract
     pub fn standard_json_input(
         &self,
@@ -503,7 +674,7 @@ impl<T: ArtifactOutput> Project<T> {
     ) -> Result<StandardJsonCompilerInput> {
         let target = target.as_ref();
         trace!("Building standard-json-input for {:?}", target);
-        let graph = Graph::resolve(&self.paths)?;
+        let graph = Graph::resolve_with_overlay(&self.paths, &self.overlay)?;
         let target_index = graph.files().get(target).ok_or_else(|| {
             SolcError::msg(format!("cannot resolve file at {:?}", target.display()))
         })?;
@@ -542,17 +713,69 @@ impl<T: ArtifactOutput> Project<T> {
     }
 }
 
-pub struct ProjectBuilder<T: ArtifactOutput = ConfigurableArtifacts> {
+impl<T: ArtifactOutput> Project<T, Solc> {
+    /// Applies the configured arguments to the given `Solc`
+    ///
+    /// This will set the `--allow-paths` to the paths configured for the `Project`, if any.
+    ///
+    /// If a version is provided and it is applicable it will also set `--base-path` and
+    /// `--include-path` This will set the `--allow-paths` to the paths configured for the
+    /// `Project`, if any.
+    /// This also accepts additional `include_paths`
+    pub(crate) fn configure_solc_with_version(
+        &self,
+        mut solc: Solc,
+        version: Option<Version>,
+        mut include_paths: IncludePaths,
+    ) -> Solc {
+        if !solc.args.iter().any(|arg| arg == "--allow-paths") {
+            if let Some([allow, libs]) = self.allowed_paths.args() {
+                solc = solc.arg(allow).arg(libs);
+            }
+        }
+        if let Some(version) = version {
+            if SUPPORTS_BASE_PATH.matches(&version) {
+                let base_path = format!("{}", self.root().display());
+                if !base_path.is_empty() {
+                    solc = solc.with_base_path(self.root());
+                    if SUPPORTS_INCLUDE_PATH.matches(&version) {
+                        include_paths.extend(self.include_paths.paths().cloned());
+                        // `--base-path` and `--include-path` conflict if set to the same path, so
+                        // as a precaution, we ensure here that the `--base-path` is not also used
+                        // for `--include-path`
+                        include_paths.remove(self.root());
+                        solc = solc.args(include_paths.args());
+                    }
+                }
+            } else {
+                solc.base_path.take();
+            }
+        }
+        solc
+    }
+}
+
+pub struct ProjectBuilder<T: ArtifactOutput = ConfigurableArtifacts, C: Compiler = Solc> {
     /// The layout of the
     paths: Option<ProjectPathsConfig>,
-    /// Where to find solc
-    solc: Option<Solc>,
+    /// The compiler to use if auto detection is disabled
+    compiler: Option<C>,
+    /// The compiler for vyper sources
+    vyper: Option<Vyper>,
     /// How solc invocation should be configured.
     solc_config: Option<SolcConfig>,
+    /// Overrides of the solc version and settings for files that match a glob
+    compiler_overrides: Vec<CompilerOverride>,
     /// Whether caching is enabled, default is true.
     cached: bool,
     /// Whether to output build information with each solc call.
     build_info: bool,
+    /// Whether orphaned files are deleted after compiling, default is false.
+    auto_gc: bool,
+    /// How the cache file is written, default is json.
+    cache_format: CacheFormat,
+    /// Whether unchanged modification dates and sizes skip hashing sources, default is false.
+    trust_mtime: bool,
     /// Whether writing artifacts to disk is enabled, default is true.
     no_artifacts: bool,
     /// Whether automatic solc version detection is enabled
@@ -572,17 +795,40 @@ pub struct ProjectBuilder<T: ArtifactOutput = ConfigurableArtifacts> {
     /// Paths to use for solc's `--include-path`
     include_paths: IncludePaths,
     solc_jobs: Option<usize>,
+    /// The maximum time a single `solc` invocation may take
+    solc_timeout: Option<Duration>,
+    /// Cancels the compilation
+    cancellation_token: Option<CancellationToken>,
+    /// The maximum time to wait for the build lock
+    build_lock_timeout: Option<Duration>,
+    /// The compiler output cache shared with other projects
+    shared_cache: Option<SharedCache>,
+    /// The remote compiler output cache
+    remote_cache: Option<Arc<dyn RemoteCache>>,
+    /// How import cycles between source files are reported
+    import_cycles: Option<Severity>,
+    /// How duplicate contract names in the sources dir are reported
+    duplicate_contracts: Option<Severity>,
+    /// How solc versions are selected
+    version_selection: VersionSelectionStrategy,
+    /// The contents of files that take precedence over the files on disk
+    overlay: SourceOverlay,
 }
 
-impl<T: ArtifactOutput> ProjectBuilder<T> {
+impl<T: ArtifactOutput, C: Compiler> ProjectBuilder<T, C> {
     /// Create a new builder with the given artifacts handler
     pub fn new(artifacts: T) -> Self {
         Self {
             paths: None,
-            solc: None,
+            compiler: None,
+            vyper: None,
             solc_config: None,
+            compiler_overrides: Vec::new(),
             cached: true,
             build_info: false,
+            auto_gc: false,
+            cache_format: CacheFormat::Json,
+            trust_mtime: false,
             no_artifacts: false,
             auto_detect: true,
             offline: false,
@@ -593,6 +839,15 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
             allowed_paths: Default::default(),
             include_paths: Default::default(),
             solc_jobs: None,
+            solc_timeout: None,
+            cancellation_token: None,
+            build_lock_timeout: None,
+            shared_cache: None,
+            remote_cache: None,
+            import_cycles: None,
+            duplicate_contracts: None,
+            version_selection: Default::default(),
+            overlay: Default::default(),
         }
     }
 
@@ -603,14 +858,17 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
     }
 
     #[must_use]
-    pub fn solc(mut self, solc: impl Into<Solc>) -> Self {
-        self.solc = Some(solc.into());
+    pub fn solc_config(mut self, solc_config: SolcConfig) -> Self {
+        self.solc_config = Some(solc_config);
         self
     }
 
+    /// Adds an override of the solc version and settings for all files that match its glob.
+    ///
+    /// If multiple overrides match a file, the first one applies.
     #[must_use]
-    pub fn solc_config(mut self, solc_config: SolcConfig) -> Self {
-        self.solc_config = Some(solc_config);
+    pub fn compiler_override(mut self, compiler_override: CompilerOverride) -> Self {
+        self.compiler_overrides.push(compiler_override);
         self
     }
 
@@ -654,9 +912,33 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
         self
     }
 
+    /// Sets the format the cache file is written in, see [`CacheFormat`]
+    #[must_use]
+    pub fn cache_format(mut self, format: CacheFormat) -> Self {
+        self.cache_format = format;
+        self
+    }
+
+    /// Sets whether the content hashes of sources whose last modification date and size didn't
+    /// change are reused instead of hashing the sources again, see [`Project::trust_mtime`]
+    #[must_use]
+    pub fn set_trust_mtime(mut self, trust_mtime: bool) -> Self {
+        self.trust_mtime = trust_mtime;
+        self
+    }
+
+    /// Sets whether orphaned artifacts and build infos are deleted after every compilation that
+    /// writes the cache, see [`Project::gc()`]
+    #[must_use]
+    pub fn set_auto_gc(mut self, auto_gc: bool) -> Self {
+        self.auto_gc = auto_gc;
+        self
+    }
+
     /// Activates offline mode
     ///
-    /// Prevents network possible access to download/check solc installs
+    /// Prevents network possible access to download/check solc installs and to fetch imports with
+    /// the [`ProjectPathsConfig::import_resolvers`]
     #[must_use]
     pub fn offline(self) -> Self {
         self.set_offline(true)
@@ -722,12 +1004,102 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
         self.solc_jobs(1)
     }
 
+    /// Sets the maximum time a single `solc` invocation may take before it's killed
+    #[must_use]
+    pub fn solc_timeout(mut self, timeout: Duration) -> Self {
+        self.solc_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the token to cancel the compilation with
+    ///
+    /// Once cancelled, running `solc` processes are killed and no further compiler jobs are
+    /// started.
+    #[must_use]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Sets the maximum time to wait for another build of the project to release the
+    /// [`BuildLock`], the compilation fails with [`SolcError::BuildLockTimeout`] afterwards
+    #[must_use]
+    pub fn build_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.build_lock_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the [`SharedCache`] to look up compiler outputs in before invoking the compiler
+    #[must_use]
+    pub fn shared_cache(mut self, cache: SharedCache) -> Self {
+        self.shared_cache = Some(cache);
+        self
+    }
+
+    /// Sets the [`RemoteCache`] to look up compiler outputs in before invoking the compiler.
+    ///
+    /// The remote cache is best effort: if it can't be reached, the compiler is invoked as usual.
+    #[must_use]
+    pub fn remote_cache(mut self, cache: impl RemoteCache + 'static) -> Self {
+        self.remote_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Checks the files in the sources dir for import cycles before compiling and reports them
+    /// with the given severity via [`Reporter::on_import_cycles()`].
+    ///
+    /// Cycles that include library files are ignored. With [`Severity::Error`] the compilation
+    /// fails with [`SolcError::ImportCycles`].
+    ///
+    /// [`Reporter::on_import_cycles()`]: crate::report::Reporter::on_import_cycles
+    #[must_use]
+    pub fn import_cycles(mut self, severity: Severity) -> Self {
+        self.import_cycles = Some(severity);
+        self
+    }
+
+    /// Checks the files in the sources dir for contracts, interfaces and libraries with the same
+    /// name before compiling and reports them with the given severity via
+    /// [`Reporter::on_duplicate_contracts()`].
+    ///
+    /// Only names that are declared in multiple source files are considered. With
+    /// [`Severity::Error`] the compilation fails with [`SolcError::DuplicateContracts`].
+    ///
+    /// [`Reporter::on_duplicate_contracts()`]: crate::report::Reporter::on_duplicate_contracts
+    #[must_use]
+    pub fn duplicate_contracts(mut self, severity: Severity) -> Self {
+        self.duplicate_contracts = Some(severity);
+        self
+    }
+
+    /// Sets the strategy that selects the solc versions of the sources if they're auto detected,
+    /// see [`Project::select_versions()`] to inspect the selected versions
+    #[must_use]
+    pub fn version_selection(mut self, strategy: VersionSelectionStrategy) -> Self {
+        self.version_selection = strategy;
+        self
+    }
+
+    /// Sets the contents of files that take precedence over the files on disk, like the unsaved
+    /// buffers of an editor.
+    ///
+    /// The files of the overlay are used for resolving imports and compiling, but neither the
+    /// artifacts nor the cache entries of the overlaid files and the files that import them are
+    /// written.
+    #[must_use]
+    pub fn overlay(mut self, overlay: SourceOverlay) -> Self {
+        self.overlay = overlay;
+        self
+    }
+
     /// Set arbitrary `ArtifactOutputHandler`
-    pub fn artifacts<A: ArtifactOutput>(self, artifacts: A) -> ProjectBuilder<A> {
+    pub fn artifacts<A: ArtifactOutput>(self, artifacts: A) -> ProjectBuilder<A, C> {
         let ProjectBuilder {
             paths,
-            solc,
+            compiler,
+            vyper,
             solc_config,
+            compiler_overrides,
             cached,
             no_artifacts,
             auto_detect,
@@ -736,6 +1108,18 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
             allowed_paths,
             include_paths,
             solc_jobs,
+            solc_timeout,
+            cancellation_token,
+            build_lock_timeout,
+            shared_cache,
+            remote_cache,
+            import_cycles,
+            duplicate_contracts,
+            auto_gc,
+            cache_format,
+            trust_mtime,
+            version_selection,
+            overlay,
             offline,
             build_info,
             slash_paths,
@@ -743,8 +1127,10 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
         } = self;
         ProjectBuilder {
             paths,
-            solc,
+            compiler,
+            vyper,
             solc_config,
+            compiler_overrides,
             cached,
             no_artifacts,
             auto_detect,
@@ -756,6 +1142,18 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
             allowed_paths,
             include_paths,
             solc_jobs,
+            solc_timeout,
+            cancellation_token,
+            build_lock_timeout,
+            shared_cache,
+            remote_cache,
+            import_cycles,
+            duplicate_contracts,
+            auto_gc,
+            cache_format,
+            trust_mtime,
+            version_selection,
+            overlay,
             build_info,
         }
     }
@@ -800,11 +1198,98 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
         self
     }
 
-    pub fn build(self) -> Result<Project<T>> {
+    /// Sets the [`Vyper`] compiler to use for vyper sources (`.vy`, `.vyi`)
+    #[must_use]
+    pub fn vyper(mut self, vyper: impl Into<Vyper>) -> Self {
+        self.vyper = Some(vyper.into());
+        self
+    }
+
+    /// Sets the [`Solc`] to use, see [`Self::compiler()`]
+    #[must_use]
+    pub fn solc(self, solc: impl Into<Solc>) -> ProjectBuilder<T, Solc> {
+        self.compiler(solc.into())
+    }
+
+    /// Sets the [`Compiler`] to use
+    ///
+    /// Note: the compiler is only used as is if automatic version detection is disabled, see
+    /// [`Self::no_auto_detect()`]
+    pub fn compiler<D: Compiler>(self, compiler: D) -> ProjectBuilder<T, D> {
+        let ProjectBuilder {
+            paths,
+            vyper,
+            solc_config,
+            compiler_overrides,
+            cached,
+            no_artifacts,
+            auto_detect,
+            artifacts,
+            ignored_error_codes,
+            compiler_severity_filter,
+            allowed_paths,
+            include_paths,
+            solc_jobs,
+            solc_timeout,
+            cancellation_token,
+            build_lock_timeout,
+            shared_cache,
+            remote_cache,
+            import_cycles,
+            duplicate_contracts,
+            auto_gc,
+            cache_format,
+            trust_mtime,
+            version_selection,
+            overlay,
+            offline,
+            build_info,
+            slash_paths,
+            ..
+        } = self;
+        ProjectBuilder {
+            paths,
+            compiler: Some(compiler),
+            vyper,
+            solc_config,
+            compiler_overrides,
+            cached,
+            no_artifacts,
+            auto_detect,
+            offline,
+            slash_paths,
+            artifacts,
+            ignored_error_codes,
+            compiler_severity_filter,
+            allowed_paths,
+            include_paths,
+            solc_jobs,
+            solc_timeout,
+            cancellation_token,
+            build_lock_timeout,
+            shared_cache,
+            remote_cache,
+            import_cycles,
+            duplicate_contracts,
+            auto_gc,
+            cache_format,
+            trust_mtime,
+            version_selection,
+            overlay,
+            build_info,
+        }
+    }
+
+    pub fn build(self) -> Result<Project<T, C>>
+    where
+        C: Default,
+    {
         let Self {
             paths,
-            solc,
+            compiler,
+            vyper,
             solc_config,
+            compiler_overrides,
             cached,
             no_artifacts,
             auto_detect,
@@ -814,28 +1299,46 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
             mut allowed_paths,
             include_paths,
             solc_jobs,
+            solc_timeout,
+            cancellation_token,
+            build_lock_timeout,
+            shared_cache,
+            remote_cache,
+            import_cycles,
+            duplicate_contracts,
+            auto_gc,
+            cache_format,
+            trust_mtime,
+            version_selection,
+            overlay,
             offline,
             build_info,
             slash_paths,
         } = self;
 
         let mut paths = paths.map(Ok).unwrap_or_else(ProjectPathsConfig::current_hardhat)?;
+        // fetched imports are only resolved from the imports cache
+        paths.offline |= offline;
 
         if slash_paths {
             // ensures we always use `/` paths
             paths.slash_paths();
         }
 
-        let solc = solc.unwrap_or_default();
+        let compiler = compiler.unwrap_or_default();
         let solc_config = solc_config.unwrap_or_else(|| SolcConfig::builder().build());
+        // make sure all settings patches apply
+        CompilerProfiles::new(&paths.root, &solc_config, &compiler_overrides)?;
 
         // allow every contract under root by default
         allowed_paths.insert(paths.root.clone());
 
         Ok(Project {
             paths,
-            solc,
+            compiler,
+            vyper,
             solc_config,
+            compiler_overrides,
             cached,
             build_info,
             no_artifacts,
@@ -848,19 +1351,31 @@ impl<T: ArtifactOutput> ProjectBuilder<T> {
             solc_jobs: solc_jobs
                 .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
                 .unwrap_or(1),
+            solc_timeout,
+            cancellation_token,
+            build_lock_timeout,
+            shared_cache,
+            remote_cache,
+            import_cycles,
+            duplicate_contracts,
+            auto_gc,
+            cache_format,
+            trust_mtime,
+            version_selection,
+            overlay,
             offline,
             slash_paths,
         })
     }
 }
 
-impl<T: ArtifactOutput + Default> Default for ProjectBuilder<T> {
+impl<T: ArtifactOutput + Default, C: Compiler> Default for ProjectBuilder<T, C> {
     fn default() -> Self {
         Self::new(T::default())
     }
 }
 
-impl<T: ArtifactOutput> ArtifactOutput for Project<T> {
+impl<T: ArtifactOutput, C: Compiler> ArtifactOutput for Project<T, C> {
     type Artifact = T::Artifact;
 
     fn on_output(
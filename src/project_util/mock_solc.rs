@@ -0,0 +1,414 @@
+//! An in-process `solc` replacement for deterministic tests, see [`MockSolc`]
+
+use crate::{
+    artifacts::{
+        contract::Contract, error::SourceLocation, output_selection::FileOutputSelection, Bytecode,
+        BytecodeObject, DeployedBytecode, Error, Evm, Settings, Severity, SourceFile, Sources,
+    },
+    compilers::{solc::create_solc_inputs, Compiler, CompilerVersion},
+    error::Result,
+    CompilerInput, CompilerOutput, InterruptOptions, ProjectPathsConfig, Solc, Source,
+};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{keccak256, Bytes};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use semver::{Version, VersionReq};
+use std::path::{Path, PathBuf};
+
+/// The latest patch version of every minor solc release [`MockSolc`] pretends to be available
+const MOCK_SOLC_RELEASES: &[(u64, u64)] = &[(4, 26), (5, 17), (6, 12), (7, 6), (8, 24)];
+
+/// The common `PUSH1 0x80 PUSH1 0x40 MSTORE` prefix of all mocked bytecode
+const BYTECODE_PREFIX: [u8; 5] = [0x60, 0x80, 0x60, 0x40, 0x52];
+
+/// Matches all contract, interface and library definitions of a source file
+static RE_CONTRACT_NAMES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*(?:abstract\s+)?(?:contract|interface|library)\s+(?P<name>\w+)").unwrap()
+});
+
+/// A diagnostic [`MockSolc`] emits whenever the `file` is compiled
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MockDiagnostic {
+    /// The file the diagnostic belongs to, either absolute or relative to the project root
+    pub file: PathBuf,
+    pub severity: Severity,
+    pub error_code: Option<u64>,
+    pub message: String,
+}
+
+impl MockDiagnostic {
+    /// Creates a new error diagnostic for the given file
+    pub fn error(file: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        Self {
+            file: file.into(),
+            severity: Severity::Error,
+            error_code: None,
+            message: message.into(),
+        }
+    }
+
+    /// Creates a new warning diagnostic with the given error code for the given file
+    pub fn warning(file: impl Into<PathBuf>, error_code: u64, message: impl Into<String>) -> Self {
+        Self {
+            file: file.into(),
+            severity: Severity::Warning,
+            error_code: Some(error_code),
+            message: message.into(),
+        }
+    }
+
+    /// Whether this diagnostic belongs to the given source file of a [`CompilerInput`]
+    fn matches(&self, file: &Path) -> bool {
+        self.file.ends_with(file)
+    }
+
+    fn to_error(&self, file: &Path) -> Error {
+        let r#type = match self.severity {
+            Severity::Error => "TypeError",
+            Severity::Warning => "Warning",
+            Severity::Info => "Info",
+        };
+        let file = file.display().to_string();
+        Error {
+            formatted_message: Some(format!("{type}: {}\n --> {file}\n", self.message)),
+            source_location: Some(SourceLocation { file, start: 0, end: 0 }),
+            secondary_source_locations: Vec::new(),
+            r#type: r#type.to_string(),
+            component: "general".to_string(),
+            severity: self.severity,
+            error_code: self.error_code,
+            message: self.message.clone(),
+        }
+    }
+}
+
+/// A mock of `solc` that compiles a [`CompilerInput`] in process without any `solc` binary.
+///
+/// The output is deterministic and plausible enough to drive the entire compile pipeline,
+/// including caching, sparse output and writing artifacts:
+///   - every `contract`, `interface` and `library` of a source file produces a contract with an
+///     empty ABI and fake bytecode derived from the content hash of the file and the contract name
+///   - files pruned by the output selection produce no contracts, like with `solc`
+///   - all configured [`MockDiagnostic`]s of the compiled files are emitted, if one of them is an
+///     error no contracts are produced at all
+///
+/// # Examples
+///
+/// ```
+/// use foundry_compilers::{
+///     project_util::{mock_solc::MockSolc, TempProject},
+///     ConfigurableArtifacts, ProjectPathsConfig,
+/// };
+///
+/// let project = TempProject::with_compiler(
+///     ProjectPathsConfig::builder(),
+///     ConfigurableArtifacts::default(),
+///     MockSolc::default(),
+/// )?;
+/// project.add_source("Counter", "pragma solidity ^0.8.10;\ncontract Counter {}")?;
+///
+/// let compiled = project.compile()?;
+/// assert!(compiled.find_first("Counter").is_some());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MockSolc {
+    /// The version this compiler reports
+    pub version: Version,
+    /// The diagnostics to emit
+    pub diagnostics: Vec<MockDiagnostic>,
+}
+
+impl Default for MockSolc {
+    fn default() -> Self {
+        let (minor, patch) = MOCK_SOLC_RELEASES[MOCK_SOLC_RELEASES.len() - 1];
+        Self::new(Version::new(0, minor, patch))
+    }
+}
+
+impl MockSolc {
+    /// Creates a new mock that reports the given version
+    pub fn new(version: Version) -> Self {
+        Self { version, diagnostics: Vec::new() }
+    }
+
+    /// Adds a diagnostic to emit whenever its file is compiled
+    #[must_use]
+    pub fn with_diagnostic(mut self, diagnostic: MockDiagnostic) -> Self {
+        self.diagnostics.push(diagnostic);
+        self
+    }
+
+    /// Compiles the input, see [`MockSolc`]
+    pub fn compile(&self, input: &CompilerInput) -> CompilerOutput {
+        let mut output = CompilerOutput::default();
+
+        for file in input.sources.keys() {
+            output.errors.extend(
+                self.diagnostics.iter().filter(|d| d.matches(file)).map(|d| d.to_error(file)),
+            );
+        }
+        let has_error = output.errors.iter().any(|err| err.severity.is_error());
+
+        for (id, (file, source)) in input.sources.iter().enumerate() {
+            let name = file.display().to_string();
+            output.sources.insert(name.clone(), SourceFile { id: id as u32, ast: None });
+
+            if has_error || !is_selected(&input.settings, &name) {
+                continue;
+            }
+
+            let contracts = contract_names(&source.content)
+                .map(|contract| (contract.to_string(), mock_contract(source, contract)))
+                .collect();
+            output.contracts.insert(name, contracts);
+        }
+
+        output
+    }
+}
+
+impl Compiler for MockSolc {
+    type Input = CompilerInput;
+
+    type Settings = Settings;
+
+    type Output = CompilerOutput;
+
+    const NAME: &'static str = "mock-solc";
+
+    const FILE_EXTENSIONS: &'static [&'static str] = &["sol", "yul"];
+
+    fn version(&self) -> Result<Version> {
+        Ok(self.version.clone())
+    }
+
+    fn source_version_req(source: &Source) -> Option<VersionReq> {
+        Solc::source_version_req(source).ok()
+    }
+
+    /// All solc releases up to `0.8.24` are considered installed
+    fn available_versions(&self, _offline: bool) -> Vec<CompilerVersion> {
+        MOCK_SOLC_RELEASES
+            .iter()
+            .flat_map(|(minor, latest)| (0..=*latest).map(|patch| Version::new(0, *minor, patch)))
+            .map(CompilerVersion::Installed)
+            .collect()
+    }
+
+    /// The returned mock keeps the configured diagnostics
+    fn for_version(&self, version: &CompilerVersion, _offline: bool) -> Result<Self> {
+        Ok(Self { version: version.as_ref().clone(), ..self.clone() })
+    }
+
+    fn settings(
+        &self,
+        settings: &Settings,
+        _sources: &Sources,
+        _paths: &ProjectPathsConfig,
+    ) -> Self::Settings {
+        settings.clone()
+    }
+
+    fn create_inputs(
+        &self,
+        sources: Sources,
+        settings: &Self::Settings,
+        version: &Version,
+        paths: &ProjectPathsConfig,
+    ) -> Vec<Self::Input> {
+        create_solc_inputs(sources, settings, version, paths)
+    }
+
+    fn compile(
+        &self,
+        input: &Self::Input,
+        _interrupt: InterruptOptions<'_>,
+    ) -> Result<CompilerOutput> {
+        Ok(MockSolc::compile(self, input))
+    }
+}
+
+/// Returns the names of all contracts defined in the given source
+fn contract_names(content: &str) -> impl Iterator<Item = &str> {
+    RE_CONTRACT_NAMES.captures_iter(content).filter_map(|cap| cap.name("name")).map(|m| m.as_str())
+}
+
+/// Returns `true` if the output selection requests any contract output for the file
+fn is_selected(settings: &Settings, file: &str) -> bool {
+    let selection = settings.output_selection.as_ref();
+    selection
+        .get(file)
+        .or_else(|| selection.get("*"))
+        .map(|selection: &FileOutputSelection| {
+            selection.iter().any(|(contract, outputs)| !contract.is_empty() && !outputs.is_empty())
+        })
+        .unwrap_or_default()
+}
+
+/// Creates a contract with fake bytecode that changes with the content of its source file
+fn mock_contract(source: &Source, name: &str) -> Contract {
+    let hash = keccak256(format!("{}:{name}", source.content_hash()));
+    let deployed = Bytes::from([&BYTECODE_PREFIX[..], hash.as_slice()].concat());
+    let creation = Bytes::from([&BYTECODE_PREFIX[..], &deployed[..]].concat());
+
+    let to_bytecode = |object| Bytecode {
+        function_debug_data: Default::default(),
+        object: BytecodeObject::Bytecode(object),
+        opcodes: None,
+        source_map: None,
+        generated_sources: Vec::new(),
+        link_references: Default::default(),
+    };
+
+    Contract {
+        abi: Some(JsonAbi::default()),
+        metadata: None,
+        userdoc: Default::default(),
+        devdoc: Default::default(),
+        ir: None,
+        storage_layout: Default::default(),
+        evm: Some(Evm {
+            assembly: None,
+            legacy_assembly: None,
+            bytecode: Some(to_bytecode(creation)),
+            deployed_bytecode: Some(DeployedBytecode {
+                bytecode: Some(to_bytecode(deployed)),
+                immutable_references: Default::default(),
+            }),
+            method_identifiers: Default::default(),
+            gas_estimates: None,
+        }),
+        ewasm: None,
+        ir_optimized: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        project_util::TempProject, Artifact, ConfigurableArtifacts, ProjectPathsConfig,
+        TestFileFilter,
+    };
+
+    fn mocked_project(solc: MockSolc) -> TempProject<ConfigurableArtifacts, MockSolc> {
+        let project = TempProject::with_compiler(
+            ProjectPathsConfig::builder(),
+            ConfigurableArtifacts::default(),
+            solc,
+        )
+        .unwrap();
+        project
+            .add_source(
+                "A",
+                r#"
+pragma solidity ^0.8.10;
+import "./B.sol";
+contract A {}
+"#,
+            )
+            .unwrap();
+        project
+            .add_source(
+                "B",
+                r#"
+pragma solidity ^0.8.10;
+library B {}
+interface IB {}
+"#,
+            )
+            .unwrap();
+        project
+    }
+
+    #[test]
+    fn can_compile_with_mock_solc() {
+        let project = mocked_project(MockSolc::default());
+
+        let compiled = project.compile().unwrap();
+        compiled.assert_success();
+        for name in ["A", "B", "IB"] {
+            assert!(compiled.find_first(name).is_some(), "missing {name}");
+        }
+        let bytecode = compiled.find_first("A").unwrap().get_bytecode_bytes().unwrap().into_owned();
+
+        project.assert_unchanged();
+
+        // only the changed file and its importers are recompiled
+        project
+            .add_source(
+                "B",
+                r#"
+pragma solidity ^0.8.10;
+library B {}
+"#,
+            )
+            .unwrap();
+        let compiled = project.compile().unwrap();
+        compiled.assert_success();
+        assert!(compiled.find_first("IB").is_none());
+        assert_eq!(
+            compiled.find_first("A").unwrap().get_bytecode_bytes().unwrap().into_owned(),
+            bytecode
+        );
+        assert_eq!(compiled.compiled_artifacts().len(), 2);
+    }
+
+    #[test]
+    fn can_compile_sparse_with_mock_solc() {
+        let project = mocked_project(MockSolc::default());
+        project
+            .add_test(
+                "A.t",
+                r#"
+pragma solidity ^0.8.10;
+import "../src/A.sol";
+contract ATest {}
+"#,
+            )
+            .unwrap();
+
+        let compiled = project.compile_sparse(Box::<TestFileFilter>::default()).unwrap();
+        compiled.assert_success();
+        assert!(compiled.find_first("ATest").is_some());
+        assert!(compiled.find_first("A").is_none());
+        assert!(compiled.find_first("B").is_none());
+    }
+
+    #[test]
+    fn can_emit_mock_diagnostics() {
+        let project = mocked_project(MockSolc::default().with_diagnostic(MockDiagnostic::warning(
+            "src/B.sol",
+            1234,
+            "w",
+        )));
+        let compiled = project.compile().unwrap();
+        assert!(compiled.has_compiler_warnings());
+        assert!(!compiled.has_compiler_errors());
+
+        // the diagnostics are kept for the auto detected version
+        let mut project = mocked_project(
+            MockSolc::new(Version::new(0, 8, 10)).with_diagnostic(MockDiagnostic::warning(
+                "src/B.sol",
+                1234,
+                "w",
+            )),
+        );
+        project.project_mut().auto_detect = true;
+        let compiled = project.compile().unwrap();
+        assert!(compiled.has_compiler_warnings());
+
+        let mut project = mocked_project(
+            MockSolc::default().with_diagnostic(MockDiagnostic::error("src/B.sol", "failed")),
+        );
+        let compiled = project.compile().unwrap();
+        assert!(compiled.has_compiler_errors());
+        assert!(compiled.find_first("A").is_none());
+        assert!(!project.cache_path().exists());
+
+        project.project_mut().compiler.diagnostics.clear();
+        project.assert_no_errors().assert_unchanged();
+    }
+}
@@ -2,15 +2,19 @@
 
 use crate::{
     artifacts::Settings,
+    compilers::Compiler,
     config::ProjectPathsConfigBuilder,
     error::{Result, SolcError},
     hh::HardhatArtifacts,
-    project_util::mock::{MockProjectGenerator, MockProjectSettings},
+    project_util::{
+        mock::{MockProjectGenerator, MockProjectSettings},
+        mock_solc::MockSolc,
+    },
     remappings::Remapping,
     utils,
     utils::tempdir,
     Artifact, ArtifactOutput, Artifacts, ConfigurableArtifacts, ConfigurableContractArtifact,
-    FileFilter, PathStyle, Project, ProjectCompileOutput, ProjectPathsConfig, SolFilesCache,
+    FileFilter, PathStyle, Project, ProjectCompileOutput, ProjectPathsConfig, SolFilesCache, Solc,
     SolcIoError,
 };
 use fs_extra::{dir, file};
@@ -23,27 +27,22 @@ use std::{
 use tempfile::TempDir;
 
 pub mod mock;
+pub mod mock_solc;
 
 /// A [`Project`] wrapper that lives in a new temporary directory
 ///
 /// Once `TempProject` is dropped, the temp dir is automatically removed, see [`TempDir::drop()`]
-pub struct TempProject<T: ArtifactOutput = ConfigurableArtifacts> {
+///
+/// The project compiles with `solc` by default, [`mock_solc::MockSolc`] can be used instead to
+/// compile without any `solc` binaries.
+pub struct TempProject<T: ArtifactOutput = ConfigurableArtifacts, C: Compiler = Solc> {
     /// temporary workspace root
     _root: TempDir,
     /// actual project workspace with the `root` tempdir as its root
-    inner: Project<T>,
+    inner: Project<T, C>,
 }
 
 impl<T: ArtifactOutput> TempProject<T> {
-    /// Makes sure all resources are created
-    pub fn create_new(root: TempDir, inner: Project<T>) -> std::result::Result<Self, SolcIoError> {
-        let mut project = Self { _root: root, inner };
-        project.paths().create_all()?;
-        // ignore license warnings
-        project.inner.ignored_error_codes.push(1878);
-        Ok(project)
-    }
-
     /// Creates a new temp project using the provided paths and artifacts handler.
     /// sets the project root to a temp dir
     pub fn with_artifacts(paths: ProjectPathsConfigBuilder, artifacts: T) -> Result<Self> {
@@ -63,21 +62,58 @@ impl<T: ArtifactOutput> TempProject<T> {
         Ok(Self::create_new(tmp_dir, inner)?)
     }
 
-    /// Overwrites the settings to pass to `solc`
-    pub fn with_settings(mut self, settings: impl Into<Settings>) -> Self {
-        self.inner.solc_config.settings = settings.into();
-        self
-    }
-
     /// Explicitly sets the solc version for the project
     #[cfg(feature = "svm-solc")]
     pub fn set_solc(&mut self, solc: impl AsRef<str>) -> &mut Self {
-        self.inner.solc = crate::Solc::find_or_install_svm_version(solc).unwrap();
+        self.inner.compiler = crate::Solc::find_or_install_svm_version(solc).unwrap();
         self.inner.auto_detect = false;
         self
     }
+}
 
-    pub fn project(&self) -> &Project<T> {
+impl<T: ArtifactOutput, C: Compiler> TempProject<T, C> {
+    /// Makes sure all resources are created
+    pub fn create_new(
+        root: TempDir,
+        inner: Project<T, C>,
+    ) -> std::result::Result<Self, SolcIoError> {
+        let mut project = Self { _root: root, inner };
+        project.paths().create_all()?;
+        // ignore license warnings
+        project.inner.ignored_error_codes.push(1878);
+        Ok(project)
+    }
+
+    /// Creates a new temp project that always compiles with the given compiler, using the provided
+    /// paths and artifacts handler.
+    ///
+    /// Automatic version detection is disabled.
+    pub fn with_compiler(
+        paths: ProjectPathsConfigBuilder,
+        artifacts: T,
+        compiler: C,
+    ) -> Result<Self>
+    where
+        C: Default,
+    {
+        let tmp_dir = tempdir("temp-project")?;
+        let paths = paths.build_with_root(tmp_dir.path());
+        let inner = Project::builder()
+            .artifacts(artifacts)
+            .paths(paths)
+            .compiler(compiler)
+            .no_auto_detect()
+            .build()?;
+        Ok(Self::create_new(tmp_dir, inner)?)
+    }
+
+    /// Overwrites the settings to pass to `solc`
+    pub fn with_settings(mut self, settings: impl Into<Settings>) -> Self {
+        self.inner.solc_config.settings = settings.into();
+        self
+    }
+
+    pub fn project(&self) -> &Project<T, C> {
         &self.inner
     }
 
@@ -93,7 +129,7 @@ impl<T: ArtifactOutput> TempProject<T> {
         self.project().flatten(target)
     }
 
-    pub fn project_mut(&mut self) -> &mut Project<T> {
+    pub fn project_mut(&mut self) -> &mut Project<T, C> {
         &mut self.inner
     }
 
@@ -355,7 +391,7 @@ impl<T: ArtifactOutput + Default> TempProject<T> {
     }
 }
 
-impl<T: ArtifactOutput> fmt::Debug for TempProject<T> {
+impl<T: ArtifactOutput, C: Compiler> fmt::Debug for TempProject<T, C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TempProject").field("paths", self.paths()).finish()
     }
@@ -439,8 +475,20 @@ impl TempProject<ConfigurableArtifacts> {
     }
 }
 
-impl<T: ArtifactOutput> AsRef<Project<T>> for TempProject<T> {
-    fn as_ref(&self) -> &Project<T> {
+impl TempProject<ConfigurableArtifacts, MockSolc> {
+    /// Creates an empty new workspace in a new temporary dir that always compiles with the
+    /// default [`MockSolc`], see [`Self::with_compiler()`]
+    pub fn with_mock_solc() -> Result<Self> {
+        Self::with_compiler(
+            ProjectPathsConfig::builder(),
+            ConfigurableArtifacts::default(),
+            MockSolc::default(),
+        )
+    }
+}
+
+impl<T: ArtifactOutput, C: Compiler> AsRef<Project<T, C>> for TempProject<T, C> {
+    fn as_ref(&self) -> &Project<T, C> {
         self.project()
     }
 }
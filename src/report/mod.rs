@@ -14,11 +14,18 @@
 
 // <https://github.com/tokio-rs/tracing/blob/master/tracing-core/src/dispatch.rs>
 
-use crate::{remappings::Remapping, CompilerInput, CompilerOutput, Solc};
+use crate::{
+    artifacts::Severity,
+    cache::DirtyReason,
+    remappings::Remapping,
+    resolver::{DuplicateContract, UnresolvedImport},
+    CompilerInput, CompilerOutput, Solc,
+};
 use semver::Version;
 use std::{
     any::{Any, TypeId},
     cell::RefCell,
+    collections::BTreeMap,
     error::Error,
     fmt,
     path::{Path, PathBuf},
@@ -125,6 +132,61 @@ pub trait Reporter: 'static + std::fmt::Debug {
     ) {
     }
 
+    /// Callback invoked right before a [`Compiler`](crate::compilers::Compiler) other than [`Solc`]
+    /// is invoked, see also [`Self::on_solc_spawn()`]
+    fn on_compiler_spawn(
+        &self,
+        _compiler_name: &str,
+        _version: &Version,
+        _dirty_files: &[PathBuf],
+    ) {
+    }
+
+    /// Invoked if a [`Compiler`](crate::compilers::Compiler) other than [`Solc`] finished
+    /// successfully, see also [`Self::on_solc_success()`]
+    fn on_compiler_success(&self, _compiler_name: &str, _version: &Version, _duration: &Duration) {}
+
+    /// Invoked if the output of a compiler job was found in the
+    /// [`SharedCache`](crate::SharedCache), the compiler is not invoked for these `dirty_files`
+    fn on_shared_cache_hit(
+        &self,
+        _compiler_name: &str,
+        _version: &Version,
+        _dirty_files: &[PathBuf],
+    ) {
+    }
+
+    /// Invoked if the output of a compiler job was not found in the
+    /// [`SharedCache`](crate::SharedCache), right before the compiler is invoked
+    fn on_shared_cache_miss(
+        &self,
+        _compiler_name: &str,
+        _version: &Version,
+        _dirty_files: &[PathBuf],
+    ) {
+    }
+
+    /// Invoked if the output of a compiler job was found in the
+    /// [`RemoteCache`](crate::RemoteCache), the compiler is not invoked for these `dirty_files`
+    fn on_remote_cache_hit(
+        &self,
+        _compiler_name: &str,
+        _version: &Version,
+        _dirty_files: &[PathBuf],
+    ) {
+    }
+
+    /// Invoked if the output of a compiler job was not found in the
+    /// [`RemoteCache`](crate::RemoteCache) or the cache couldn't be reached, right before the
+    /// compiler is invoked
+    fn on_remote_cache_miss(
+        &self,
+        _compiler_name: &str,
+        _version: &Version,
+        _dirty_files: &[PathBuf],
+    ) {
+    }
+
     /// Invoked before a new [`Solc`] bin is installed
     fn on_solc_installation_start(&self, _version: &Version) {}
 
@@ -138,6 +200,38 @@ pub trait Reporter: 'static + std::fmt::Debug {
     /// list of all import paths and the file they occurred in: `(import stmt, file)`
     fn on_unresolved_imports(&self, _imports: &[(&Path, &Path)], _remappings: &[Remapping]) {}
 
+    /// Invoked if imports couldn't be resolved with the given remappings, together with
+    /// suggestions how to fix them
+    ///
+    /// By default this calls [`Self::on_unresolved_imports()`] without the suggestions.
+    fn on_unresolved_import_suggestions(
+        &self,
+        imports: &[UnresolvedImport],
+        remappings: &[Remapping],
+    ) {
+        let imports = imports
+            .iter()
+            .map(|unresolved| (unresolved.import.as_path(), unresolved.file.as_path()))
+            .collect::<Vec<_>>();
+        self.on_unresolved_imports(&imports, remappings)
+    }
+
+    /// Invoked if import cycles between source files were found, see
+    /// [`ProjectBuilder::import_cycles()`](crate::ProjectBuilder::import_cycles)
+    fn on_import_cycles(&self, _cycles: &[Vec<PathBuf>], _severity: Severity) {}
+
+    /// Invoked with the files that are compiled because their cached artifacts can't be reused,
+    /// together with the reason why
+    fn on_dirty_files(&self, _dirty_files: &BTreeMap<PathBuf, DirtyReason>) {}
+
+    /// Invoked if contract names that are declared in multiple source files were found, see
+    /// [`ProjectBuilder::duplicate_contracts()`](crate::ProjectBuilder::duplicate_contracts)
+    fn on_duplicate_contracts(&self, _duplicates: &[DuplicateContract], _severity: Severity) {}
+
+    /// Invoked if another build of the project holds the build lock, before waiting for it to be
+    /// released, see [`BuildLock`](crate::BuildLock)
+    fn on_build_lock_wait(&self, _lock: &Path) {}
+
     /// If `self` is the same type as the provided `TypeId`, returns an untyped
     /// [`NonNull`] pointer to that type. Otherwise, returns `None`.
     ///
@@ -199,6 +293,30 @@ pub(crate) fn solc_success(
     get_default(|r| r.reporter.on_solc_success(solc, version, output, duration));
 }
 
+pub(crate) fn compiler_spawn(compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+    get_default(|r| r.reporter.on_compiler_spawn(compiler_name, version, dirty_files));
+}
+
+pub(crate) fn compiler_success(compiler_name: &str, version: &Version, duration: &Duration) {
+    get_default(|r| r.reporter.on_compiler_success(compiler_name, version, duration));
+}
+
+pub(crate) fn shared_cache_hit(compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+    get_default(|r| r.reporter.on_shared_cache_hit(compiler_name, version, dirty_files));
+}
+
+pub(crate) fn shared_cache_miss(compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+    get_default(|r| r.reporter.on_shared_cache_miss(compiler_name, version, dirty_files));
+}
+
+pub(crate) fn remote_cache_hit(compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+    get_default(|r| r.reporter.on_remote_cache_hit(compiler_name, version, dirty_files));
+}
+
+pub(crate) fn remote_cache_miss(compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+    get_default(|r| r.reporter.on_remote_cache_miss(compiler_name, version, dirty_files));
+}
+
 #[allow(dead_code)]
 pub(crate) fn solc_installation_start(version: &Version) {
     get_default(|r| r.reporter.on_solc_installation_start(version));
@@ -214,8 +332,24 @@ pub(crate) fn solc_installation_error(version: &Version, error: &str) {
     get_default(|r| r.reporter.on_solc_installation_error(version, error));
 }
 
-pub(crate) fn unresolved_imports(imports: &[(&Path, &Path)], remappings: &[Remapping]) {
-    get_default(|r| r.reporter.on_unresolved_imports(imports, remappings));
+pub(crate) fn unresolved_imports(imports: &[UnresolvedImport], remappings: &[Remapping]) {
+    get_default(|r| r.reporter.on_unresolved_import_suggestions(imports, remappings));
+}
+
+pub(crate) fn import_cycles(cycles: &[Vec<PathBuf>], severity: Severity) {
+    get_default(|r| r.reporter.on_import_cycles(cycles, severity));
+}
+
+pub(crate) fn dirty_files(dirty_files: &BTreeMap<PathBuf, DirtyReason>) {
+    get_default(|r| r.reporter.on_dirty_files(dirty_files));
+}
+
+pub(crate) fn duplicate_contracts(duplicates: &[DuplicateContract], severity: Severity) {
+    get_default(|r| r.reporter.on_duplicate_contracts(duplicates, severity));
+}
+
+pub(crate) fn build_lock_wait(lock: &Path) {
+    get_default(|r| r.reporter.on_build_lock_wait(lock));
 }
 
 fn get_global() -> Option<&'static Report> {
@@ -375,6 +509,46 @@ impl Reporter for BasicStdoutReporter {
         );
     }
 
+    fn on_compiler_spawn(&self, compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+        println!(
+            "Compiling {} files with {} {}.{}.{}",
+            dirty_files.len(),
+            compiler_name,
+            version.major,
+            version.minor,
+            version.patch
+        );
+    }
+
+    fn on_compiler_success(&self, compiler_name: &str, version: &Version, duration: &Duration) {
+        println!(
+            "{} {}.{}.{} finished in {duration:.2?}",
+            compiler_name, version.major, version.minor, version.patch
+        );
+    }
+
+    fn on_shared_cache_hit(&self, compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+        println!(
+            "Reusing cached output for {} files of {} {}.{}.{}",
+            dirty_files.len(),
+            compiler_name,
+            version.major,
+            version.minor,
+            version.patch
+        );
+    }
+
+    fn on_remote_cache_hit(&self, compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+        println!(
+            "Reusing remotely cached output for {} files of {} {}.{}.{}",
+            dirty_files.len(),
+            compiler_name,
+            version.major,
+            version.minor,
+            version.patch
+        );
+    }
+
     /// Invoked before a new [`Solc`] bin is installed
     fn on_solc_installation_start(&self, version: &Version) {
         println!("installing solc version \"{version}\"");
@@ -395,6 +569,59 @@ impl Reporter for BasicStdoutReporter {
         }
         println!("{}", format_unresolved_imports(imports, remappings))
     }
+
+    fn on_unresolved_import_suggestions(
+        &self,
+        imports: &[UnresolvedImport],
+        remappings: &[Remapping],
+    ) {
+        if imports.is_empty() {
+            return;
+        }
+        println!("{}", format_unresolved_import_suggestions(imports, remappings))
+    }
+
+    fn on_import_cycles(&self, cycles: &[Vec<PathBuf>], severity: Severity) {
+        if cycles.is_empty() {
+            return;
+        }
+        println!("{severity}: {}", format_import_cycles(cycles))
+    }
+
+    fn on_duplicate_contracts(&self, duplicates: &[DuplicateContract], severity: Severity) {
+        if duplicates.is_empty() {
+            return;
+        }
+        println!("{severity}: {}", format_duplicate_contracts(duplicates))
+    }
+
+    fn on_build_lock_wait(&self, lock: &Path) {
+        println!("Waiting for another build to release the lock \"{}\"", lock.display())
+    }
+}
+
+/// Creates a meaningful message for all import cycles, every cycle is printed as
+/// `A.sol -> B.sol -> A.sol`
+pub fn format_import_cycles(cycles: &[Vec<PathBuf>]) -> String {
+    let info = cycles
+        .iter()
+        .map(|cycle| {
+            cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|file| file.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n      ");
+    format!("Found import cycles between source files:\n      {info}")
+}
+
+/// Creates a meaningful message for all contract names that are declared in multiple files
+pub fn format_duplicate_contracts(duplicates: &[DuplicateContract]) -> String {
+    let info = duplicates.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("\n      ");
+    format!("Found contracts with the same name in multiple files:\n      {info}")
 }
 
 /// Creates a meaningful message for all unresolved imports
@@ -411,6 +638,20 @@ pub fn format_unresolved_imports(imports: &[(&Path, &Path)], remappings: &[Remap
     )
 }
 
+/// Creates a meaningful message for all unresolved imports, including the suggestions how to fix
+/// them, see [`UnresolvedImport`]
+pub fn format_unresolved_import_suggestions(
+    imports: &[UnresolvedImport],
+    remappings: &[Remapping],
+) -> String {
+    let info = imports.iter().map(|import| import.to_string()).collect::<Vec<_>>().join("\n      ");
+    format!(
+        "Unable to resolve imports:\n      {}\nwith remappings:\n      {}",
+        info,
+        remappings.iter().map(|r| r.to_string()).collect::<Vec<_>>().join("\n      ")
+    )
+}
+
 /// Returned if setting the global reporter fails.
 #[derive(Debug)]
 pub struct SetGlobalReporterError {
@@ -534,4 +775,24 @@ with remappings:
                 .trim()
         )
     }
+
+    #[test]
+    fn test_unresolved_suggestions_message() {
+        let unresolved = vec![UnresolvedImport {
+            import: "oz/token/ERC20.sol".into(),
+            file: "src/Token.sol".into(),
+            suggestions: vec![crate::resolver::ImportSuggestion::Remapping(
+                Remapping::from_str("oz/=lib/openzeppelin/").unwrap(),
+            )],
+        }];
+
+        assert_eq!(
+            format_unresolved_import_suggestions(&unresolved, &[]).trim(),
+            r#"
+Unable to resolve imports:
+      "oz/token/ERC20.sol" in "src/Token.sol"; did you mean the remapping "oz/=lib/openzeppelin/"?
+with remappings:"#
+                .trim()
+        )
+    }
 }
@@ -0,0 +1,81 @@
+//! Contract names that are declared in multiple files
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// A contract name that's declared in multiple files.
+///
+/// The artifacts of these contracts are written to separate files, see
+/// [`ArtifactOutput::conflict_free_output_file()`](crate::ArtifactOutput::conflict_free_output_file),
+/// but lookups by name only, like
+/// [`ProjectCompileOutput::find_first()`](crate::ProjectCompileOutput::find_first), are ambiguous.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DuplicateContract {
+    /// The name of the contract
+    pub name: String,
+    /// All files that declare a contract with this name, sorted
+    pub files: Vec<PathBuf>,
+}
+
+impl DuplicateContract {
+    /// Returns all names of the `(name, file)` pairs that are declared in more than one file,
+    /// sorted by name
+    pub fn find_all<N, F>(contracts: impl IntoIterator<Item = (N, F)>) -> Vec<Self>
+    where
+        N: Into<String>,
+        F: AsRef<Path>,
+    {
+        let mut files: BTreeMap<String, BTreeSet<PathBuf>> = BTreeMap::new();
+        for (name, file) in contracts {
+            files.entry(name.into()).or_default().insert(file.as_ref().to_path_buf());
+        }
+        files
+            .into_iter()
+            .filter(|(_, files)| files.len() > 1)
+            .map(|(name, files)| Self { name, files: files.into_iter().collect() })
+            .collect()
+    }
+}
+
+impl fmt::Display for DuplicateContract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" in ", self.name)?;
+        for (idx, file) in self.files.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "\"{}\"", file.display())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_find_duplicate_contracts() {
+        let duplicates = DuplicateContract::find_all([
+            ("Token", "src/b/Token.sol"),
+            ("Math", "src/Math.sol"),
+            ("Token", "src/a/Token.sol"),
+            // the same contract compiled with multiple versions
+            ("Math", "src/Math.sol"),
+        ]);
+        assert_eq!(
+            duplicates,
+            vec![DuplicateContract {
+                name: "Token".to_string(),
+                files: vec!["src/a/Token.sol".into(), "src/b/Token.sol".into()],
+            }]
+        );
+        assert_eq!(
+            duplicates[0].to_string(),
+            "\"Token\" in \"src/a/Token.sol\", \"src/b/Token.sol\""
+        );
+    }
+}
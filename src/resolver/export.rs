@@ -0,0 +1,347 @@
+//! Exports of the dependency graph as Graphviz DOT and JSON
+
+use super::{parse::SolImportAlias, Graph};
+use crate::utils;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Write},
+    path::PathBuf,
+};
+
+/// The version of the [`GraphExport`] schema, increased on every breaking change
+pub const GRAPH_EXPORT_VERSION: u32 = 1;
+
+/// A serializable snapshot of a [`Graph`], see [`Graph::export()`].
+///
+/// All paths are relative to the project root, nodes are sorted by path and edges by the path of
+/// the importing file and the order of the imports in that file, so the export of the same
+/// project is always the same. The JSON representation is the serde serialization of this type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphExport {
+    /// The version of the schema, see [`GRAPH_EXPORT_VERSION`]
+    pub version: u32,
+    /// All files of the graph
+    pub nodes: Vec<NodeExport>,
+    /// All resolved imports
+    pub edges: Vec<EdgeExport>,
+}
+
+/// A single file of a [`GraphExport`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeExport {
+    /// The path of the file
+    pub path: PathBuf,
+    /// The version pragma of the file, like `^0.8.10`
+    pub version_pragma: Option<String>,
+    /// The SPDX license identifier of the file
+    pub license: Option<String>,
+    /// Whether the file is not an input file of the project, but only imported by one
+    pub is_library: bool,
+    /// The compiler versions the file was assigned, a file can be compiled with multiple versions
+    /// if it's imported by files that require different versions. Empty if versions weren't
+    /// resolved.
+    pub compiler_versions: Vec<Version>,
+}
+
+/// An import of a [`GraphExport`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeExport {
+    /// The path of the importing file
+    pub from: PathBuf,
+    /// The path of the imported file
+    pub to: PathBuf,
+    /// The aliases of the import statement
+    pub aliases: Vec<ImportAliasExport>,
+}
+
+/// An alias of an import statement, see [`SolImportAlias`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ImportAliasExport {
+    /// `import "File.sol" as Name;` or a symbol that's imported under its own name
+    File { name: String },
+    /// `import { Symbol as Alias } from "File.sol";`
+    Contract { name: String, alias: String },
+}
+
+impl From<&SolImportAlias> for ImportAliasExport {
+    fn from(alias: &SolImportAlias) -> Self {
+        match alias {
+            SolImportAlias::File(name) => ImportAliasExport::File { name: name.clone() },
+            SolImportAlias::Contract(alias, name) => {
+                ImportAliasExport::Contract { name: name.clone(), alias: alias.clone() }
+            }
+        }
+    }
+}
+
+impl GraphExport {
+    /// Creates the export of the `graph`, with the compiler versions assigned to the input nodes
+    /// by their index
+    pub(crate) fn new(graph: &Graph, versions: &HashMap<usize, Vec<Version>>) -> Self {
+        let root = &graph.root;
+        let source_name =
+            |idx: usize| utils::source_name(&graph.node(idx).path, root).to_path_buf();
+
+        // every imported file is compiled with all versions of the files that import it
+        let mut node_versions: HashMap<usize, BTreeSet<&Version>> = HashMap::new();
+        for (input, versions) in versions {
+            for idx in graph.node_ids(*input) {
+                node_versions.entry(idx).or_default().extend(versions);
+            }
+        }
+
+        let mut nodes = (0..graph.nodes.len())
+            .map(|idx| {
+                let data = &graph.node(idx).data;
+                NodeExport {
+                    path: source_name(idx),
+                    version_pragma: data.version.as_ref().map(|v| v.data().clone()),
+                    license: data.license.as_ref().map(|l| l.data().clone()),
+                    is_library: idx >= graph.edges.num_input_files,
+                    compiler_versions: node_versions
+                        .get(&idx)
+                        .map(|versions| versions.iter().map(|v| (*v).clone()).collect())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut edges = BTreeMap::new();
+        for (idx, imports) in graph.node_imports.iter().enumerate() {
+            let node_edges = graph
+                .node(idx)
+                .data
+                .imports
+                .iter()
+                .zip(&imports.targets)
+                .filter_map(|(import, target)| {
+                    let to = graph.edges.indices.get(target.as_ref()?)?;
+                    Some(EdgeExport {
+                        from: source_name(idx),
+                        to: source_name(*to),
+                        aliases: import.data().aliases().iter().map(Into::into).collect(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            edges.insert(source_name(idx), node_edges);
+        }
+
+        Self {
+            version: GRAPH_EXPORT_VERSION,
+            nodes,
+            edges: edges.into_values().flatten().collect(),
+        }
+    }
+
+    /// Returns the graph in the Graphviz DOT format.
+    ///
+    /// Every node is labeled with its path, version pragma and assigned compiler versions, library
+    /// files are dashed and edges are labeled with the aliases of the import.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot).expect("writing to a string never fails");
+        dot
+    }
+
+    /// Writes the graph in the Graphviz DOT format, see [`Self::to_dot()`]
+    pub fn write_dot<W: Write>(&self, out: &mut W) -> fmt::Result {
+        writeln!(out, "digraph {{")?;
+        for node in self.nodes.iter() {
+            let mut label = node.path.display().to_string();
+            if let Some(pragma) = &node.version_pragma {
+                label.push_str(&format!("\n{pragma}"));
+            }
+            if !node.compiler_versions.is_empty() {
+                let versions =
+                    node.compiler_versions.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+                label.push_str(&format!("\n[{}]", versions.join(", ")));
+            }
+            write!(
+                out,
+                "    {} [label={}",
+                quote(&node.path.display().to_string()),
+                quote(&label)
+            )?;
+            if node.is_library {
+                write!(out, ", style=dashed")?;
+            }
+            writeln!(out, "];")?;
+        }
+        for edge in self.edges.iter() {
+            write!(
+                out,
+                "    {} -> {}",
+                quote(&edge.from.display().to_string()),
+                quote(&edge.to.display().to_string())
+            )?;
+            if !edge.aliases.is_empty() {
+                let aliases = edge
+                    .aliases
+                    .iter()
+                    .map(|alias| match alias {
+                        ImportAliasExport::File { name } => name.clone(),
+                        ImportAliasExport::Contract { name, alias } => format!("{name} as {alias}"),
+                    })
+                    .collect::<Vec<_>>();
+                write!(out, " [label={}]", quote(&aliases.join(", ")))?;
+            }
+            writeln!(out, ";")?;
+        }
+        writeln!(out, "}}")
+    }
+}
+
+/// Returns the string as a quoted DOT id
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProjectPathsConfig;
+    use std::path::Path;
+
+    fn dapp_sample() -> GraphExport {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/dapp-sample");
+        let paths = ProjectPathsConfig::dapptools(root).unwrap();
+        Graph::resolve(&paths).unwrap().export()
+    }
+
+    #[test]
+    fn can_export_graph() {
+        let export = dapp_sample();
+        assert_eq!(export.version, GRAPH_EXPORT_VERSION);
+        assert_eq!(
+            export
+                .nodes
+                .iter()
+                .map(|n| (n.path.to_str().unwrap(), n.is_library))
+                .collect::<Vec<_>>(),
+            vec![
+                ("lib/ds-test/src/test.sol", true),
+                ("src/Dapp.sol", false),
+                ("src/Dapp.t.sol", false)
+            ]
+        );
+        assert_eq!(export.nodes[1].version_pragma.as_deref(), Some(">=0.6.6"));
+        assert_eq!(
+            export
+                .edges
+                .iter()
+                .map(|e| (e.from.to_str().unwrap(), e.to.to_str().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("src/Dapp.t.sol", "lib/ds-test/src/test.sol"),
+                ("src/Dapp.t.sol", "src/Dapp.sol")
+            ]
+        );
+
+        let json = serde_json::to_string(&export).unwrap();
+        assert_eq!(serde_json::from_str::<GraphExport>(&json).unwrap(), export);
+    }
+
+    #[test]
+    fn can_export_graph_as_dot() {
+        let dot = dapp_sample().to_dot();
+        assert_eq!(
+            dot,
+            r#"digraph {
+    "lib/ds-test/src/test.sol" [label="lib/ds-test/src/test.sol\n>=0.4.23", style=dashed];
+    "src/Dapp.sol" [label="src/Dapp.sol\n>=0.6.6"];
+    "src/Dapp.t.sol" [label="src/Dapp.t.sol\n>=0.6.6"];
+    "src/Dapp.t.sol" -> "lib/ds-test/src/test.sol";
+    "src/Dapp.t.sol" -> "src/Dapp.sol";
+}
+"#
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "svm-solc", feature = "project-util"))]
+    fn can_export_graph_with_versions() {
+        use crate::project_util::{mock_solc::MockSolc, TempProject};
+
+        let project = TempProject::dapptools().unwrap();
+        project
+            .add_lib(
+                "Lib",
+                r#"
+pragma solidity >=0.7.0;
+library Lib {}
+"#,
+            )
+            .unwrap();
+        project
+            .add_source(
+                "A",
+                r#"
+pragma solidity =0.8.10;
+import "../lib/Lib.sol";
+contract A {}
+"#,
+            )
+            .unwrap();
+        project
+            .add_source(
+                "B",
+                r#"
+pragma solidity =0.7.6;
+import "../lib/Lib.sol";
+contract B {}
+"#,
+            )
+            .unwrap();
+
+        let graph = Graph::resolve(project.paths()).unwrap();
+        let export =
+            graph.export_with_versions(&MockSolc::default(), true, &Default::default()).unwrap();
+        let versions = |v: &[(u64, u64, u64)]| {
+            v.iter().map(|(major, minor, patch)| Version::new(*major, *minor, *patch)).collect()
+        };
+        assert_eq!(
+            export
+                .nodes
+                .iter()
+                .map(|n| (n.path.to_str().unwrap(), n.compiler_versions.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("lib/Lib.sol", versions(&[(0, 7, 6), (0, 8, 10)])),
+                ("src/A.sol", versions(&[(0, 8, 10)])),
+                ("src/B.sol", versions(&[(0, 7, 6)]))
+            ]
+        );
+        assert!(export.to_dot().contains(r#"label="lib/Lib.sol\n>=0.7.0\n[0.7.6, 0.8.10]""#));
+
+        // versions are only assigned by a version aware export
+        assert!(graph.export().nodes.iter().all(|n| n.compiler_versions.is_empty()));
+    }
+
+    #[test]
+    fn can_export_import_aliases() {
+        let alias = SolImportAlias::Contract("Test".to_string(), "DSTest".to_string());
+        let json = serde_json::to_value(ImportAliasExport::from(&alias)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "contract", "name": "DSTest", "alias": "Test" })
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Resolvers for the import paths of files, see [`ImportResolver`]
+
+use crate::{error::Result, utils, ProjectPathsConfig, SolcError};
+use std::{
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+/// Resolves import paths of files to the files they refer to.
+///
+/// [`ProjectPathsConfig::resolve_import()`] consults the [`RemappingResolver`] and the
+/// [`FilesystemResolver`] first and then all resolvers of
+/// [`ProjectPathsConfig::import_resolvers`] in order, until one of them resolves the import.
+/// Custom resolvers can fetch imports that don't refer to files on disk, like `https://...`, the
+/// fetched content is cached in [`ProjectPathsConfig::imports_cache_dir()`] and compiled from
+/// there. Imports that are cached already are resolved from the cache without consulting the
+/// custom resolvers, which are never consulted if [`ProjectPathsConfig::offline`] is set.
+pub trait ImportResolver: fmt::Debug + Send + Sync {
+    /// Resolves the `import` of a file in the `cwd` dir.
+    ///
+    /// Returns `Ok(None)` if the import isn't handled by this resolver, so the next one is
+    /// consulted.
+    fn resolve(
+        &self,
+        paths: &ProjectPathsConfig,
+        cwd: &Path,
+        import: &Path,
+    ) -> Result<Option<ResolvedImport>>;
+}
+
+/// The result of an [`ImportResolver`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResolvedImport {
+    /// The import refers to this file on disk
+    File(PathBuf),
+    /// The import refers to this file of a library that uses absolute imports, like
+    /// `import "src/Contract.sol"`, which solc can only resolve if the `include_path` is passed via
+    /// `--include-path`
+    LibraryFile { file: PathBuf, include_path: PathBuf },
+    /// The import refers to this content, which isn't a file on disk.
+    ///
+    /// The content is written to the [`ProjectPathsConfig::imports_cache_dir()`], so relative
+    /// imports of the content must refer to files that were cached there as well.
+    Content(String),
+}
+
+/// Resolves imports with the [`ProjectPathsConfig::remappings`], the remapped file doesn't need
+/// to exist.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RemappingResolver;
+
+impl ImportResolver for RemappingResolver {
+    fn resolve(
+        &self,
+        paths: &ProjectPathsConfig,
+        cwd: &Path,
+        import: &Path,
+    ) -> Result<Option<ResolvedImport>> {
+        if is_relative(import) {
+            return Ok(None);
+        }
+        Ok(paths.resolve_remapped_import(cwd, import).map(ResolvedImport::File))
+    }
+}
+
+/// Resolves relative imports and imports of files in the library dirs, in libraries that use
+/// absolute imports and in the project's dirs.
+///
+/// Relative imports of files that don't exist are an error.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FilesystemResolver;
+
+impl ImportResolver for FilesystemResolver {
+    fn resolve(
+        &self,
+        paths: &ProjectPathsConfig,
+        cwd: &Path,
+        import: &Path,
+    ) -> Result<Option<ResolvedImport>> {
+        if is_relative(import) {
+            // if the import is relative we assume it's already part of the processed input
+            // file set
+            let file = utils::normalize_solidity_import_path(cwd, import).map_err(|err| {
+                SolcError::msg(format!("failed to resolve relative import \"{err:?}\""))
+            })?;
+            return Ok(Some(ResolvedImport::File(file)));
+        }
+
+        if let Some(file) = utils::resolve_library(&paths.libraries, import) {
+            return Ok(Some(ResolvedImport::File(file)));
+        }
+
+        // absolute paths in solidity are a thing for example `import
+        // "src/interfaces/IConfig.sol"` which could either point to `cwd +
+        // src/interfaces/IConfig.sol`, or make use of a remapping (`src/=....`)
+        if let Some(lib) = paths.find_library_ancestor(cwd) {
+            if let Some((include_path, file)) = utils::resolve_absolute_library(lib, cwd, import) {
+                // track the path for this absolute import inside a nested library
+                return Ok(Some(ResolvedImport::LibraryFile { file, include_path }));
+            }
+        }
+
+        // also try to resolve absolute imports from the project paths
+        for path in [&paths.root, &paths.sources, &paths.tests, &paths.scripts] {
+            if cwd.starts_with(path) {
+                if let Ok(file) = utils::normalize_solidity_import_path(path, import) {
+                    return Ok(Some(ResolvedImport::File(file)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Returns `true` if the import starts with `./` or `../`
+fn is_relative(import: &Path) -> bool {
+    matches!(import.components().next(), Some(Component::CurDir | Component::ParentDir))
+}
+
+/// Returns the path of the fetched content of the `import` relative to the imports cache dir, like
+/// `https/example.com/Contract.sol` for `https://example.com/Contract.sol`
+pub(crate) fn cached_import_path(import: &Path) -> PathBuf {
+    import
+        .to_string_lossy()
+        .split(['/', '\\', ':'])
+        .filter(|name| !name.is_empty() && *name != ".")
+        .map(|name| if name == ".." { "_" } else { name })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_sanitize_cached_import_paths() {
+        assert_eq!(
+            cached_import_path(Path::new("https://example.com/a/Contract.sol")),
+            Path::new("https/example.com/a/Contract.sol")
+        );
+        assert_eq!(
+            cached_import_path(Path::new("ipfs://Qm/../Contract.sol")),
+            Path::new("ipfs/Qm/_/Contract.sol")
+        );
+        assert_eq!(
+            cached_import_path(Path::new("registry:pkg/A.sol")),
+            Path::new("registry/pkg/A.sol")
+        );
+    }
+}
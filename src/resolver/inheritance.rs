@@ -0,0 +1,305 @@
+//! The inheritance relationships between the contracts of a [`Graph`]
+
+use super::{parse::SolContractKind, Graph, SolImportAlias};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Identifies a contract, interface or library by its file and name
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContractId {
+    /// The file that declares the contract
+    pub file: PathBuf,
+    /// The name of the contract
+    pub name: String,
+}
+
+impl ContractId {
+    pub fn new(file: impl Into<PathBuf>, name: impl Into<String>) -> Self {
+        Self { file: file.into(), name: name.into() }
+    }
+}
+
+impl fmt::Display for ContractId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.name)
+    }
+}
+
+/// A contract of the [`InheritanceGraph`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InheritedContract {
+    /// The kind of the contract
+    pub kind: SolContractKind,
+    /// The resolved base contracts, in the order of the `is` list
+    pub bases: Vec<ContractId>,
+    /// The base contracts that couldn't be found in the file or its imports, as they appear in
+    /// the `is` list
+    pub unresolved_bases: Vec<String>,
+    /// The contracts that directly inherit from this contract
+    derived: BTreeSet<ContractId>,
+}
+
+/// The inheritance relationships between all contracts of a [`Graph`], extracted from the
+/// parsed sources without compiling them, see [`Graph::inheritance()`].
+///
+/// A base contract is looked up by its name in the file that declares the contract and then in
+/// the files it imports, following the aliases of the import statements like `import {A as B}`.
+/// Qualified names like `Lib.Base` are looked up in the file imported as `Lib`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InheritanceGraph {
+    contracts: BTreeMap<ContractId, InheritedContract>,
+}
+
+impl InheritanceGraph {
+    /// Extracts the inheritance relationships of all contracts of the `graph`
+    pub fn new(graph: &Graph) -> Self {
+        let mut contracts = BTreeMap::new();
+        for (idx, node) in graph.nodes.iter().enumerate() {
+            for contract in &node.data.contracts {
+                let mut bases = Vec::new();
+                let mut unresolved_bases = Vec::new();
+                for base in &contract.bases {
+                    match resolve_base(graph, idx, base) {
+                        Some(id) => bases.push(id),
+                        None => unresolved_bases.push(base.clone()),
+                    }
+                }
+                let inherited = InheritedContract {
+                    kind: contract.kind,
+                    bases,
+                    unresolved_bases,
+                    derived: BTreeSet::new(),
+                };
+                contracts.insert(ContractId::new(&node.path, &contract.name), inherited);
+            }
+        }
+
+        let edges = contracts
+            .iter()
+            .flat_map(|(id, c)| c.bases.iter().map(move |base| (base.clone(), id.clone())))
+            .collect::<Vec<_>>();
+        for (base, derived) in edges {
+            if let Some(base) = contracts.get_mut(&base) {
+                base.derived.insert(derived);
+            }
+        }
+
+        Self { contracts }
+    }
+
+    /// Returns an iterator over all contracts
+    pub fn contracts(&self) -> impl Iterator<Item = (&ContractId, &InheritedContract)> + '_ {
+        self.contracts.iter()
+    }
+
+    /// Returns the contract with the given id
+    pub fn get(&self, id: &ContractId) -> Option<&InheritedContract> {
+        self.contracts.get(id)
+    }
+
+    /// Returns all contracts with the given name
+    pub fn find<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ContractId> + 'a {
+        self.contracts.keys().filter(move |id| id.name == name)
+    }
+
+    /// Returns the contract with the given name that's declared in the `file`
+    pub fn find_in_file(&self, file: impl AsRef<Path>, name: &str) -> Option<&ContractId> {
+        let file = file.as_ref();
+        self.contracts.keys().find(|id| id.file == file && id.name == name)
+    }
+
+    /// Returns the contracts the given contract directly inherits from
+    pub fn bases(&self, id: &ContractId) -> &[ContractId] {
+        self.get(id).map(|c| c.bases.as_slice()).unwrap_or_default()
+    }
+
+    /// Returns all contracts the given contract inherits from, directly or through other bases,
+    /// closest bases first
+    pub fn all_bases(&self, id: &ContractId) -> Vec<&ContractId> {
+        self.walk(id, |contract| contract.bases.iter())
+    }
+
+    /// Returns the contracts that directly inherit from the given contract
+    pub fn derived(&self, id: &ContractId) -> impl Iterator<Item = &ContractId> + '_ {
+        self.get(id).into_iter().flat_map(|c| c.derived.iter())
+    }
+
+    /// Returns all contracts that inherit from the given contract, directly or through other
+    /// contracts, closest first
+    pub fn all_derived(&self, id: &ContractId) -> Vec<&ContractId> {
+        self.walk(id, |contract| contract.derived.iter())
+    }
+
+    /// Visits all contracts reachable from `start` via `next` in breadth-first order, excluding
+    /// `start`
+    fn walk<'a, I>(
+        &'a self,
+        start: &ContractId,
+        next: impl Fn(&'a InheritedContract) -> I,
+    ) -> Vec<&'a ContractId>
+    where
+        I: Iterator<Item = &'a ContractId>,
+    {
+        let mut visited = BTreeSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut found = Vec::new();
+        while let Some(id) = queue.pop_front() {
+            let Some(contract) = self.get(id) else { continue };
+            for id in next(contract) {
+                if visited.insert(id) {
+                    found.push(id);
+                    queue.push_back(id);
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Resolves the `base` of a contract in the file of the node `idx` to the contract it refers to
+fn resolve_base(graph: &Graph, idx: usize, base: &str) -> Option<ContractId> {
+    resolve_name(graph, idx, base, &mut HashSet::new())
+}
+
+/// Resolves the `name` in the scope of the file of the node `idx`
+fn resolve_name(
+    graph: &Graph,
+    idx: usize,
+    name: &str,
+    visited: &mut HashSet<(usize, String)>,
+) -> Option<ContractId> {
+    if !visited.insert((idx, name.to_string())) {
+        return None;
+    }
+    let node = graph.node(idx);
+    let mut imports = node.data.imports.iter().zip(&graph.node_imports[idx].targets).filter_map(
+        |(import, target)| Some((import.data(), *graph.edges.indices.get(target.as_ref()?)?)),
+    );
+
+    // `Lib.Base` refers to `Base` of the file imported as `Lib`
+    if let Some((unit, name)) = name.split_once('.') {
+        let unit = SolImportAlias::File(unit.to_string());
+        return imports
+            .filter(|(import, _)| import.aliases().contains(&unit))
+            .find_map(|(_, target)| resolve_name(graph, target, name, visited));
+    }
+
+    if node.data.contracts.iter().any(|contract| contract.name == name) {
+        return Some(ContractId::new(&node.path, name));
+    }
+    imports.find_map(|(import, target)| {
+        // only plain imports make all names of the imported file available
+        if import.aliases().is_empty() {
+            return resolve_name(graph, target, name, visited);
+        }
+        import.aliases().iter().find_map(|alias| match alias {
+            SolImportAlias::Contract(alias, original) if alias == name => {
+                resolve_name(graph, target, original, visited)
+            }
+            SolImportAlias::File(symbol) if symbol == name => {
+                resolve_name(graph, target, name, visited)
+            }
+            _ => None,
+        })
+    })
+}
+
+#[cfg(all(test, feature = "project-util"))]
+mod tests {
+    use super::*;
+    use crate::project_util::TempProject;
+
+    #[test]
+    fn can_resolve_inheritance() {
+        let project = TempProject::dapptools().unwrap();
+        let base = project
+            .add_source(
+                "Base",
+                r#"
+pragma solidity ^0.8.10;
+interface IToken {}
+abstract contract Base is IToken {}
+"#,
+            )
+            .unwrap();
+        let token = project
+            .add_source(
+                "Token",
+                r#"
+pragma solidity ^0.8.10;
+import {Base as Parent} from "./Base.sol";
+contract Token is Parent, Missing {}
+"#,
+            )
+            .unwrap();
+        let mintable = project
+            .add_source(
+                "Mintable",
+                r#"
+pragma solidity ^0.8.10;
+import "./Token.sol" as T;
+contract Mintable is T.Token {}
+"#,
+            )
+            .unwrap();
+
+        let graph = Graph::resolve(project.paths()).unwrap();
+        let inheritance = graph.inheritance();
+        let i_token = ContractId::new(&base, "IToken");
+        let base_id = ContractId::new(&base, "Base");
+        let token_id = ContractId::new(&token, "Token");
+        let mintable_id = ContractId::new(&mintable, "Mintable");
+
+        let token_contract = inheritance.get(&token_id).unwrap();
+        assert_eq!(token_contract.kind, SolContractKind::Contract);
+        assert_eq!(token_contract.bases, vec![base_id.clone()]);
+        assert_eq!(token_contract.unresolved_bases, vec!["Missing".to_string()]);
+        assert_eq!(inheritance.get(&base_id).unwrap().kind, SolContractKind::Abstract);
+        assert_eq!(inheritance.find_in_file(&base, "IToken"), Some(&i_token));
+
+        assert_eq!(inheritance.all_bases(&mintable_id), vec![&token_id, &base_id, &i_token]);
+        assert_eq!(inheritance.derived(&i_token).collect::<Vec<_>>(), vec![&base_id]);
+        assert_eq!(inheritance.all_derived(&i_token), vec![&base_id, &token_id, &mintable_id]);
+        assert!(inheritance.all_derived(&mintable_id).is_empty());
+    }
+
+    #[test]
+    fn can_resolve_bases_with_the_same_name() {
+        let project = TempProject::dapptools().unwrap();
+        let a = project.add_source("a/Base", "pragma solidity ^0.8.10;\ncontract Base {}").unwrap();
+        let b = project.add_source("b/Base", "pragma solidity ^0.8.10;\ncontract Base {}").unwrap();
+        let units = project
+            .add_source(
+                "Units",
+                r#"
+pragma solidity ^0.8.10;
+import "./a/Base.sol" as A;
+import "./b/Base.sol" as B;
+contract Units is B.Base {}
+"#,
+            )
+            .unwrap();
+        let aliases = project
+            .add_source(
+                "Aliases",
+                r#"
+pragma solidity ^0.8.10;
+import "./a/Base.sol";
+import {Base as Other} from "./b/Base.sol";
+contract Plain is Base {}
+contract Aliased is Other {}
+"#,
+            )
+            .unwrap();
+
+        let graph = Graph::resolve(project.paths()).unwrap();
+        let inheritance = graph.inheritance();
+        let bases = |file: &Path, name: &str| inheritance.bases(&ContractId::new(file, name));
+        assert_eq!(bases(&units, "Units"), [ContractId::new(&b, "Base")]);
+        assert_eq!(bases(&aliases, "Plain"), [ContractId::new(&a, "Base")]);
+        assert_eq!(bases(&aliases, "Aliased"), [ContractId::new(&b, "Base")]);
+    }
+}
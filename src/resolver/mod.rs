@@ -46,28 +46,51 @@
 //! [version pragma](https://docs.soliditylang.org/en/develop/layout-of-source-files.html#version-pragma),
 //! which is defined on a per source file basis.
 
-use crate::{error::Result, utils, IncludePaths, ProjectPathsConfig, SolcError, Source, Sources};
+use crate::{
+    error::Result, remappings::Remapping, utils, CompilerOverride, IncludePaths,
+    ProjectPathsConfig, SolcError, Source, Sources,
+};
 use parse::{SolData, SolDataUnit, SolImport};
 use rayon::prelude::*;
 use semver::VersionReq;
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     fmt, io,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
+mod duplicates;
+mod export;
+pub(crate) mod imports;
+mod inheritance;
+mod overlay;
 mod parse;
+mod selection;
+mod suggest;
 mod tree;
 
+#[cfg(feature = "svm-solc")]
+use crate::compilers::Compiler;
 use crate::utils::find_case_sensitive_existing_file;
-pub use parse::SolImportAlias;
+pub use duplicates::DuplicateContract;
+pub use export::{EdgeExport, GraphExport, ImportAliasExport, NodeExport, GRAPH_EXPORT_VERSION};
+pub use imports::{FilesystemResolver, ImportResolver, RemappingResolver, ResolvedImport};
+pub use inheritance::{ContractId, InheritanceGraph, InheritedContract};
+pub use overlay::SourceOverlay;
+pub use parse::{SolContractKind, SolImportAlias};
+pub use selection::{
+    SelectedVersion, VersionSelection, VersionSelectionReason, VersionSelectionStrategy,
+};
+pub(crate) use suggest::format_suggestions;
+pub use suggest::{ImportSuggestion, UnresolvedImport};
 pub use tree::{print, Charset, TreeOptions};
 
 /// The underlying edges of the graph which only contains the raw relationship data.
 ///
 /// This is kept separate from the `Graph` as the `Node`s get consumed when the `Solc` to `Sources`
 /// set is determined.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct GraphEdges {
     /// The indices of `edges` correspond to the `nodes`. That is, `edges[0]`
     /// is the set of outgoing edges for `nodes[0]`.
@@ -81,7 +104,7 @@ pub struct GraphEdges {
     /// the identified version requirement of a file
     versions: HashMap<usize, Option<VersionReq>>,
     /// the extracted data from the source file
-    data: HashMap<usize, SolData>,
+    data: HashMap<usize, Arc<SolData>>,
     /// with how many input files we started with, corresponds to `let input_files =
     /// nodes[..num_input_files]`.
     ///
@@ -90,12 +113,17 @@ pub struct GraphEdges {
     num_input_files: usize,
     /// tracks all imports that we failed to resolve for a file
     unresolved_imports: HashSet<(PathBuf, PathBuf)>,
+    /// the unresolved imports with suggestions how to fix them, sorted by import
+    unresolved_import_suggestions: Vec<UnresolvedImport>,
     /// tracks additional include paths resolved by scanning all imports of the graph
     ///
     /// Absolute imports, like `import "src/Contract.sol"` are possible, but this does not play
     /// nice with the standard-json import format, since the VFS won't be able to resolve
     /// "src/Contract.sol" without help via `--include-path`
     resolved_solc_include_paths: IncludePaths,
+    /// remappings of the imports fetched by an [`ImportResolver`] to the files their contents are
+    /// cached in, solc can't resolve these imports otherwise
+    import_remappings: Vec<Remapping>,
 }
 
 impl GraphEdges {
@@ -124,11 +152,22 @@ impl GraphEdges {
         &self.resolved_solc_include_paths
     }
 
+    /// Returns the remappings of all imports that were fetched by an [`ImportResolver`], like
+    /// `https://example.com/A.sol=cache/imports/https/example.com/A.sol`
+    pub fn import_remappings(&self) -> &[Remapping] {
+        &self.import_remappings
+    }
+
     /// Returns all imports that we failed to resolve
     pub fn unresolved_imports(&self) -> &HashSet<(PathBuf, PathBuf)> {
         &self.unresolved_imports
     }
 
+    /// Returns all imports that we failed to resolve, together with suggestions how to fix them
+    pub fn unresolved_import_suggestions(&self) -> &[UnresolvedImport] {
+        &self.unresolved_import_suggestions
+    }
+
     /// Returns a list of nodes the given node index points to for the given kind.
     pub fn imported_nodes(&self, from: usize) -> &[usize] {
         &self.edges[from]
@@ -185,6 +224,141 @@ impl GraphEdges {
             .and_then(|v| v.as_ref())
     }
 
+    /// Returns all import cycles of the graph.
+    ///
+    /// Every cycle is a strongly connected component of the import graph with more than one file
+    /// or a single file that imports itself. A cycle is a closed walk along the imports that
+    /// visits all files of the component, starting with the file with the lowest path: every file
+    /// imports the next one and the last one imports the first one. Files appear more than once if
+    /// the component consists of multiple cycles. The cycles are sorted by their first file.
+    pub fn import_cycles(&self) -> Vec<Vec<PathBuf>> {
+        let mut cycles = self
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.edges[component[0]].contains(&component[0])
+            })
+            .map(|component| self.order_cycle(&component))
+            .collect::<Vec<_>>();
+        cycles.sort();
+        cycles
+    }
+
+    /// Returns the strongly connected components of the import graph, using Tarjan's algorithm
+    fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let num_nodes = self.edges.len();
+        let mut index = vec![UNVISITED; num_nodes];
+        let mut lowlink = vec![0; num_nodes];
+        let mut on_stack = vec![false; num_nodes];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for start in 0..num_nodes {
+            if index[start] != UNVISITED {
+                continue;
+            }
+            // the nodes of the current DFS path and the next of their imports to visit
+            let mut path = vec![(start, 0)];
+            while let Some((node, edge)) = path.pop() {
+                if index[node] == UNVISITED {
+                    index[node] = next_index;
+                    lowlink[node] = next_index;
+                    next_index += 1;
+                    stack.push(node);
+                    on_stack[node] = true;
+                }
+
+                if let Some(&import) = self.edges[node].get(edge) {
+                    path.push((node, edge + 1));
+                    if index[import] == UNVISITED {
+                        path.push((import, 0));
+                    } else if on_stack[import] {
+                        lowlink[node] = lowlink[node].min(index[import]);
+                    }
+                    continue;
+                }
+
+                // all imports of the node are visited
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+                if let Some(&(parent, _)) = path.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+            }
+        }
+        components
+    }
+
+    /// Returns a closed walk along the imports of a strongly connected component that visits all
+    /// of its files, starting with the file with the lowest path.
+    ///
+    /// Every file imports the next one and the last file imports the first one. Files are repeated
+    /// if the component isn't a single simple cycle, like `A -> B -> A -> C` for `A` that imports
+    /// `B` and `C` which both import `A`.
+    fn order_cycle(&self, component: &[usize]) -> Vec<PathBuf> {
+        let members = component.iter().copied().collect::<HashSet<_>>();
+        let mut by_path = component.to_vec();
+        by_path.sort_by_key(|idx| &self.rev_indices[idx]);
+        let start = by_path[0];
+
+        let mut walk = vec![start];
+        let mut visited = HashSet::from([start]);
+        for target in by_path.into_iter().skip(1).chain([start]) {
+            if target != start && visited.contains(&target) {
+                continue;
+            }
+            let from = *walk.last().expect("not empty");
+            let path = self.shortest_import_path(from, target, &members);
+            visited.extend(path.iter().copied());
+            walk.extend(path);
+        }
+        // the walk ends where it started
+        walk.pop();
+        walk.into_iter().map(|idx| self.rev_indices[&idx].clone()).collect()
+    }
+
+    /// Returns the files on the shortest path of imports from `from` to `to` within the `members`,
+    /// without `from`, which always exists within a strongly connected component
+    fn shortest_import_path(&self, from: usize, to: usize, members: &HashSet<usize>) -> Vec<usize> {
+        let mut parents = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(node) = queue.pop_front() {
+            if node == to && !parents.is_empty() {
+                break;
+            }
+            for &import in &self.edges[node] {
+                if members.contains(&import) && !parents.contains_key(&import) {
+                    parents.insert(import, node);
+                    queue.push_back(import);
+                }
+            }
+        }
+
+        let mut path = vec![to];
+        let mut node = to;
+        while let Some(&parent) = parents.get(&node) {
+            if parent == from {
+                break;
+            }
+            path.push(parent);
+            node = parent;
+        }
+        path.reverse();
+        path
+    }
+
     /// Returns those library files that will be required as `linkReferences` by the given file
     ///
     /// This is a preprocess function that attempts to resolve those libraries that will the
@@ -207,12 +381,14 @@ impl GraphEdges {
 /// Represents a fully-resolved solidity dependency graph. Each node in the graph
 /// is a file and edges represent dependencies between them.
 /// See also <https://docs.soliditylang.org/en/latest/layout-of-source-files.html?highlight=import#importing-other-source-files>
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Graph {
     /// all nodes in the project, a `Node` represents a single file
     nodes: Vec<Node>,
     /// relationship of the nodes
     edges: GraphEdges,
+    /// the resolved imports of the nodes, reused by [`Graph::update()`]
+    node_imports: Vec<NodeImports>,
     /// the root of the project this graph represents
     root: PathBuf,
 }
@@ -299,6 +475,90 @@ impl Graph {
         (sources.into_iter().collect(), edges)
     }
 
+    /// Same as [`Self::into_sources()`] but partitions the sources by the first of the `overrides`
+    /// that matches their input files, `None` if there's none.
+    ///
+    /// Every set contains the input files together with all their imports, so imported files can
+    /// be part of multiple sets.
+    pub fn into_sources_by_override(
+        self,
+        overrides: &[CompilerOverride],
+    ) -> (HashMap<Option<usize>, Sources>, GraphEdges) {
+        let mut groups: HashMap<_, Vec<_>> = HashMap::new();
+        for idx in 0..self.edges.num_input_files {
+            let profile = CompilerOverride::find(overrides, &self.root, &self.node(idx).path);
+            groups.entry(profile).or_default().push(idx);
+        }
+        self.into_grouped_sources(groups)
+    }
+
+    /// Consumes the nodes of the graph and returns the sources of all groups of input nodes
+    /// together with their imports
+    fn into_grouped_sources<K: Eq + std::hash::Hash>(
+        self,
+        groups: HashMap<K, Vec<usize>>,
+    ) -> (HashMap<K, Sources>, GraphEdges) {
+        /// insert the imports of the given node into the sources map
+        /// There can be following graph:
+        /// `A(<=0.8.10) imports C(>0.4.0)` and `B(0.8.11) imports C(>0.4.0)`
+        /// where `C` is a library import, in which case we assign `C` only to the first input file.
+        /// However, it's not required to include them in the solc `CompilerInput` as they would get
+        /// picked up by solc otherwise, but we add them, so we can create a corresponding
+        /// cache entry for them as well. This can be optimized however
+        fn insert_imports(
+            idx: usize,
+            all_nodes: &mut HashMap<usize, (PathBuf, Source)>,
+            sources: &mut Sources,
+            edges: &[Vec<usize>],
+            processed_sources: &mut HashSet<usize>,
+        ) {
+            // iterate over all dependencies not processed yet
+            for dep in edges[idx].iter().copied() {
+                // keep track of processed dependencies, if the dep was already in the set we have
+                // processed it already
+                if !processed_sources.insert(dep) {
+                    continue;
+                }
+
+                // library import
+                if let Some((path, source)) = all_nodes.get(&dep).cloned() {
+                    sources.insert(path, source);
+                    insert_imports(dep, all_nodes, sources, edges, processed_sources);
+                }
+            }
+        }
+
+        let (nodes, edges) = self.split();
+
+        let mut grouped_sources = HashMap::with_capacity(groups.len());
+
+        let mut all_nodes = nodes.into_iter().enumerate().collect::<HashMap<_, _>>();
+
+        // determine the `Sources` set for each group
+        for (group, input_node_indices) in groups {
+            let mut sources = Sources::new();
+
+            // all input nodes will be processed
+            let mut processed_sources = input_node_indices.iter().copied().collect();
+
+            // we only process input nodes (from sources, tests for example)
+            for idx in input_node_indices {
+                // insert the input node in the sources set and remove it from the available set
+                let (path, source) = all_nodes.get(&idx).cloned().expect("node is preset. qed");
+                sources.insert(path, source);
+                insert_imports(
+                    idx,
+                    &mut all_nodes,
+                    &mut sources,
+                    &edges.edges,
+                    &mut processed_sources,
+                );
+            }
+            grouped_sources.insert(group, sources);
+        }
+        (grouped_sources, edges)
+    }
+
     /// Returns an iterator that yields only those nodes that represent input files.
     /// See `Self::resolve_sources`
     /// This won't yield any resolved library nodes
@@ -311,8 +571,77 @@ impl Graph {
         self.edges.imports(path)
     }
 
+    /// Returns all import cycles of the graph, see [`GraphEdges::import_cycles()`]
+    pub fn import_cycles(&self) -> Vec<Vec<PathBuf>> {
+        self.edges.import_cycles()
+    }
+
+    /// Returns all contract, interface and library names that are declared in more than one file
+    /// of the graph, see [`DuplicateContract`]
+    pub fn duplicate_contracts(&self) -> Vec<DuplicateContract> {
+        DuplicateContract::find_all(self.nodes.iter().flat_map(|node| {
+            node.data.contracts.iter().map(|contract| (&contract.name, &node.path))
+        }))
+    }
+
+    /// Returns the inheritance relationships between all contracts of the graph, see
+    /// [`InheritanceGraph`]
+    pub fn inheritance(&self) -> InheritanceGraph {
+        InheritanceGraph::new(self)
+    }
+
+    /// Returns a serializable snapshot of the graph that can be rendered as DOT or JSON, see
+    /// [`GraphExport`].
+    ///
+    /// This does not resolve compiler versions, see `Self::export_with_versions()`.
+    pub fn export(&self) -> GraphExport {
+        GraphExport::new(self, &HashMap::new())
+    }
+
+    /// Returns all vyper input files together with all their transitive imports
+    pub fn vyper_sources(&self) -> Sources {
+        (0..self.edges.num_input_files)
+            .filter(|idx| utils::is_vyper_file(&self.node(*idx).path))
+            .flat_map(|idx| self.nodes(idx))
+            .map(|node| (node.path.clone(), node.source.clone()))
+            .collect()
+    }
+
     /// Resolves a number of sources within the given config
     pub fn resolve_sources(paths: &ProjectPathsConfig, sources: Sources) -> Result<Graph> {
+        Self::resolve_sources_with_overlay(paths, sources, &SourceOverlay::default())
+    }
+
+    /// Same as [`Self::resolve_sources()`], but the contents of the files of the `overlay` take
+    /// precedence over the `sources` and the files on disk, see [`SourceOverlay`]
+    pub fn resolve_sources_with_overlay(
+        paths: &ProjectPathsConfig,
+        mut sources: Sources,
+        overlay: &SourceOverlay,
+    ) -> Result<Graph> {
+        overlay.apply(&mut sources);
+        // we start off by reading all input files, which includes all solidity files from the
+        // source and test folder
+        let inputs = sources
+            .into_par_iter()
+            .map(|(path, source)| {
+                let data = SolData::parse(source.as_ref(), &path);
+                Node { path, source, data: Arc::new(data) }
+            })
+            .collect();
+        Self::resolve_nodes(paths, inputs, None, overlay)
+    }
+
+    /// Resolves all imports of the `inputs`, starting with the input nodes.
+    ///
+    /// Imported files are only read if they're not part of `reuse`, the same goes for resolving
+    /// the imports of a node.
+    fn resolve_nodes(
+        paths: &ProjectPathsConfig,
+        inputs: Vec<Node>,
+        reuse: Option<&Reuse<'_>>,
+        overlay: &SourceOverlay,
+    ) -> Result<Graph> {
         /// checks if the given target path was already resolved, if so it adds its id to the list
         /// of resolved imports. If it hasn't been resolved yet, it queues in the file for
         /// processing
@@ -321,12 +650,20 @@ impl Graph {
             index: &mut HashMap<PathBuf, usize>,
             resolved_imports: &mut Vec<usize>,
             target: PathBuf,
+            reuse: Option<&Reuse<'_>>,
+            overlay: &SourceOverlay,
         ) -> Result<()> {
             if let Some(idx) = index.get(&target).copied() {
                 resolved_imports.push(idx);
             } else {
                 // imported file is not part of the input files
-                let node = Node::read(&target)?;
+                let node = match reuse.and_then(|reuse| reuse.node(&target)) {
+                    Some(node) => node,
+                    None => match overlay.get(&target) {
+                        Some(source) => Node::new(target.clone(), source.clone()),
+                        None => Node::read(&target)?,
+                    },
+                };
                 unresolved.push_back((target.clone(), node));
                 let idx = index.len();
                 index.insert(target, idx);
@@ -335,15 +672,8 @@ impl Graph {
             Ok(())
         }
 
-        // we start off by reading all input files, which includes all solidity files from the
-        // source and test folder
-        let mut unresolved: VecDeque<(PathBuf, Node)> = sources
-            .into_par_iter()
-            .map(|(path, source)| {
-                let data = SolData::parse(source.as_ref(), &path);
-                (path.clone(), Node { path, source, data })
-            })
-            .collect();
+        let mut unresolved: VecDeque<(PathBuf, Node)> =
+            inputs.into_iter().map(|node| (node.path.clone(), node)).collect();
 
         // identifiers of all resolved files
         let mut index: HashMap<_, _> =
@@ -355,10 +685,13 @@ impl Graph {
         let mut nodes = Vec::with_capacity(unresolved.len());
         let mut edges = Vec::with_capacity(unresolved.len());
         let mut rev_edges = Vec::with_capacity(unresolved.len());
+        let mut node_imports = Vec::with_capacity(unresolved.len());
 
         // tracks additional paths that should be used with `--include-path`, these are libraries
         // that use absolute imports like `import "src/Contract.sol"`
         let mut resolved_solc_include_paths = IncludePaths::default();
+        let imports_cache_dir = paths.imports_cache_dir();
+        let mut import_remappings = BTreeSet::new();
 
         // keep track of all unique paths that we failed to resolve to not spam the reporter with
         // the same path
@@ -374,44 +707,63 @@ impl Graph {
                 None => continue,
             };
 
-            for import in node.data.imports.iter() {
+            let imports = match reuse.and_then(|reuse| reuse.imports(&path)) {
+                Some(imports) => imports.clone(),
+                None => NodeImports::resolve(paths, cwd, &node, overlay),
+            };
+            resolved_solc_include_paths.extend(imports.include_paths.iter().cloned());
+
+            for (import, target) in node.data.imports.iter().zip(&imports.targets) {
                 let import_path = import.data().path();
-                match paths.resolve_import_and_include_paths(
-                    cwd,
-                    import_path,
-                    &mut resolved_solc_include_paths,
-                ) {
-                    Ok(import) => {
-                        add_node(&mut unresolved, &mut index, &mut resolved_imports, import)
-                            .map_err(|err| {
-                                match err {
-                                    SolcError::ResolveCaseSensitiveFileName { .. }
-                                    | SolcError::Resolve(_) => {
-                                        // make the error more helpful by providing additional
-                                        // context
-                                        SolcError::FailedResolveImport(
-                                            Box::new(err),
-                                            node.path.clone(),
-                                            import_path.clone(),
-                                        )
-                                    }
-                                    _ => err,
-                                }
-                            })?
-                    }
-                    Err(err) => {
+                if let Some(target) = target.as_ref().filter(|t| t.starts_with(&imports_cache_dir))
+                {
+                    import_remappings.insert(Remapping {
+                        context: None,
+                        name: import_path.to_string_lossy().into_owned(),
+                        path: utils::source_name(target, &paths.root)
+                            .to_string_lossy()
+                            .into_owned(),
+                    });
+                }
+                match target {
+                    Some(target) => add_node(
+                        &mut unresolved,
+                        &mut index,
+                        &mut resolved_imports,
+                        target.clone(),
+                        reuse,
+                        overlay,
+                    )
+                    .map_err(|err| {
+                        match err {
+                            SolcError::ResolveCaseSensitiveFileName { .. }
+                            | SolcError::Resolve(_) => {
+                                // make the error more helpful by providing additional context
+                                let suggestions = UnresolvedImport::new(
+                                    paths,
+                                    import_path.clone(),
+                                    node.path.clone(),
+                                )
+                                .suggestions;
+                                SolcError::FailedResolveImport(
+                                    Box::new(err),
+                                    node.path.clone(),
+                                    import_path.clone(),
+                                    suggestions.into(),
+                                )
+                            }
+                            _ => err,
+                        }
+                    })?,
+                    None => {
                         unresolved_imports.insert((import_path.to_path_buf(), node.path.clone()));
-                        trace!(
-                            "failed to resolve import component \"{:?}\" for {:?}",
-                            err,
-                            node.path
-                        )
                     }
                 };
             }
 
             nodes.push(node);
             edges.push(resolved_imports);
+            node_imports.push(imports);
             // Will be populated later
             rev_edges.push(Vec::new());
         }
@@ -423,15 +775,13 @@ impl Graph {
             }
         }
 
+        let mut unresolved_import_suggestions = Vec::new();
         if !unresolved_imports.is_empty() {
+            unresolved_import_suggestions =
+                UnresolvedImport::find_all(paths, unresolved_imports.iter().cloned());
+            unresolved_import_suggestions.sort();
             // notify on all unresolved imports
-            crate::report::unresolved_imports(
-                &unresolved_imports
-                    .iter()
-                    .map(|(i, f)| (i.as_path(), f.as_path()))
-                    .collect::<Vec<_>>(),
-                &paths.remappings,
-            );
+            crate::report::unresolved_imports(&unresolved_import_suggestions, &paths.remappings);
         }
 
         let edges = GraphEdges {
@@ -447,15 +797,82 @@ impl Graph {
                 .collect(),
             data: Default::default(),
             unresolved_imports,
+            unresolved_import_suggestions,
             resolved_solc_include_paths,
+            import_remappings: import_remappings.into_iter().collect(),
         };
-        Ok(Graph { nodes, edges, root: paths.root.clone() })
+        Ok(Graph { nodes, edges, node_imports, root: paths.root.clone() })
+    }
+
+    /// Updates the graph after the given files were modified, added or removed.
+    ///
+    /// Only the `changed` files are read and parsed again, all other nodes are reused. Imports are
+    /// only resolved again for the modified files, unless files were added or removed, which may
+    /// change the target of any import. The result is the same graph [`Graph::resolve()`] would
+    /// return.
+    ///
+    /// The `changed` paths must be absolute, like the paths in [`Graph::files()`]. Whether a file
+    /// was added or removed is determined by whether it exists. Added files under the sources,
+    /// tests or scripts dirs become input files.
+    ///
+    /// This expects that the graph was resolved from the files of the `paths`, see
+    /// [`Graph::resolve()`]. If an error is returned, the graph is unchanged.
+    pub fn update<I, P>(&mut self, paths: &ProjectPathsConfig, changed: I) -> Result<()>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let changed: HashSet<PathBuf> =
+            changed.into_iter().map(|path| path.as_ref().to_path_buf()).collect();
+        if changed.is_empty() {
+            return Ok(());
+        }
+
+        let mut inputs: BTreeSet<PathBuf> =
+            self.input_nodes().map(|node| node.path.clone()).collect();
+        let mut files_changed = false;
+        for file in changed.iter() {
+            if file.is_file() {
+                files_changed |= !self.edges.indices.contains_key(file);
+                if is_input_file(paths, file) {
+                    inputs.insert(file.clone());
+                }
+            } else {
+                files_changed = true;
+                inputs.remove(file);
+            }
+        }
+        trace!(changed = changed.len(), files_changed, "updating graph");
+
+        let reuse = Reuse { graph: self, changed: &changed, resolve_imports: files_changed };
+        let inputs = inputs
+            .into_par_iter()
+            .map(|path| match reuse.node(&path) {
+                Some(node) => Ok(node),
+                None => {
+                    let source = Source::read(&path)?;
+                    let data = SolData::parse(source.as_ref(), &path);
+                    Ok(Node { path, source, data: Arc::new(data) })
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        *self = Self::resolve_nodes(paths, inputs, Some(&reuse), &SourceOverlay::default())?;
+        Ok(())
     }
 
     /// Resolves the dependencies of a project's source contracts
     pub fn resolve(paths: &ProjectPathsConfig) -> Result<Graph> {
         Self::resolve_sources(paths, paths.read_input_files()?)
     }
+
+    /// Same as [`Self::resolve()`], but the files of the `overlay` take precedence over the files
+    /// on disk and new files of the overlay in the sources, tests or scripts dirs are input files
+    pub fn resolve_with_overlay(
+        paths: &ProjectPathsConfig,
+        overlay: &SourceOverlay,
+    ) -> Result<Graph> {
+        Self::resolve_sources_with_overlay(paths, overlay.read_input_files(paths)?, overlay)
+    }
 }
 
 #[cfg(feature = "svm-solc")]
@@ -465,71 +882,54 @@ impl Graph {
     ///
     /// First we determine the compatible version for each input file (from sources and test folder,
     /// see `Self::resolve`) and then we add all resolved library imports.
-    pub fn into_sources_by_version(self, offline: bool) -> Result<(VersionedSources, GraphEdges)> {
-        /// insert the imports of the given node into the sources map
-        /// There can be following graph:
-        /// `A(<=0.8.10) imports C(>0.4.0)` and `B(0.8.11) imports C(>0.4.0)`
-        /// where `C` is a library import, in which case we assign `C` only to the first input file.
-        /// However, it's not required to include them in the solc `CompilerInput` as they would get
-        /// picked up by solc otherwise, but we add them, so we can create a corresponding
-        /// cache entry for them as well. This can be optimized however
-        fn insert_imports(
-            idx: usize,
-            all_nodes: &mut HashMap<usize, (PathBuf, Source)>,
-            sources: &mut Sources,
-            edges: &[Vec<usize>],
-            processed_sources: &mut HashSet<usize>,
-        ) {
-            // iterate over all dependencies not processed yet
-            for dep in edges[idx].iter().copied() {
-                // keep track of processed dependencies, if the dep was already in the set we have
-                // processed it already
-                if !processed_sources.insert(dep) {
-                    continue;
-                }
-
-                // library import
-                if let Some((path, source)) = all_nodes.get(&dep).cloned() {
-                    sources.insert(path, source);
-                    insert_imports(dep, all_nodes, sources, edges, processed_sources);
-                }
-            }
-        }
-
-        let versioned_nodes = self.get_input_node_versions(offline)?;
-        let (nodes, edges) = self.split();
-
-        let mut versioned_sources = HashMap::with_capacity(versioned_nodes.len());
-
-        let mut all_nodes = nodes.into_iter().enumerate().collect::<HashMap<_, _>>();
-
-        // determine the `Sources` set for each solc version
-        for (version, input_node_indices) in versioned_nodes {
-            let mut sources = Sources::new();
+    ///
+    /// The candidate versions are the ones the given `compiler` knows about, see
+    /// [`Compiler::available_versions()`].
+    pub fn into_sources_by_version<C: Compiler>(
+        self,
+        compiler: &C,
+        offline: bool,
+    ) -> Result<(VersionedSources<C>, GraphEdges)> {
+        self.into_sources_by_version_with_strategy(compiler, offline, &Default::default())
+    }
 
-            // all input nodes will be processed
-            let mut processed_sources = input_node_indices.iter().copied().collect();
+    /// Same as [`Self::into_sources_by_version()`] but selects the versions of the input files
+    /// with the given strategy
+    pub fn into_sources_by_version_with_strategy<C: Compiler>(
+        self,
+        compiler: &C,
+        offline: bool,
+        strategy: &VersionSelectionStrategy,
+    ) -> Result<(VersionedSources<C>, GraphEdges)> {
+        self.into_sources_by_version_with_overrides(compiler, offline, strategy, &[])
+    }
 
-            // we only process input nodes (from sources, tests for example)
-            for idx in input_node_indices {
-                // insert the input node in the sources set and remove it from the available set
-                let (path, source) = all_nodes.get(&idx).cloned().expect("node is preset. qed");
-                sources.insert(path, source);
-                insert_imports(
-                    idx,
-                    &mut all_nodes,
-                    &mut sources,
-                    &edges.edges,
-                    &mut processed_sources,
-                );
-            }
-            versioned_sources.insert(version, sources);
+    /// Same as [`Self::into_sources_by_version_with_strategy()`] but also applies the version
+    /// requirements of the `overrides` and partitions the sources of every version by the
+    /// override of their input files, see [`VersionedSources::get()`]
+    pub fn into_sources_by_version_with_overrides<C: Compiler>(
+        self,
+        compiler: &C,
+        offline: bool,
+        strategy: &VersionSelectionStrategy,
+        overrides: &[CompilerOverride],
+    ) -> Result<(VersionedSources<C>, GraphEdges)> {
+        let mut groups: HashMap<_, Vec<_>> = HashMap::new();
+        for (idx, selected) in
+            self.select_input_node_versions(compiler, offline, strategy, overrides)?
+        {
+            let profile = CompilerOverride::find(overrides, &self.root, &self.node(idx).path);
+            groups.entry((selected.version, profile)).or_default().push(idx);
         }
+        trace!("resolved {} versions {:?}", groups.len(), groups.keys());
+
+        let (versioned_sources, edges) = self.into_grouped_sources(groups);
         Ok((
             VersionedSources {
                 inner: versioned_sources,
                 offline,
                 resolved_solc_include_paths: edges.resolved_solc_include_paths.clone(),
+                _compiler: Default::default(),
             },
             edges,
         ))
@@ -575,14 +975,55 @@ impl Graph {
         }
     }
 
-    /// Ensures that all files are compatible with all of their imports.
-    pub fn ensure_compatible_imports(&self, offline: bool) -> Result<()> {
-        self.get_input_node_versions(offline)?;
+    /// Same as [`Self::export()`] but also assigns every file the compiler versions it would be
+    /// compiled with, see [`Self::into_sources_by_version_with_strategy()`]
+    pub fn export_with_versions<C: Compiler>(
+        &self,
+        compiler: &C,
+        offline: bool,
+        strategy: &VersionSelectionStrategy,
+    ) -> Result<GraphExport> {
+        let versions = self
+            .select_input_node_versions(compiler, offline, strategy, &[])?
+            .into_iter()
+            .map(|(idx, selected)| (idx, vec![selected.version.into()]))
+            .collect();
+        Ok(GraphExport::new(self, &versions))
+    }
+
+    /// Ensures that all files are compatible with all of their imports and a version of the
+    /// given compiler.
+    pub fn ensure_compatible_imports<C: Compiler>(
+        &self,
+        compiler: &C,
+        offline: bool,
+    ) -> Result<()> {
+        self.select_input_node_versions(compiler, offline, &Default::default(), &[])?;
         Ok(())
     }
 
-    /// Returns a map of versions together with the input nodes that are compatible with that
-    /// version.
+    /// Returns the versions the given strategy selects for the input files together with the
+    /// reason for each choice, without consuming the graph.
+    ///
+    /// The versions of files that match one of the `overrides` are restricted to its version
+    /// requirement. Files that aren't compiled by `C` are ignored.
+    pub fn select_versions<C: Compiler>(
+        &self,
+        compiler: &C,
+        offline: bool,
+        strategy: &VersionSelectionStrategy,
+        overrides: &[CompilerOverride],
+    ) -> Result<VersionSelection> {
+        let files = self
+            .select_input_node_versions(compiler, offline, strategy, overrides)?
+            .into_iter()
+            .map(|(idx, selected)| (self.node(idx).path.clone(), selected))
+            .collect();
+        Ok(VersionSelection { files })
+    }
+
+    /// Selects a version for every input node with the given strategy, restricted to the version
+    /// requirements of the first override that matches the node.
     ///
     /// This will essentially do a DFS on all input sources and their transitive imports and
     /// checking that all can compiled with the version stated in the input file.
@@ -591,12 +1032,13 @@ impl Graph {
     ///
     /// This also attempts to prefer local installations over remote available.
     /// If `offline` is set to `true` then only already installed.
-    fn get_input_node_versions(
+    fn select_input_node_versions<C: Compiler>(
         &self,
+        compiler: &C,
         offline: bool,
-    ) -> Result<HashMap<crate::SolcVersion, Vec<usize>>> {
-        use crate::Solc;
-
+        strategy: &VersionSelectionStrategy,
+        overrides: &[CompilerOverride],
+    ) -> Result<Vec<(usize, SelectedVersion)>> {
         trace!("resolving input node versions");
         // this is likely called by an application and will be eventually printed so we don't exit
         // on first error, instead gather all the errors and return a bundled error message instead
@@ -605,20 +1047,39 @@ impl Graph {
         let mut erroneous_nodes = HashSet::with_capacity(self.edges.num_input_files);
 
         // the sorted list of all versions
-        let all_versions = if offline { Solc::installed_versions() } else { Solc::all_versions() };
-
-        // stores all versions and their nodes that can be compiled
-        let mut versioned_nodes = HashMap::new();
+        let all_versions = compiler.available_versions(offline);
 
         // stores all files and the versions they're compatible with
         let mut all_candidates = Vec::with_capacity(self.edges.num_input_files);
         // walking through the node's dep tree and filtering the versions along the way
         for idx in 0..self.edges.num_input_files {
+            if !crate::compilers::is_compiler_source::<C>(&self.node(idx).path) {
+                // compiled separately, see `ProjectCompiler`
+                continue;
+            }
             let mut candidates = all_versions.iter().collect::<Vec<_>>();
             // remove all incompatible versions from the candidates list by checking the node and
             // all its imports
             self.retain_compatible_versions(idx, &mut candidates);
 
+            // restrict the candidates to the version override of the file, if any
+            let version_override =
+                CompilerOverride::find(overrides, &self.root, &self.node(idx).path)
+                    .and_then(|i| Some((&overrides[i], overrides[i].version_req()?)));
+            if let Some((version_override, req)) = version_override {
+                let compatible = !candidates.is_empty();
+                candidates.retain(|v| req.matches(v.as_ref()));
+                if compatible && candidates.is_empty() {
+                    let f = utils::source_name(&self.node(idx).path, &self.root).display();
+                    errors.push(format!(
+                        "Found no solc version for {f} that satisfies the version override \"{req}\" of \"{}\"",
+                        version_override.pattern()
+                    ));
+                    erroneous_nodes.insert(idx);
+                    continue;
+                }
+            }
+
             if candidates.is_empty() && !erroneous_nodes.contains(&idx) {
                 // check if the version is even valid
                 let node = self.node(idx);
@@ -633,123 +1094,16 @@ impl Graph {
 
                 erroneous_nodes.insert(idx);
             } else {
-                // found viable candidates, pick the most recent version that's already installed
-                let candidate =
-                    if let Some(pos) = candidates.iter().rposition(|v| v.is_installed()) {
-                        candidates[pos]
-                    } else {
-                        candidates.last().expect("not empty; qed.")
-                    }
-                    .clone();
-
-                // also store all possible candidates to optimize the set
-                all_candidates.push((idx, candidates.into_iter().collect::<HashSet<_>>()));
-
-                versioned_nodes.entry(candidate).or_insert_with(|| Vec::with_capacity(1)).push(idx);
+                all_candidates.push((idx, candidates));
             }
         }
 
-        // detected multiple versions but there might still exist a single version that satisfies
-        // all sources
-        if versioned_nodes.len() > 1 {
-            versioned_nodes = Self::resolve_multiple_versions(all_candidates);
-        }
-
-        if versioned_nodes.len() == 1 {
-            trace!(
-                "found exact solc version for all sources  \"{}\"",
-                versioned_nodes.keys().next().unwrap()
-            );
-        }
-
-        if errors.is_empty() {
-            trace!("resolved {} versions {:?}", versioned_nodes.len(), versioned_nodes.keys());
-            Ok(versioned_nodes)
-        } else {
+        if !errors.is_empty() {
             error!("failed to resolve versions");
-            Err(SolcError::msg(errors.join("\n")))
+            return Err(SolcError::msg(errors.join("\n")));
         }
-    }
-
-    /// Tries to find the "best" set of versions to nodes, See [Solc version
-    /// auto-detection](#solc-version-auto-detection)
-    ///
-    /// This is a bit inefficient but is fine, the max. number of versions is ~80 and there's
-    /// a high chance that the number of source files is <50, even for larger projects.
-    fn resolve_multiple_versions(
-        all_candidates: Vec<(usize, HashSet<&crate::SolcVersion>)>,
-    ) -> HashMap<crate::SolcVersion, Vec<usize>> {
-        // returns the intersection as sorted set of nodes
-        fn intersection<'a>(
-            mut sets: Vec<&HashSet<&'a crate::SolcVersion>>,
-        ) -> Vec<&'a crate::SolcVersion> {
-            if sets.is_empty() {
-                return Vec::new();
-            }
-
-            let mut result = sets.pop().cloned().expect("not empty; qed.");
-            if !sets.is_empty() {
-                result.retain(|item| sets.iter().all(|set| set.contains(item)));
-            }
-
-            let mut v = result.into_iter().collect::<Vec<_>>();
-            v.sort_unstable();
-            v
-        }
-
-        /// returns the highest version that is installed
-        /// if the candidates set only contains uninstalled versions then this returns the highest
-        /// uninstalled version
-        fn remove_candidate(candidates: &mut Vec<&crate::SolcVersion>) -> crate::SolcVersion {
-            debug_assert!(!candidates.is_empty());
-
-            if let Some(pos) = candidates.iter().rposition(|v| v.is_installed()) {
-                candidates.remove(pos)
-            } else {
-                candidates.pop().expect("not empty; qed.")
-            }
-            .clone()
-        }
-
-        let all_sets = all_candidates.iter().map(|(_, versions)| versions).collect();
 
-        // find all versions that satisfy all nodes
-        let mut intersection = intersection(all_sets);
-        if !intersection.is_empty() {
-            let exact_version = remove_candidate(&mut intersection);
-            let all_nodes = all_candidates.into_iter().map(|(node, _)| node).collect();
-            trace!("resolved solc version compatible with all sources  \"{}\"", exact_version);
-            return HashMap::from([(exact_version, all_nodes)]);
-        }
-
-        // no version satisfies all nodes
-        let mut versioned_nodes: HashMap<crate::SolcVersion, Vec<usize>> = HashMap::new();
-
-        // try to minimize the set of versions, this is guaranteed to lead to `versioned_nodes.len()
-        // > 1` as no solc version exists that can satisfy all sources
-        for (node, versions) in all_candidates {
-            // need to sort them again
-            let mut versions = versions.into_iter().collect::<Vec<_>>();
-            versions.sort_unstable();
-
-            let candidate =
-                if let Some(idx) = versions.iter().rposition(|v| versioned_nodes.contains_key(v)) {
-                    // use a version that's already in the set
-                    versions.remove(idx).clone()
-                } else {
-                    // use the highest version otherwise
-                    remove_candidate(&mut versions)
-                };
-
-            versioned_nodes.entry(candidate).or_insert_with(|| Vec::with_capacity(1)).push(node);
-        }
-
-        trace!(
-            "no solc version can satisfy all source files, resolved multiple versions  \"{:?}\"",
-            versioned_nodes.keys()
-        );
-
-        versioned_nodes
+        Ok(strategy.select(all_candidates))
     }
 }
 
@@ -781,85 +1135,62 @@ impl<'a> Iterator for NodesIter<'a> {
     }
 }
 
-/// Container type for solc versions and their compatible sources
+/// Container type for compiler versions and their compatible sources, partitioned by the
+/// [`CompilerOverride`] of the input files
 #[cfg(feature = "svm-solc")]
 #[derive(Debug)]
-pub struct VersionedSources {
+pub struct VersionedSources<C: Compiler = crate::Solc> {
     resolved_solc_include_paths: IncludePaths,
-    inner: HashMap<crate::SolcVersion, Sources>,
+    inner: HashMap<(crate::SolcVersion, Option<usize>), Sources>,
     offline: bool,
+    _compiler: std::marker::PhantomData<C>,
 }
 
 #[cfg(feature = "svm-solc")]
-impl VersionedSources {
-    /// Resolves or installs the corresponding `Solc` installation.
+impl<C: Compiler> VersionedSources<C> {
+    /// Resolves or installs the corresponding compiler installation.
     ///
-    /// This will also configure following solc arguments:
+    /// This will also configure the compiler for the project, e.g. following solc arguments:
     ///    - `allowed_paths`
     ///    - `base_path`
+    ///
+    /// Every compiler is paired with the index of the [`CompilerOverride`] its sources are
+    /// compiled with, `None` if they're compiled with the settings of the project.
     pub fn get<T: crate::ArtifactOutput>(
         self,
-        project: &crate::Project<T>,
-    ) -> Result<std::collections::BTreeMap<crate::Solc, (semver::Version, Sources)>> {
-        use crate::Solc;
-        // we take the installer lock here to ensure installation checking is done in sync
-        #[cfg(test)]
-        let _lock = crate::compile::take_solc_installer_lock();
-
+        project: &crate::Project<T, C>,
+    ) -> Result<crate::artifacts::VersionedSources<C>> {
         let mut sources_by_version = std::collections::BTreeMap::new();
-        for (version, sources) in self.inner {
-            let solc = if !version.is_installed() {
-                if self.offline {
-                    return Err(SolcError::msg(format!(
-                        "missing solc \"{version}\" installation in offline mode"
-                    )));
-                } else {
-                    // install missing solc
-                    Solc::blocking_install(version.as_ref())?
-                }
-            } else {
-                // find installed svm
-                Solc::find_svm_installed_version(version.to_string())?.ok_or_else(|| {
-                    SolcError::msg(format!("solc \"{version}\" should have been installed"))
-                })?
-            };
-
-            if self.offline {
-                trace!("skip verifying solc checksum for {} in offline mode", solc.solc.display());
-            } else {
-                trace!("verifying solc checksum for {}", solc.solc.display());
-                if let Err(err) = solc.verify_checksum() {
-                    trace!(?err, "corrupted solc version, redownloading  \"{}\"", version);
-                    Solc::blocking_install(version.as_ref())?;
-                    trace!("reinstalled solc: \"{}\"", version);
-                }
-            }
-
-            let version = solc.version()?;
-
-            // this will configure the `Solc` executable and its arguments
-            let solc = project.configure_solc_with_version(
-                solc,
-                Some(version.clone()),
-                self.resolved_solc_include_paths.clone(),
-            );
-            sources_by_version.insert(solc, (version, sources));
+        for ((version, profile), sources) in self.inner {
+            let compiler = project.compiler.for_version(&version, self.offline)?;
+            let version = compiler.version()?;
+
+            // this will configure the compiler executable and its arguments
+            let compiler =
+                compiler.configure(project, &version, self.resolved_solc_include_paths.clone());
+            sources_by_version.insert((compiler, profile), (version, sources));
         }
         Ok(sources_by_version)
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Node {
     /// path of the solidity  file
     path: PathBuf,
     /// content of the solidity file
     source: Source,
     /// parsed data
-    data: SolData,
+    data: Arc<SolData>,
 }
 
 impl Node {
+    /// Returns a [Node] of the file with the given content
+    fn new(path: PathBuf, source: Source) -> Self {
+        let data = SolData::parse(source.as_ref(), &path);
+        Self { path, source, data: Arc::new(data) }
+    }
+
     /// Reads the content of the file and returns a [Node] containing relevant information
     pub fn read(file: impl AsRef<Path>) -> Result<Self> {
         let file = file.as_ref();
@@ -882,7 +1213,7 @@ impl Node {
             }
         })?;
         let data = SolData::parse(source.as_ref(), file);
-        Ok(Self { path: file.to_path_buf(), source, data })
+        Ok(Self { path: file.to_path_buf(), source, data: Arc::new(data) })
     }
 
     pub fn content(&self) -> &str {
@@ -923,7 +1254,7 @@ impl Node {
         let v = data.data();
 
         let req = crate::Solc::version_req(v)
-            .map_err(|err| SourceVersionError::InvalidVersion(v.to_string(), err))?;
+            .map_err(|err| SourceVersionError::InvalidVersion(v.to_string(), Box::new(err)))?;
 
         if !all_versions.iter().any(|v| req.matches(v.as_ref())) {
             return if offline {
@@ -937,6 +1268,91 @@ impl Node {
     }
 }
 
+/// The resolved imports of a node
+#[derive(Clone, Debug, Default)]
+struct NodeImports {
+    /// the resolved target of every import of the node, `None` if it couldn't be resolved
+    targets: Vec<Option<PathBuf>>,
+    /// the additional `--include-path`s required by the imports
+    include_paths: IncludePaths,
+}
+
+impl NodeImports {
+    /// Resolves all imports of the `node`, the `cwd` is the parent directory of the node
+    fn resolve(
+        paths: &ProjectPathsConfig,
+        cwd: &Path,
+        node: &Node,
+        overlay: &SourceOverlay,
+    ) -> Self {
+        // vyper imports name modules instead of files and are resolved differently
+        let is_vyper = utils::is_vyper_file(&node.path);
+
+        let mut include_paths = IncludePaths::default();
+        let targets = node
+            .data
+            .imports
+            .iter()
+            .map(|import| {
+                let import_path = import.data().path();
+                let resolved = if is_vyper {
+                    paths.resolve_vyper_import(cwd, import_path)
+                } else {
+                    paths.resolve_import_and_include_paths(cwd, import_path, &mut include_paths)
+                };
+                resolved
+                    .or_else(|err| overlay.resolve_import(paths, cwd, import_path).ok_or(err))
+                    .map_err(|err| {
+                        trace!(
+                            "failed to resolve import component \"{:?}\" for {:?}",
+                            err,
+                            node.path
+                        )
+                    })
+                    .ok()
+            })
+            .collect();
+        Self { targets, include_paths }
+    }
+}
+
+/// The parts of a previous graph that can be reused by [`Graph::update()`]
+struct Reuse<'a> {
+    graph: &'a Graph,
+    /// the files that need to be read again
+    changed: &'a HashSet<PathBuf>,
+    /// whether the imports of all nodes need to be resolved again
+    resolve_imports: bool,
+}
+
+impl Reuse<'_> {
+    /// Returns the node of the file, if it didn't change
+    fn node(&self, file: &Path) -> Option<Node> {
+        if self.changed.contains(file) {
+            return None;
+        }
+        self.graph.edges.indices.get(file).map(|idx| self.graph.nodes[*idx].clone())
+    }
+
+    /// Returns the resolved imports of the file, if they can't have changed
+    fn imports(&self, file: &Path) -> Option<&NodeImports> {
+        if self.resolve_imports || self.changed.contains(file) {
+            return None;
+        }
+        self.graph.edges.indices.get(file).map(|idx| &self.graph.node_imports[*idx])
+    }
+}
+
+/// Returns `true` if the file would be one of the input files of [`Graph::resolve()`]
+fn is_input_file(paths: &ProjectPathsConfig, file: &Path) -> bool {
+    let is_source_file = file
+        .extension()
+        .map(|ext| ext == "sol" || ext == "yul" || ext == "vy" || ext == "vyi")
+        .unwrap_or_default();
+    is_source_file
+        && [&paths.sources, &paths.tests, &paths.scripts].iter().any(|dir| file.starts_with(dir))
+}
+
 /// Helper type for formatting a node
 pub(crate) struct DisplayNode<'a> {
     node: &'a Node,
@@ -959,7 +1375,7 @@ impl<'a> fmt::Display for DisplayNode<'a> {
 #[allow(dead_code)]
 enum SourceVersionError {
     #[error("Failed to parse solidity version {0}: {1}")]
-    InvalidVersion(String, SolcError),
+    InvalidVersion(String, Box<SolcError>),
     #[error("No solc version exists that matches the version requirement: {0}")]
     NoMatchingVersion(VersionReq),
     #[error("No solc version installed that matches the version requirement: {0}")]
@@ -1016,6 +1432,80 @@ mod tests {
         assert_eq!(graph.imported_nodes(1).to_vec(), vec![2, 0]);
     }
 
+    #[test]
+    #[cfg(feature = "project-util")]
+    fn can_update_graph() {
+        let project = crate::project_util::TempProject::dapptools().unwrap();
+        let a = project
+            .add_source("A", "pragma solidity ^0.8.10;\nimport \"./B.sol\";\ncontract A {}")
+            .unwrap();
+        let b = project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}").unwrap();
+        let paths = project.paths();
+        let mut graph = Graph::resolve(paths).unwrap();
+        let b_data = graph.node(graph.files()[&b]).data.clone();
+
+        // only the modified file is parsed again
+        project.add_source("A", "pragma solidity ^0.8.0;\ncontract A {}").unwrap();
+        graph.update(paths, [&a]).unwrap();
+        assert!(Arc::ptr_eq(&graph.node(graph.files()[&b]).data, &b_data));
+        assert!(graph.imports(&a).is_empty());
+        assert_eq!(graph.edges.version_requirement(&a).unwrap().to_string(), "^0.8.0");
+
+        let c = project
+            .add_source("C", "pragma solidity ^0.8.10;\nimport \"./B.sol\";\ncontract C {}")
+            .unwrap();
+        std::fs::remove_file(&a).unwrap();
+        graph.update(paths, [&a, &c]).unwrap();
+        assert!(Arc::ptr_eq(&graph.node(graph.files()[&b]).data, &b_data));
+        assert_eq!(graph.files(), Graph::resolve(paths).unwrap().files());
+        assert_eq!(graph.imports(&c), HashSet::from([&b]));
+
+        // a failed update leaves the graph unchanged
+        std::fs::write(&b, [0xff, 0xfe]).unwrap();
+        assert!(graph.update(paths, [&b]).is_err());
+        assert!(Arc::ptr_eq(&graph.node(graph.files()[&b]).data, &b_data));
+    }
+
+    #[test]
+    #[cfg(feature = "project-util")]
+    fn can_detect_import_cycles() {
+        let project = crate::project_util::TempProject::dapptools().unwrap();
+        let add = |name: &str, imports: &[&str]| {
+            let imports =
+                imports.iter().map(|i| format!("import \"./{i}.sol\";\n")).collect::<String>();
+            project
+                .add_source(
+                    name,
+                    format!("pragma solidity ^0.8.10;\n{imports}contract {name} {{}}"),
+                )
+                .unwrap()
+        };
+        let a = add("A", &["B"]);
+        let b = add("B", &["C"]);
+        let c = add("C", &["A", "D"]);
+        add("D", &[]);
+        let e = add("E", &["E", "D"]);
+        add("F", &["A"]);
+
+        let graph = Graph::resolve(project.paths()).unwrap();
+        assert_eq!(graph.import_cycles(), vec![vec![a, b, c], vec![e]]);
+
+        // a component of two cycles through `G` is walked along existing imports only
+        let g = add("G", &["H", "I"]);
+        let h = add("H", &["G"]);
+        let i = add("I", &["G"]);
+        let graph = Graph::resolve(project.paths()).unwrap();
+        let cycles = graph.import_cycles();
+        assert_eq!(cycles[2], vec![g.clone(), h, g, i]);
+        let idx = |file: &PathBuf| graph.files()[file];
+        for cycle in &cycles {
+            for (file, next) in cycle.iter().zip(cycle.iter().cycle().skip(1)) {
+                let imports = &graph.edges.edges[idx(file)];
+                assert!(imports.contains(&idx(next)), "{file:?} doesn't import {next:?}");
+            }
+        }
+    }
+
     #[test]
     #[cfg(not(target_os = "windows"))]
     fn can_print_dapp_sample_graph() {
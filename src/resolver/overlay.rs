@@ -0,0 +1,169 @@
+//! In-memory file contents that take precedence over the files on disk
+
+use super::is_input_file;
+use crate::{
+    artifacts::{Source, Sources},
+    error::SolcIoError,
+    utils, ProjectPathsConfig,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+/// The contents of files that take precedence over the files on disk, like the unsaved buffers of
+/// an editor.
+///
+/// The overlay is used whenever a file is read while resolving the imports of a [`super::Graph`]
+/// and for compiling, see [`crate::ProjectBuilder::overlay()`]. A file of the overlay doesn't need
+/// to exist on disk, new files in the sources, tests or scripts dirs are input files of the
+/// project.
+///
+/// All paths are expected to be absolute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceOverlay {
+    files: BTreeMap<PathBuf, Source>,
+}
+
+impl SourceOverlay {
+    /// Creates an empty overlay
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the content of the file, returns the previous content if there was one
+    pub fn insert(
+        &mut self,
+        file: impl Into<PathBuf>,
+        content: impl Into<String>,
+    ) -> Option<Source> {
+        self.files.insert(file.into(), Source::new(content))
+    }
+
+    /// Removes the file from the overlay, so it's read from disk again
+    pub fn remove(&mut self, file: impl AsRef<Path>) -> Option<Source> {
+        self.files.remove(file.as_ref())
+    }
+
+    /// Returns the content of the file if it's part of the overlay
+    pub fn get(&self, file: impl AsRef<Path>) -> Option<&Source> {
+        self.files.get(file.as_ref())
+    }
+
+    /// Returns `true` if the file is part of the overlay
+    pub fn contains(&self, file: impl AsRef<Path>) -> bool {
+        self.files.contains_key(file.as_ref())
+    }
+
+    /// Returns an iterator over all files of the overlay
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.keys()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns the content of the file, from the overlay if it's part of it or from disk
+    pub fn read(&self, file: impl AsRef<Path>) -> Result<Source, SolcIoError> {
+        let file = file.as_ref();
+        match self.get(file) {
+            Some(source) => Ok(source.clone()),
+            None => Source::read(file),
+        }
+    }
+
+    /// Same as [`Source::read_all()`], but files of the overlay aren't read from disk
+    pub fn read_all<T, I>(&self, files: I) -> Result<Sources, SolcIoError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<PathBuf>,
+    {
+        let (overlaid, files): (Vec<_>, Vec<_>) =
+            files.into_iter().map(Into::into).partition(|file| self.contains(file));
+        let mut sources = Source::read_all(files)?;
+        sources.extend(overlaid.into_iter().map(|file| {
+            let source = self.files[&file].clone();
+            (file, source)
+        }));
+        Ok(sources)
+    }
+
+    /// Returns all input files of the project, the files in the sources, tests and scripts dirs
+    /// together with those files of the overlay that don't exist yet, see
+    /// [`ProjectPathsConfig::input_files()`]
+    pub fn input_files(&self, paths: &ProjectPathsConfig) -> Vec<PathBuf> {
+        let mut files = paths.input_files();
+        let existing = files.iter().cloned().collect::<BTreeSet<_>>();
+        files.extend(
+            self.files()
+                .filter(|file| !existing.contains(*file) && is_input_file(paths, file))
+                .cloned(),
+        );
+        files
+    }
+
+    /// Reads all input files of the project, see [`Self::input_files()`]
+    pub fn read_input_files(&self, paths: &ProjectPathsConfig) -> Result<Sources, SolcIoError> {
+        self.read_all(self.input_files(paths))
+    }
+
+    /// Replaces the contents of all `sources` that are part of the overlay
+    pub(crate) fn apply(&self, sources: &mut Sources) {
+        if self.is_empty() {
+            return;
+        }
+        for (file, source) in sources.iter_mut() {
+            if let Some(overlaid) = self.get(file) {
+                *source = overlaid.clone();
+            }
+        }
+    }
+
+    /// Resolves an import of a file in `cwd` to a file of the overlay that doesn't exist on disk,
+    /// see [`ProjectPathsConfig::resolve_import()`] for the resolution of existing files
+    pub(crate) fn resolve_import(
+        &self,
+        paths: &ProjectPathsConfig,
+        cwd: &Path,
+        import: &Path,
+    ) -> Option<PathBuf> {
+        if self.is_empty() {
+            return None;
+        }
+        if let Some(remapped) = paths.resolve_remapped_import(cwd, import) {
+            let file = utils::clean_solidity_path(paths.root.join(remapped));
+            if self.contains(&file) {
+                return Some(file);
+            }
+        }
+        let is_relative = import.starts_with(".") || import.starts_with("..");
+        let dirs = if is_relative {
+            vec![cwd]
+        } else {
+            [&paths.root, &paths.sources, &paths.tests, &paths.scripts]
+                .into_iter()
+                .chain(paths.libraries.iter())
+                .map(PathBuf::as_path)
+                .collect()
+        };
+        dirs.into_iter()
+            .map(|dir| utils::clean_solidity_path(dir.join(import)))
+            .find(|file| self.contains(file))
+    }
+}
+
+impl<P: Into<PathBuf>, S: Into<String>> FromIterator<(P, S)> for SourceOverlay {
+    fn from_iter<I: IntoIterator<Item = (P, S)>>(iter: I) -> Self {
+        Self {
+            files: iter
+                .into_iter()
+                .map(|(file, content)| (file.into(), Source::new(content)))
+                .collect(),
+        }
+    }
+}
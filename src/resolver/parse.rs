@@ -1,4 +1,4 @@
-use crate::{utils, Solc};
+use crate::{compilers::vyper::Vyper, utils, Solc};
 use semver::VersionReq;
 use solang_parser::pt::{
     ContractPart, ContractTy, FunctionAttribute, FunctionDefinition, Import, ImportPath, Loc,
@@ -38,6 +38,10 @@ impl SolData {
     /// This will attempt to parse the solidity AST and extract the imports and version pragma. If
     /// parsing fails, we'll fall back to extract that info via regex
     pub fn parse(content: &str, file: &Path) -> Self {
+        if utils::is_vyper_file(file) {
+            return Self::parse_vyper(content);
+        }
+
         let mut version = None;
         let mut experimental = None;
         let mut imports = Vec::<SolDataUnit<SolImport>>::new();
@@ -79,7 +83,7 @@ impl SolData {
                             imports.push(SolDataUnit::from_loc(sol_import, loc));
                         }
                         SourceUnitPart::ContractDefinition(def) => {
-                            let functions = def
+                            let functions: Vec<_> = def
                                 .parts
                                 .into_iter()
                                 .filter_map(|part| match part {
@@ -88,15 +92,36 @@ impl SolData {
                                 })
                                 .collect();
                             if let Some(name) = def.name {
-                                match def.ty {
-                                    ContractTy::Contract(_) => {
-                                        contracts.push(SolContract { name: name.name, functions });
-                                    }
+                                let kind = match def.ty {
+                                    ContractTy::Contract(_) => SolContractKind::Contract,
+                                    ContractTy::Abstract(_) => SolContractKind::Abstract,
+                                    ContractTy::Interface(_) => SolContractKind::Interface,
                                     ContractTy::Library(_) => {
-                                        libraries.push(SolLibrary { name: name.name, functions });
+                                        libraries.push(SolLibrary {
+                                            name: name.name.clone(),
+                                            functions: functions.clone(),
+                                        });
+                                        SolContractKind::Library
                                     }
-                                    _ => {}
-                                }
+                                };
+                                let bases = def
+                                    .base
+                                    .into_iter()
+                                    .map(|base| {
+                                        base.name
+                                            .identifiers
+                                            .into_iter()
+                                            .map(|id| id.name)
+                                            .collect::<Vec<_>>()
+                                            .join(".")
+                                    })
+                                    .collect();
+                                contracts.push(SolContract {
+                                    name: name.name,
+                                    kind,
+                                    bases,
+                                    functions,
+                                });
                             }
                         }
                         _ => {}
@@ -126,6 +151,27 @@ impl SolData {
         Self { version_req, version, experimental, imports, license, libraries, contracts }
     }
 
+    /// Extracts the version pragma and the imported modules from a vyper source
+    ///
+    /// Imports of the builtin `vyper` and `ethereum` modules are skipped, see
+    /// [`capture_vyper_imports`]
+    pub fn parse_vyper(content: &str) -> Self {
+        let version = capture_outer_and_inner(content, &utils::RE_VYPER_VERSION, &["version"])
+            .first()
+            .map(|(cap, name)| SolDataUnit::new(name.as_str().to_owned(), cap.range()));
+        let version_req = version.as_ref().and_then(|v| Vyper::version_req(v.data()).ok());
+
+        Self {
+            version_req,
+            version,
+            experimental: None,
+            imports: capture_vyper_imports(content),
+            license: None,
+            libraries: Vec::new(),
+            contracts: Vec::new(),
+        }
+    }
+
     /// Returns `true` if the solidity file associated with this type contains a solidity library
     /// that won't be inlined
     pub fn has_link_references(&self) -> bool {
@@ -133,13 +179,26 @@ impl SolData {
     }
 }
 
-/// Minimal representation of a contract inside a solidity file
+/// Minimal representation of a contract, interface or library inside a solidity file
 #[derive(Debug)]
 pub struct SolContract {
     pub name: String,
+    pub kind: SolContractKind,
+    /// The names of the inherited contracts as they appear in the `is` list, like `Base` or
+    /// `Lib.Base`
+    pub bases: Vec<String>,
     pub functions: Vec<FunctionDefinition>,
 }
 
+/// The kind of a contract definition in a solidity file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SolContractKind {
+    Contract,
+    Abstract,
+    Interface,
+    Library,
+}
+
 #[derive(Debug, Clone)]
 pub struct SolImport {
     path: PathBuf,
@@ -288,6 +347,66 @@ pub fn capture_imports(content: &str) -> Vec<SolDataUnit<SolImport>> {
     imports
 }
 
+/// Capture the modules imported by a vyper source.
+///
+/// Vyper imports name modules, so the import path is the module path without an extension:
+/// `import lib.math as math` becomes `lib/math` and `from .interfaces import IToken` becomes
+/// `./interfaces/IToken`. The file extension is determined when the import is resolved, see
+/// [`crate::ProjectPathsConfig::resolve_vyper_import`].
+pub fn capture_vyper_imports(content: &str) -> Vec<SolDataUnit<SolImport>> {
+    let mut imports = vec![];
+    for cap in utils::RE_VYPER_IMPORT.captures_iter(content) {
+        let statement_match = cap.get(0).unwrap();
+        let alias = cap.name("alias").map(|alias| alias.as_str().to_owned());
+        let (module, aliases) = match (cap.name("from"), cap.name("name"), cap.name("module")) {
+            (Some(from), Some(name), _) => {
+                let from = from.as_str();
+                let module = if from.ends_with('.') {
+                    format!("{from}{}", name.as_str())
+                } else {
+                    format!("{from}.{}", name.as_str())
+                };
+                let aliases = alias
+                    .map(|alias| vec![SolImportAlias::Contract(alias, name.as_str().to_owned())])
+                    .unwrap_or_default();
+                (module, aliases)
+            }
+            (_, _, Some(module)) => {
+                (module.as_str().to_owned(), alias.map(SolImportAlias::File).into_iter().collect())
+            }
+            _ => continue,
+        };
+
+        let Some(path) = vyper_module_path(&module) else { continue };
+        let import = SolImport::new(path).set_aliases(aliases);
+        imports.push(SolDataUnit::new(import, statement_match.range()));
+    }
+    imports
+}
+
+/// Converts a dotted vyper module path into a relative path without extension
+///
+/// Returns `None` for the builtin `vyper` and `ethereum` modules which don't exist on disk.
+fn vyper_module_path(module: &str) -> Option<PathBuf> {
+    let relative = module.trim_start_matches('.');
+    let level = module.len() - relative.len();
+    if level == 0 {
+        let root = relative.split('.').next().unwrap_or_default();
+        if root == "vyper" || root == "ethereum" {
+            return None;
+        }
+    }
+
+    let mut path = PathBuf::new();
+    match level {
+        0 => {}
+        1 => path.push("."),
+        _ => (1..level).for_each(|_| path.push("..")),
+    }
+    relative.split('.').filter(|s| !s.is_empty()).for_each(|segment| path.push(segment));
+    Some(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -342,4 +461,70 @@ import { FloatMath as Math, Math as FloatMath } from "./Math.sol";
             ]
         );
     }
+
+    #[test]
+    fn can_parse_vyper_imports_and_version() {
+        let content = r#"
+# pragma version ^0.3.10
+
+from vyper.interfaces import ERC20
+from ethereum.ercs import IERC20
+import interfaces.IToken as IToken
+from . import utils
+from ..lib import math as m
+from snekmate.auth import ownable
+
+@external
+def foo():
+    pass
+"#;
+        let data = SolData::parse(content, Path::new("src/Token.vy"));
+        assert_eq!(data.version.as_ref().map(|v| v.data().as_str()), Some("^0.3.10"));
+        assert_eq!(data.version_req, Some(VersionReq::parse("^0.3.10").unwrap()));
+
+        let imports = data.imports.iter().map(|i| i.data().path().clone()).collect::<Vec<_>>();
+        assert_eq!(
+            imports,
+            vec![
+                PathBuf::from("interfaces/IToken"),
+                "./utils".into(),
+                "../lib/math".into(),
+                "snekmate/auth/ownable".into(),
+            ]
+        );
+        assert_eq!(data.imports[0].data().aliases(), &vec![SolImportAlias::File("IToken".into())]);
+        assert_eq!(
+            data.imports[2].data().aliases(),
+            &vec![SolImportAlias::Contract("m".into(), "math".into())]
+        );
+    }
+
+    #[test]
+    fn can_parse_contract_kinds_and_bases() {
+        let content = r#"
+pragma solidity ^0.8.10;
+import "./Lib.sol" as Lib;
+
+interface IToken {}
+abstract contract Base is IToken {}
+library Math { function add(uint a, uint b) public pure returns (uint) { return a + b; } }
+contract Token is Base, Lib.Ownable(msg.sender) {}
+"#;
+        let data = SolData::parse(content, Path::new("src/Token.sol"));
+        let contracts = data
+            .contracts
+            .iter()
+            .map(|c| (c.name.as_str(), c.kind, c.bases.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contracts,
+            vec![
+                ("IToken", SolContractKind::Interface, vec![]),
+                ("Base", SolContractKind::Abstract, vec!["IToken".to_string()]),
+                ("Math", SolContractKind::Library, vec![]),
+                ("Token", SolContractKind::Contract, vec!["Base".into(), "Lib.Ownable".into()]),
+            ]
+        );
+        assert!(data.has_link_references());
+    }
 }
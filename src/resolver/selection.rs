@@ -0,0 +1,359 @@
+//! Strategies for selecting the compiler versions of the input files of a [`Graph`](super::Graph)
+
+use crate::SolcVersion;
+use semver::Version;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::PathBuf,
+};
+
+#[cfg(feature = "svm-solc")]
+use std::collections::HashSet;
+
+/// How the compiler versions of the input files are selected if the project auto detects them.
+///
+/// Every strategy only considers versions that are compatible with a file and all of its imports
+/// and prefers installed versions over the ones that would need to be installed first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum VersionSelectionStrategy {
+    /// Uses the newest version that's compatible with all files if there is one. Otherwise every
+    /// file gets the newest compatible version that's already used for another file, or the
+    /// newest compatible version if there's none.
+    #[default]
+    Newest,
+    /// Uses as few distinct versions as possible, by repeatedly selecting the version that's
+    /// compatible with most of the remaining files. Every file then gets the newest of the
+    /// selected versions it's compatible with.
+    Fewest,
+    /// Uses the given version for every file that's compatible with it, the remaining files are
+    /// resolved with [`Self::Newest`].
+    Preferred(Version),
+}
+
+/// Why a version was selected for a file, see [`VersionSelectionStrategy`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VersionSelectionReason {
+    /// The newest version that's compatible with all files
+    CompatibleWithAll,
+    /// The newest version that's compatible with the file and its imports
+    Newest,
+    /// The newest compatible version that's also used for other files
+    Reused,
+    /// The newest of the fewest versions that are compatible with all files
+    Fewest,
+    /// The preferred version of [`VersionSelectionStrategy::Preferred`]
+    Preferred,
+}
+
+impl fmt::Display for VersionSelectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            VersionSelectionReason::CompatibleWithAll => "newest version compatible with all files",
+            VersionSelectionReason::Newest => "newest compatible version",
+            VersionSelectionReason::Reused => "newest compatible version used by other files",
+            VersionSelectionReason::Fewest => "newest of the fewest versions for all files",
+            VersionSelectionReason::Preferred => "preferred version",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// The version selected for a single file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectedVersion {
+    pub version: SolcVersion,
+    pub reason: VersionSelectionReason,
+}
+
+/// The versions selected for all input files of a [`Graph`](super::Graph), see
+/// [`Graph::select_versions()`](super::Graph::select_versions)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionSelection {
+    /// The selected version of every input file
+    pub files: BTreeMap<PathBuf, SelectedVersion>,
+}
+
+impl VersionSelection {
+    /// Returns all distinct versions that were selected
+    pub fn versions(&self) -> BTreeSet<&SolcVersion> {
+        self.files.values().map(|selected| &selected.version).collect()
+    }
+
+    /// Returns all input files grouped by their selected version
+    pub fn files_by_version(&self) -> BTreeMap<&SolcVersion, Vec<&PathBuf>> {
+        let mut files: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (file, selected) in self.files.iter() {
+            files.entry(&selected.version).or_default().push(file);
+        }
+        files
+    }
+}
+
+impl fmt::Display for VersionSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (file, selected) in self.files.iter() {
+            writeln!(f, "{}: {} ({})", file.display(), selected.version, selected.reason)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "svm-solc")]
+impl VersionSelectionStrategy {
+    /// Selects a version for every node from its sorted, non-empty list of compatible versions.
+    ///
+    /// Returns the selected versions in the order of the `candidates`.
+    pub(crate) fn select(
+        &self,
+        candidates: Vec<(usize, Vec<&SolcVersion>)>,
+    ) -> Vec<(usize, SelectedVersion)> {
+        match self {
+            VersionSelectionStrategy::Newest => select_newest(candidates),
+            VersionSelectionStrategy::Fewest => select_fewest(candidates),
+            VersionSelectionStrategy::Preferred(preferred) => {
+                let (compatible, fallback): (Vec<_>, Vec<_>) = candidates
+                    .into_iter()
+                    .partition(|(_, versions)| versions.iter().any(|v| v.as_ref() == preferred));
+                let mut selected = select_newest(fallback);
+                // only the remaining files were considered
+                for (_, selected) in selected.iter_mut() {
+                    if selected.reason == VersionSelectionReason::CompatibleWithAll {
+                        selected.reason = VersionSelectionReason::Newest;
+                    }
+                }
+                selected.extend(compatible.into_iter().map(|(node, versions)| {
+                    let version = versions
+                        .into_iter()
+                        .filter(|v| v.as_ref() == preferred)
+                        .max_by_key(|v| v.is_installed())
+                        .expect("contains preferred version; qed.")
+                        .clone();
+                    (node, SelectedVersion { version, reason: VersionSelectionReason::Preferred })
+                }));
+                selected.sort_unstable_by_key(|(node, _)| *node);
+                selected
+            }
+        }
+    }
+}
+
+/// Returns the highest version that is installed, if the candidates only contain uninstalled
+/// versions then this returns the highest uninstalled version
+#[cfg(feature = "svm-solc")]
+fn newest<'a>(candidates: &[&'a SolcVersion]) -> &'a SolcVersion {
+    debug_assert!(!candidates.is_empty());
+    candidates
+        .iter()
+        .rev()
+        .find(|v| v.is_installed())
+        .or_else(|| candidates.last())
+        .expect("not empty; qed.")
+}
+
+/// See [`VersionSelectionStrategy::Newest`] and [Solc version
+/// auto-detection](super#solc-version-auto-detection)
+#[cfg(feature = "svm-solc")]
+fn select_newest(candidates: Vec<(usize, Vec<&SolcVersion>)>) -> Vec<(usize, SelectedVersion)> {
+    let selected =
+        |version: &SolcVersion, reason| SelectedVersion { version: version.clone(), reason };
+
+    let newest_versions =
+        candidates.iter().map(|(_, versions)| newest(versions)).collect::<HashSet<_>>();
+    if newest_versions.len() <= 1 {
+        trace!("found exact solc version for all sources  \"{:?}\"", newest_versions);
+        return candidates
+            .into_iter()
+            .map(|(node, versions)| {
+                (node, selected(newest(&versions), VersionSelectionReason::CompatibleWithAll))
+            })
+            .collect();
+    }
+
+    // detected multiple versions but there might still exist a single version that satisfies all
+    // sources
+    let mut intersection = candidates[0].1.clone();
+    for (_, versions) in candidates.iter().skip(1) {
+        let versions = versions.iter().collect::<HashSet<_>>();
+        intersection.retain(|v| versions.contains(v));
+    }
+    if !intersection.is_empty() {
+        let exact_version = newest(&intersection);
+        trace!("resolved solc version compatible with all sources  \"{}\"", exact_version);
+        return candidates
+            .into_iter()
+            .map(|(node, _)| {
+                (node, selected(exact_version, VersionSelectionReason::CompatibleWithAll))
+            })
+            .collect();
+    }
+
+    // no version satisfies all nodes, try to minimize the set of versions by reusing versions
+    let mut used = HashSet::new();
+    let selected = candidates
+        .into_iter()
+        .map(|(node, versions)| {
+            let selected = if let Some(version) = versions.iter().rev().find(|v| used.contains(**v))
+            {
+                selected(version, VersionSelectionReason::Reused)
+            } else {
+                selected(newest(&versions), VersionSelectionReason::Newest)
+            };
+            used.insert(selected.version.clone());
+            (node, selected)
+        })
+        .collect();
+
+    trace!(
+        "no solc version can satisfy all source files, resolved multiple versions  \"{:?}\"",
+        used
+    );
+
+    selected
+}
+
+/// See [`VersionSelectionStrategy::Fewest`]
+#[cfg(feature = "svm-solc")]
+fn select_fewest(candidates: Vec<(usize, Vec<&SolcVersion>)>) -> Vec<(usize, SelectedVersion)> {
+    // versions are ranked by the number of files they cover, then installed and newest first
+    let rank = |v: &SolcVersion| (v.is_installed(), v.as_ref().clone());
+
+    // greedy set cover, finding the actual minimum is NP-hard
+    let mut chosen = HashSet::new();
+    let mut uncovered = candidates.iter().collect::<Vec<_>>();
+    while !uncovered.is_empty() {
+        let mut covered_files: BTreeMap<&SolcVersion, usize> = BTreeMap::new();
+        for (_, versions) in uncovered.iter() {
+            for version in versions.iter() {
+                *covered_files.entry(*version).or_default() += 1;
+            }
+        }
+        let (version, _) = covered_files
+            .into_iter()
+            .max_by_key(|(version, count)| (*count, rank(version)))
+            .expect("candidates are not empty; qed.");
+        uncovered.retain(|(_, versions)| !versions.contains(&version));
+        chosen.insert(version);
+    }
+
+    let reason = if chosen.len() == 1 {
+        VersionSelectionReason::CompatibleWithAll
+    } else {
+        VersionSelectionReason::Fewest
+    };
+    candidates
+        .into_iter()
+        .map(|(node, versions)| {
+            let version = versions
+                .into_iter()
+                .filter(|v| chosen.contains(v))
+                .max_by_key(|v| rank(v))
+                .expect("every file is covered; qed.")
+                .clone();
+            (node, SelectedVersion { version, reason })
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "svm-solc"))]
+mod tests {
+    use super::*;
+
+    fn installed(version: &str) -> SolcVersion {
+        SolcVersion::Installed(version.parse().unwrap())
+    }
+
+    fn remote(version: &str) -> SolcVersion {
+        SolcVersion::Remote(version.parse().unwrap())
+    }
+
+    fn select(
+        strategy: VersionSelectionStrategy,
+        candidates: &[Vec<&SolcVersion>],
+    ) -> Vec<(String, VersionSelectionReason)> {
+        strategy
+            .select(candidates.iter().cloned().enumerate().collect())
+            .into_iter()
+            .map(|(_, selected)| (selected.version.to_string(), selected.reason))
+            .collect()
+    }
+
+    #[test]
+    fn can_select_newest_versions() {
+        let (v1, v2, v3) = (installed("0.8.10"), installed("0.8.11"), remote("0.8.12"));
+
+        // one version is compatible with all files
+        assert_eq!(
+            select(VersionSelectionStrategy::Newest, &[vec![&v1, &v2, &v3], vec![&v1, &v2]]),
+            vec![
+                ("0.8.11".to_string(), VersionSelectionReason::CompatibleWithAll),
+                ("0.8.11".to_string(), VersionSelectionReason::CompatibleWithAll)
+            ]
+        );
+
+        // installed versions are preferred
+        assert_eq!(
+            select(VersionSelectionStrategy::Newest, &[vec![&v1, &v2, &v3]]),
+            vec![("0.8.11".to_string(), VersionSelectionReason::CompatibleWithAll)]
+        );
+
+        assert_eq!(
+            select(
+                VersionSelectionStrategy::Newest,
+                &[vec![&v1], vec![&v2, &v3], vec![&v1, &v2, &v3], vec![&v3]]
+            ),
+            vec![
+                ("0.8.10".to_string(), VersionSelectionReason::Newest),
+                ("0.8.11".to_string(), VersionSelectionReason::Newest),
+                ("0.8.11".to_string(), VersionSelectionReason::Reused),
+                ("0.8.12".to_string(), VersionSelectionReason::Newest)
+            ]
+        );
+    }
+
+    #[test]
+    fn can_select_fewest_versions() {
+        let (v1, v2, v3) = (installed("0.8.10"), installed("0.8.11"), installed("0.8.12"));
+        let candidates = [vec![&v1, &v2], vec![&v3], vec![&v2, &v3], vec![&v1]];
+
+        // the newest strategy needs three versions
+        assert_eq!(
+            select(VersionSelectionStrategy::Newest, &candidates)
+                .into_iter()
+                .map(|(v, _)| v)
+                .collect::<BTreeSet<_>>()
+                .len(),
+            3
+        );
+        assert_eq!(
+            select(VersionSelectionStrategy::Fewest, &candidates),
+            vec![
+                ("0.8.10".to_string(), VersionSelectionReason::Fewest),
+                ("0.8.12".to_string(), VersionSelectionReason::Fewest),
+                ("0.8.12".to_string(), VersionSelectionReason::Fewest),
+                ("0.8.10".to_string(), VersionSelectionReason::Fewest)
+            ]
+        );
+        assert_eq!(
+            select(VersionSelectionStrategy::Fewest, &[vec![&v1, &v2], vec![&v2, &v3]]),
+            vec![
+                ("0.8.11".to_string(), VersionSelectionReason::CompatibleWithAll),
+                ("0.8.11".to_string(), VersionSelectionReason::CompatibleWithAll)
+            ]
+        );
+    }
+
+    #[test]
+    fn can_select_preferred_version() {
+        let (v1, v2, v3) = (installed("0.8.10"), installed("0.8.11"), installed("0.8.12"));
+        let strategy = VersionSelectionStrategy::Preferred("0.8.11".parse().unwrap());
+        assert_eq!(
+            select(strategy, &[vec![&v1, &v2, &v3], vec![&v3], vec![&v2]]),
+            vec![
+                ("0.8.11".to_string(), VersionSelectionReason::Preferred),
+                ("0.8.12".to_string(), VersionSelectionReason::Newest),
+                ("0.8.11".to_string(), VersionSelectionReason::Preferred)
+            ]
+        );
+    }
+}
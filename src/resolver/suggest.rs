@@ -0,0 +1,328 @@
+//! Suggestions for imports that couldn't be resolved
+
+use crate::{remappings::Remapping, utils, ProjectPathsConfig};
+use std::{
+    collections::{BTreeSet, HashMap},
+    ffi::OsStr,
+    fmt, fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// An import that couldn't be resolved, together with suggestions how to fix it
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnresolvedImport {
+    /// The path of the import statement, like `@openzeppelin/contracts/token/ERC20/ERC20.sol`
+    pub import: PathBuf,
+    /// The file that contains the import
+    pub file: PathBuf,
+    /// Possible fixes for the import, empty if no file the import could refer to was found
+    pub suggestions: Vec<ImportSuggestion>,
+}
+
+impl UnresolvedImport {
+    /// Searches the library dirs and `node_modules` of the project for files the `import` of
+    /// `file` could refer to.
+    pub fn new(paths: &ProjectPathsConfig, import: PathBuf, file: PathBuf) -> Self {
+        Self::find_all(paths, [(import, file)]).pop().expect("one import")
+    }
+
+    /// Same as [`Self::new()`] for multiple `(import, file)` pairs, but searches the library dirs
+    /// only once.
+    pub fn find_all(
+        paths: &ProjectPathsConfig,
+        imports: impl IntoIterator<Item = (PathBuf, PathBuf)>,
+    ) -> Vec<Self> {
+        let mut candidates: Option<Candidates> = None;
+        imports
+            .into_iter()
+            .map(|(import, file)| {
+                let suggestions = if utils::is_vyper_file(&file) {
+                    // vyper imports name modules, not files
+                    Vec::new()
+                } else if is_relative(&import) {
+                    suggest_relative(&import, &file)
+                } else {
+                    let candidates = candidates.get_or_insert_with(|| Candidates::new(paths));
+                    suggest_library(paths, &import, &file, candidates)
+                };
+                Self { import, file, suggestions }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for UnresolvedImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" in \"{}\"", self.import.display(), self.file.display())?;
+        if !self.suggestions.is_empty() {
+            write!(f, "; {}", format_suggestions(&self.suggestions))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the suggestions as a question like `did you mean "a.sol" or "A.sol"?`, empty if there
+/// are none
+pub(crate) fn format_suggestions(suggestions: &[ImportSuggestion]) -> String {
+    let mut msg = String::new();
+    for (idx, suggestion) in suggestions.iter().enumerate() {
+        if idx == 0 {
+            msg.push_str(&format!("did you mean {suggestion}"));
+        } else {
+            msg.push_str(&format!(" or {suggestion}"));
+        }
+    }
+    if !suggestions.is_empty() {
+        msg.push('?');
+    }
+    msg
+}
+
+/// A possible fix for an [`UnresolvedImport`]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImportSuggestion {
+    /// The remapping that's missing to resolve the import, like
+    /// `@openzeppelin/=lib/openzeppelin-contracts/`
+    Remapping(Remapping),
+    /// The import path with the case of an existing file, like `forge-std/Test.sol` for
+    /// `forge-std/test.sol`
+    Path(PathBuf),
+}
+
+impl fmt::Display for ImportSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportSuggestion::Remapping(remapping) => write!(f, "the remapping \"{remapping}\""),
+            ImportSuggestion::Path(path) => write!(f, "\"{}\"", path.display()),
+        }
+    }
+}
+
+/// All files in the library dirs and `node_modules`, by their lowercase file name
+struct Candidates(HashMap<String, Vec<PathBuf>>);
+
+impl Candidates {
+    fn new(paths: &ProjectPathsConfig) -> Self {
+        let mut dirs = paths.libraries.iter().cloned().collect::<BTreeSet<_>>();
+        let node_modules = paths.root.join("node_modules");
+        if node_modules.is_dir() {
+            dirs.insert(node_modules);
+        }
+
+        let mut files: HashMap<_, Vec<_>> = HashMap::new();
+        for file in dirs.iter().flat_map(utils::source_files_iter) {
+            if let Some(name) = file.file_name() {
+                files.entry(name.to_string_lossy().to_lowercase()).or_default().push(file);
+            }
+        }
+        Self(files)
+    }
+
+    fn get(&self, import: &Path) -> &[PathBuf] {
+        import
+            .file_name()
+            .and_then(|name| self.0.get(&name.to_string_lossy().to_lowercase()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Suggests remappings or the correct case for a library import, from the files with the most
+/// trailing path components in common with the import
+fn suggest_library(
+    paths: &ProjectPathsConfig,
+    import: &Path,
+    file: &Path,
+    candidates: &Candidates,
+) -> Vec<ImportSuggestion> {
+    let import_components = normal_components(import);
+    let cwd = file.parent().unwrap_or(file);
+
+    let matches = candidates
+        .get(import)
+        .iter()
+        .map(|candidate| {
+            let components = normal_components(candidate);
+            let common = import_components
+                .iter()
+                .rev()
+                .zip(components.iter().rev())
+                .take_while(|(a, b)| a.to_string_lossy().eq_ignore_ascii_case(&b.to_string_lossy()))
+                .count();
+            (candidate, components, common)
+        })
+        .collect::<Vec<_>>();
+    let Some(most_common) = matches.iter().map(|(_, _, common)| *common).max() else {
+        return Vec::new();
+    };
+
+    let mut suggestions = BTreeSet::new();
+    for (candidate, components, common) in matches {
+        if common < most_common {
+            continue;
+        }
+        let (import_prefix, import_suffix) =
+            import_components.split_at(import_components.len() - common);
+        let candidate_suffix = &components[components.len() - common..];
+
+        let is_cased = import_suffix == candidate_suffix;
+        let corrected = import_prefix.iter().chain(candidate_suffix).collect::<PathBuf>();
+        if !is_cased && paths.resolve_library_import(cwd, &corrected).is_some() {
+            // only the case is wrong
+            suggestions.insert(ImportSuggestion::Path(corrected));
+            continue;
+        }
+
+        if !import_prefix.is_empty() {
+            let mut path = (*candidate).clone();
+            for _ in 0..common {
+                path.pop();
+            }
+            let path = utils::source_name(&path, &paths.root);
+            suggestions.insert(ImportSuggestion::Remapping(Remapping {
+                context: None,
+                name: format!("{}/", import_prefix.iter().collect::<PathBuf>().display()),
+                path: format!("{}/", path.display()),
+            }));
+        }
+        if !is_cased {
+            suggestions.insert(ImportSuggestion::Path(corrected));
+        }
+    }
+    suggestions.into_iter().collect()
+}
+
+/// Suggests the correct case for a relative import
+fn suggest_relative(import: &Path, file: &Path) -> Vec<ImportSuggestion> {
+    let Some(cwd) = file.parent() else { return Vec::new() };
+    let Some(existing) = find_case_insensitive(&utils::clean_solidity_path(cwd.join(import)))
+    else {
+        return Vec::new();
+    };
+
+    // replace the trailing file and directory names of the import with the existing ones
+    let names = normal_components(import).len();
+    let mut components = import.components().collect::<Vec<_>>();
+    components.truncate(components.len() - names);
+    let existing = normal_components(&existing);
+    let corrected = components
+        .iter()
+        .map(|c| c.as_os_str())
+        .chain(existing[existing.len() - names..].iter().copied())
+        .collect();
+    vec![ImportSuggestion::Path(corrected)]
+}
+
+/// Returns the existing file that matches the `path` if the case is ignored
+fn find_case_insensitive(path: &Path) -> Option<PathBuf> {
+    let mut found = PathBuf::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            found.push(component);
+            continue;
+        };
+        let exact = found.join(name);
+        if exact.exists() {
+            found = exact;
+            continue;
+        }
+        let name = name.to_string_lossy();
+        let entry =
+            fs::read_dir(if found.as_os_str().is_empty() { Path::new(".") } else { &found })
+                .ok()?
+                .filter_map(Result::ok)
+                .find(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case(&name))?;
+        found.push(entry.file_name());
+    }
+    found.is_file().then_some(found)
+}
+
+/// Returns the file and directory names of the path
+fn normal_components(path: &Path) -> Vec<&OsStr> {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns `true` if the import starts with `./` or `../`
+fn is_relative(import: &Path) -> bool {
+    matches!(import.components().next(), Some(Component::CurDir | Component::ParentDir))
+}
+
+#[cfg(all(test, feature = "project-util"))]
+mod tests {
+    use super::*;
+    use crate::project_util::TempProject;
+
+    #[test]
+    fn can_suggest_missing_remapping() {
+        let project = TempProject::dapptools().unwrap();
+        project
+            .add_lib("openzeppelin-contracts/contracts/token/ERC20/ERC20", "contract ERC20 {}")
+            .unwrap();
+        let file = project.add_source("Token", "").unwrap();
+
+        let unresolved = UnresolvedImport::new(
+            project.paths(),
+            "@openzeppelin/contracts/token/ERC20/ERC20.sol".into(),
+            file,
+        );
+        assert_eq!(
+            unresolved.suggestions,
+            vec![ImportSuggestion::Remapping(
+                "@openzeppelin/=lib/openzeppelin-contracts/".parse().unwrap()
+            )]
+        );
+        assert!(unresolved.to_string().ends_with(
+            "did you mean the remapping \"@openzeppelin/=lib/openzeppelin-contracts/\"?"
+        ));
+    }
+
+    #[test]
+    fn can_suggest_fixes_for_failed_imports() {
+        let mut project = TempProject::dapptools().unwrap();
+        project
+            .add_lib("openzeppelin-contracts/contracts/token/ERC20/ERC20", "contract ERC20 {}")
+            .unwrap();
+        project
+            .add_source("Token", "import \"@openzeppelin/contracts/token/ERC20/ERC20.sol\";")
+            .unwrap();
+        let remapping = format!("@openzeppelin/={}/", project.root().join("lib/oz").display());
+        project.project_mut().paths.remappings.push(remapping.parse().unwrap());
+
+        let err = crate::Graph::resolve(project.paths()).unwrap_err();
+        assert!(matches!(err, crate::error::SolcError::FailedResolveImport(..)), "{err}");
+        assert!(err.to_string().ends_with(
+            "did you mean the remapping \"@openzeppelin/=lib/openzeppelin-contracts/\"?"
+        ));
+    }
+
+    #[test]
+    fn can_suggest_import_case() {
+        let project = TempProject::dapptools().unwrap();
+        project.add_lib("forge-std/src/Test", "contract Test {}").unwrap();
+        project.add_source("Token", "contract Token {}").unwrap();
+        let file = project.add_source("test/Token.t", "").unwrap();
+
+        let unresolved = UnresolvedImport::find_all(
+            project.paths(),
+            [
+                ("forge-std/test.sol".into(), file.clone()),
+                ("../token.sol".into(), file.clone()),
+                ("missing/Missing.sol".into(), file),
+            ],
+        );
+        let suggestions = unresolved.into_iter().map(|u| u.suggestions).collect::<Vec<_>>();
+        assert_eq!(
+            suggestions,
+            vec![
+                vec![ImportSuggestion::Path("forge-std/Test.sol".into())],
+                vec![ImportSuggestion::Path("../Token.sol".into())],
+                vec![],
+            ]
+        );
+    }
+}
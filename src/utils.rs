@@ -13,6 +13,7 @@ use std::{
     io::Write,
     ops::Range,
     path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 use walkdir::WalkDir;
 
@@ -34,6 +35,26 @@ pub static RE_SOL_IMPORT_ALIAS: Lazy<Regex> =
 pub static RE_SOL_PRAGMA_VERSION: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"pragma\s+solidity\s+(?P<version>.+?);").unwrap());
 
+/// A regex that matches the version part of a vyper version pragma
+/// as follows: `# pragma version ^0.3.10` or `# @version ^0.3.10` => `^0.3.10`
+/// statement with the named group "version".
+pub static RE_VYPER_VERSION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^\s*#\s*(?:pragma\s+version|@version)\s+(?P<version>[^\s#]+(?:[ \t,]+[^\s#]+)*)",
+    )
+    .unwrap()
+});
+
+/// A regex that matches a vyper import statement with the named groups "from" and "name" for
+/// `from <from> import <name>` statements, "module" for `import <module>` statements and the
+/// optional "alias".
+pub static RE_VYPER_IMPORT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^[ \t]*(?:from\s+(?P<from>\.*[\w.]*)\s+import\s+(?P<name>\w+)|import\s+(?P<module>[\w.]+))(?:\s+as\s+(?P<alias>\w+))?",
+    )
+    .unwrap()
+});
+
 /// A regex that matches the SDPX license identifier
 /// statement with the named group "license".
 pub static RE_SOL_SDPX_LICENSE_IDENTIFIER: Lazy<Regex> =
@@ -74,8 +95,19 @@ pub fn find_version_pragma(contract: &str) -> Option<Match<'_>> {
     RE_SOL_PRAGMA_VERSION.captures(contract)?.name("version")
 }
 
-/// Returns an iterator that yields all solidity/yul files funder under the given root path or the
-/// `root` itself, if it is a sol/yul file
+/// Returns the vyper version pragma from the given input:
+/// `# pragma version ^0.3.10` => `^0.3.10`
+pub fn find_vyper_version_pragma(contract: &str) -> Option<Match<'_>> {
+    RE_VYPER_VERSION.captures(contract)?.name("version")
+}
+
+/// Returns `true` if the given path is a vyper source or interface file (`.vy`, `.vyi`)
+pub fn is_vyper_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref().extension().map(|ext| ext == "vy" || ext == "vyi").unwrap_or_default()
+}
+
+/// Returns an iterator that yields all solidity/yul/vyper files funder under the given root path or
+/// the `root` itself, if it is a sol/yul/vy/vyi file
 ///
 /// This also follows symlinks.
 pub fn source_files_iter(root: impl AsRef<Path>) -> impl Iterator<Item = PathBuf> {
@@ -85,13 +117,16 @@ pub fn source_files_iter(root: impl AsRef<Path>) -> impl Iterator<Item = PathBuf
         .filter_map(Result::ok)
         .filter(|e| e.file_type().is_file())
         .filter(|e| {
-            e.path().extension().map(|ext| (ext == "sol") || (ext == "yul")).unwrap_or_default()
+            e.path()
+                .extension()
+                .map(|ext| (ext == "sol") || (ext == "yul") || (ext == "vy") || (ext == "vyi"))
+                .unwrap_or_default()
         })
         .map(|e| e.path().into())
 }
 
-/// Returns a list of absolute paths to all the solidity files under the root, or the file itself,
-/// if the path is a solidity file.
+/// Returns a list of absolute paths to all the solidity, yul and vyper files under the root, or the
+/// file itself, if the path is a source file.
 ///
 /// This also follows symlinks.
 ///
@@ -140,6 +175,7 @@ pub fn solidity_dirs(root: impl AsRef<Path>) -> Vec<PathBuf> {
     let sources = source_files(root);
     sources
         .iter()
+        .filter(|p| !is_vyper_file(p))
         .filter_map(|p| p.parent())
         .collect::<HashSet<_>>()
         .into_iter()
@@ -230,7 +266,7 @@ pub fn normalize_solidity_import_path(
 // * Otherwise, the component remains untouched.
 //
 // Finally, the processed components are reassembled into a path.
-fn clean_solidity_path(original_path: impl AsRef<Path>) -> PathBuf {
+pub(crate) fn clean_solidity_path(original_path: impl AsRef<Path>) -> PathBuf {
     let mut new_path = Vec::new();
 
     for component in original_path.as_ref().components() {
@@ -477,7 +513,7 @@ pub(crate) fn find_case_sensitive_existing_file(non_existing: &Path) -> Option<P
 }
 
 cfg_if! {
-    if #[cfg(any(feature = "async", feature = "svm-solc"))] {
+    if #[cfg(any(feature = "async", feature = "svm-solc", feature = "remote-cache"))] {
         use tokio::runtime::{Handle, Runtime};
 
         #[derive(Debug)]
@@ -526,16 +562,58 @@ pub fn read_json_file<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T,
 }
 
 /// Writes serializes the provided value to JSON and writes it to a file.
+///
+/// The file is replaced atomically, see [`write_atomic()`].
 pub fn write_json_file<T: Serialize>(
     value: &T,
     path: impl AsRef<Path>,
     capacity: usize,
 ) -> Result<(), SolcError> {
     let path = path.as_ref();
-    let file = fs::File::create(path).map_err(|err| SolcError::io(err, path))?;
-    let mut writer = std::io::BufWriter::with_capacity(capacity, file);
-    serde_json::to_writer(&mut writer, value)?;
-    writer.flush().map_err(|e| SolcError::io(e, path))
+    let tmp = temp_file_path(path);
+    let res = (|| {
+        let file = fs::File::create(&tmp).map_err(|err| SolcError::io(err, &tmp))?;
+        let mut writer = std::io::BufWriter::with_capacity(capacity, file);
+        serde_json::to_writer(&mut writer, value)?;
+        writer.flush().map_err(|e| SolcError::io(e, &tmp))?;
+        drop(writer);
+        fs::rename(&tmp, path).map_err(|err| SolcError::io(err, path))
+    })();
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    res
+}
+
+/// Writes the `contents` to a temporary file next to the `path` first and then renames it to the
+/// `path`, so the file is either replaced entirely or not at all, even if the process is killed
+/// while writing.
+///
+/// Temporary files of interrupted writes are left behind, see [`is_temp_file()`].
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<(), SolcIoError> {
+    let path = path.as_ref();
+    let tmp = temp_file_path(path);
+    if let Err(err) = fs::write(&tmp, contents) {
+        let _ = fs::remove_file(&tmp);
+        return Err(SolcIoError::new(err, tmp));
+    }
+    fs::rename(&tmp, path).map_err(|err| SolcIoError::new(err, path))
+}
+
+/// Returns `true` if the file is a temporary file of [`write_atomic()`] or [`write_json_file()`]
+pub fn is_temp_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map_or(false, |name| name.starts_with('.') && name.ends_with(".tmp"))
+}
+
+/// Returns a unique path for a temporary file next to the `path`, like `.Greeter.json.1234.0.tmp`
+pub(crate) fn temp_file_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{name}.{}.{id}.tmp", std::process::id()))
 }
 
 /// Creates the parent directory of the `file` and all its ancestors if it does not exist.
@@ -559,6 +637,26 @@ pub fn create_parent_dir_all(file: impl AsRef<Path>) -> Result<(), SolcError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn can_write_files_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Greeter.json");
+        write_json_file(&serde_json::json!({ "abi": [] }), &file, 64).unwrap();
+        write_atomic(&file, r#"{"abi":[1]}"#).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), r#"{"abi":[1]}"#);
+
+        let tmp = temp_file_path(&file);
+        assert_eq!(tmp.parent(), Some(dir.path()));
+        assert!(is_temp_file(tmp));
+        assert!(!is_temp_file(&file));
+        // no temporary files are left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let missing = dir.path().join("missing").join("Greeter.json");
+        assert!(write_atomic(&missing, "").is_err());
+        assert!(write_json_file(&(), &missing, 64).is_err());
+    }
+
     use solang_parser::pt::SourceUnitPart;
     use std::{
         collections::HashSet,
@@ -659,6 +757,23 @@ contract A {}
         assert_eq!(files, expected);
     }
 
+    #[test]
+    fn can_find_vyper_sources() {
+        let tmp_dir = tempdir("contracts").unwrap();
+
+        let file_a = tmp_dir.path().join("a.sol");
+        let file_b = tmp_dir.path().join("b.vy");
+        let file_c = tmp_dir.path().join("c.vyi");
+        File::create(&file_a).unwrap();
+        File::create(&file_b).unwrap();
+        File::create(&file_c).unwrap();
+        File::create(tmp_dir.path().join("d.json")).unwrap();
+
+        let files: HashSet<_> = source_files(tmp_dir.path()).into_iter().collect();
+        let expected: HashSet<_> = [file_a, file_b, file_c].into();
+        assert_eq!(files, expected);
+    }
+
     #[test]
     fn can_parse_curly_bracket_imports() {
         let s =
@@ -722,6 +837,18 @@ pragma solidity ^0.8.0;
         assert_eq!(Some("^0.8.0"), find_version_pragma(s).map(|s| s.as_str()));
     }
 
+    #[test]
+    fn can_find_vyper_version() {
+        let s = "# pragma version ^0.3.10\n\n@external\ndef foo():\n    pass\n";
+        assert_eq!(Some("^0.3.10"), find_vyper_version_pragma(s).map(|s| s.as_str()));
+
+        let s = "# @version >=0.3.7 <0.4.0\n";
+        assert_eq!(Some(">=0.3.7 <0.4.0"), find_vyper_version_pragma(s).map(|s| s.as_str()));
+
+        let s = "# a comment\nx: uint256\n";
+        assert!(find_vyper_version_pragma(s).is_none());
+    }
+
     #[test]
     fn can_normalize_solidity_import_path() {
         let dir = tempfile::tempdir().unwrap();
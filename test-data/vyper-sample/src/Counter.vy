@@ -0,0 +1,11 @@
+# pragma version ~=0.4.0
+
+from . import ICounter
+
+implements: ICounter
+
+number: public(uint256)
+
+@external
+def increment():
+    self.number += 1
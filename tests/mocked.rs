@@ -6,7 +6,9 @@ use foundry_compilers::{
         mock::{MockProjectGenerator, MockProjectSettings, MockProjectSkeleton},
         TempProject,
     },
+    Graph,
 };
+use proptest::prelude::*;
 
 // default version to use
 const DEFAULT_VERSION: &str = "^0.8.10";
@@ -129,3 +131,115 @@ fn can_compile_skeleton() {
         project.artifacts_snapshot().unwrap().assert_artifacts_essentials_present();
     }
 }
+
+/// An edit of a mocked project, file ids wrap around the number of files
+#[derive(Clone, Debug)]
+enum Edit {
+    /// Modifies the content of a file
+    Modify(usize),
+    /// Deletes a file
+    Remove(usize),
+    /// Replaces the imports of a source file with an import of another file
+    Rewrite(usize, usize),
+    /// Adds a new source file that imports another file
+    Add(usize),
+}
+
+fn edit_strategy() -> impl Strategy<Value = Edit> {
+    prop_oneof![
+        any::<usize>().prop_map(Edit::Modify),
+        any::<usize>().prop_map(Edit::Remove),
+        (any::<usize>(), any::<usize>()).prop_map(|(id, import)| Edit::Rewrite(id, import)),
+        any::<usize>().prop_map(Edit::Add),
+    ]
+}
+
+/// Everything a resolved graph consists of, in the order of the graph's nodes
+fn describe(graph: Graph) -> impl PartialEq + std::fmt::Debug {
+    let nodes = (0..graph.files().len())
+        .map(|idx| {
+            let node = graph.node(idx);
+            let (path, source) = node.unpack();
+            let version = node.version().as_ref().map(|version| version.data().clone());
+            (path.clone(), source.content.clone(), version, graph.imported_nodes(idx).to_vec())
+        })
+        .collect::<Vec<_>>();
+    let num_inputs = graph.input_nodes().count();
+    let (_, edges) = graph.into_sources();
+    let include_paths = edges.include_paths().iter().cloned().collect::<Vec<_>>();
+    (nodes, num_inputs, edges.unresolved_imports().clone(), include_paths)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn can_update_graph_incrementally(
+        num_lib_files in 1..8usize,
+        num_libs in 0..3usize,
+        max_imports in 1..5usize,
+        edits in prop::collection::vec(edit_strategy(), 1..10),
+    ) {
+        let settings = MockProjectSettings {
+            num_lib_files,
+            num_libs,
+            min_imports: 0,
+            max_imports,
+            ..Default::default()
+        };
+        run_mock(settings, |project, gen| {
+            let paths = project.paths();
+            let num_files = gen.file_ids().count();
+            let internal = gen.internal_file_ids().collect::<Vec<_>>();
+            let import_of = |id: usize| {
+                let target = gen.get_file(id % num_files).target_path(gen, paths);
+                format!("../{}", target.strip_prefix(&paths.root).unwrap().display())
+            };
+
+            let mut graph = Graph::resolve(paths)?;
+            // files that changed since the last successful update
+            let mut changed = Vec::new();
+            for (i, edit) in edits.iter().enumerate() {
+                match *edit {
+                    Edit::Modify(id) => {
+                        changed.push(gen.modify_file(id % num_files, paths, DEFAULT_VERSION)?);
+                    }
+                    Edit::Remove(id) => {
+                        let target = gen.get_file(id % num_files).target_path(gen, paths);
+                        let _ = std::fs::remove_file(&target);
+                        changed.push(target);
+                    }
+                    Edit::Rewrite(id, import) => {
+                        let file = gen.get_file(internal[id % internal.len()]);
+                        let content = format!(
+                            "pragma solidity {DEFAULT_VERSION};\nimport \"{}\";\ncontract {} {{}}",
+                            import_of(import),
+                            file.name
+                        );
+                        let target = file.target_path(gen, paths);
+                        std::fs::write(&target, content).unwrap();
+                        changed.push(target);
+                    }
+                    Edit::Add(import) => {
+                        let content = format!(
+                            "pragma solidity {DEFAULT_VERSION};\nimport \"{}\";\ncontract Added{i} {{}}",
+                            import_of(import)
+                        );
+                        changed.push(project.add_source(format!("Added{i}"), content)?);
+                    }
+                }
+
+                let updated = graph.update(paths, &changed);
+                match Graph::resolve(paths) {
+                    Ok(resolved) => {
+                        updated?;
+                        changed.clear();
+                        assert_eq!(describe(graph.clone()), describe(resolved));
+                    }
+                    Err(_) => assert!(updated.is_err(), "{edit:?} should fail to resolve"),
+                }
+            }
+            Ok(())
+        });
+    }
+}
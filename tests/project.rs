@@ -8,6 +8,7 @@ use foundry_compilers::{
     },
     buildinfo::BuildInfo,
     cache::{SolFilesCache, SOLIDITY_FILES_CACHE_FILENAME},
+    compilers::vyper::Vyper,
     error::SolcError,
     flatten::Flattener,
     info::ContractInfo,
@@ -19,7 +20,7 @@ use foundry_compilers::{
 use pretty_assertions::assert_eq;
 use semver::Version;
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs, io,
     path::{Path, PathBuf},
     str::FromStr,
@@ -122,6 +123,1323 @@ fn can_compile_yul_sample() {
     assert_eq!(cache, updated_cache);
 }
 
+// compiles the vyper sample with a stub `vyper` that emits the recorded standard JSON output
+#[test]
+#[cfg(unix)]
+fn can_compile_vyper_sample() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data/vyper-sample");
+    let mut project = TempProject::dapptools().unwrap();
+    project.copy_sources([root.join("src/Counter.vy"), root.join("src/ICounter.vyi")]).unwrap();
+
+    let vyper = project.root().join("vyper");
+    fs::write(
+        &vyper,
+        format!(
+            "#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then\n  echo 0.4.0+commit.e9db8d9\nelse\n  cat > /dev/null\n  cat {}\nfi\n",
+            root.join("output.json").display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&vyper, fs::Permissions::from_mode(0o755)).unwrap();
+    project.project_mut().vyper = Some(Vyper::new(vyper));
+
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    let counter = compiled.find_first("Counter").unwrap();
+    assert!(counter.abi.is_some());
+    assert!(counter.bytecode.is_some());
+    assert_eq!(counter.method_identifiers.as_ref().unwrap()["number()"], "8381f58a");
+
+    let cache = SolFilesCache::read_joined(project.paths()).unwrap();
+    let entry = cache.entry(project.sources_path().join("Counter.vy")).unwrap();
+    assert_eq!(entry.version_requirement.as_deref(), Some("~0.4.0"));
+    assert!(entry.imports.contains(Path::new("src/ICounter.vyi")));
+
+    // nothing to compile
+    let compiled = project.compile().unwrap();
+    assert!(compiled.find_first("Counter").is_some());
+    assert!(compiled.is_unchanged());
+}
+
+#[test]
+#[cfg(unix)]
+fn can_reject_incompatible_vyper_and_interrupt_it() {
+    use foundry_compilers::Interrupt;
+    use std::{os::unix::fs::PermissionsExt, time::Duration};
+
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data/vyper-sample");
+    let mut project = TempProject::dapptools().unwrap();
+    project.copy_sources([root.join("src/Counter.vy"), root.join("src/ICounter.vyi")]).unwrap();
+
+    // the sources require `~=0.4.0`
+    let vyper = project.root().join("vyper");
+    let write_vyper = |version: &str| {
+        fs::write(
+            &vyper,
+            format!("#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then\n  echo {version}\nelse\n  exec sleep 30\nfi\n"),
+        )
+        .unwrap();
+        fs::set_permissions(&vyper, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    write_vyper("0.3.10+commit.91361694");
+    project.project_mut().vyper = Some(Vyper::new(&vyper));
+    let err = project.compile().unwrap_err().to_string();
+    assert!(err.contains("src/Counter.vy requires vyper \"~0.4.0\", found \"0.3.10"), "{err}");
+
+    // the timeout applies to vyper as well
+    write_vyper("0.4.0+commit.e9db8d9");
+    project.project_mut().solc_timeout = Some(Duration::from_millis(200));
+    let start = std::time::Instant::now();
+    let err = project.compile().unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(20));
+    assert!(matches!(err, SolcError::Interrupted { reason: Interrupt::Timeout(_), .. }), "{err}");
+}
+
+#[test]
+#[cfg(unix)]
+fn can_interrupt_hanging_solc() {
+    use foundry_compilers::{CancellationToken, Interrupt};
+    use std::{os::unix::fs::PermissionsExt, time::Duration};
+
+    let mut project = TempProject::<ConfigurableArtifacts>::dapptools().unwrap();
+    project
+        .add_source(
+            "A",
+            r"
+pragma solidity ^0.8.10;
+contract A {}
+",
+        )
+        .unwrap();
+
+    // a solc that never finishes compiling
+    let solc = project.root().join("solc");
+    fs::write(
+        &solc,
+        "#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then\n  echo Version: 0.8.24\nelse\n  exec sleep 30\nfi\n",
+    )
+    .unwrap();
+    fs::set_permissions(&solc, fs::Permissions::from_mode(0o755)).unwrap();
+    project.project_mut().compiler = Solc::new(solc);
+    project.project_mut().auto_detect = false;
+    project.project_mut().solc_timeout = Some(Duration::from_millis(200));
+
+    let start = std::time::Instant::now();
+    let err = project.compile().unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(20));
+    match err {
+        SolcError::Interrupted { reason, version, dirty_files } => {
+            assert_eq!(reason, Interrupt::Timeout(Duration::from_millis(200)));
+            assert_eq!(version, Some(Version::new(0, 8, 24)));
+            assert_eq!(dirty_files, vec![project.sources_path().join("A.sol")]);
+        }
+        err => panic!("expected interrupted error, got {err}"),
+    }
+
+    // no job is started once the compilation is cancelled
+    let token = CancellationToken::new();
+    token.cancel();
+    project.project_mut().solc_timeout = None;
+    project.project_mut().cancellation_token = Some(token);
+    let err = project.compile().unwrap_err();
+    assert!(matches!(err, SolcError::Interrupted { reason: Interrupt::Cancelled, .. }), "{err}");
+    assert!(!project.cache_path().exists());
+}
+
+#[tokio::test]
+#[cfg(all(unix, feature = "async"))]
+async fn can_interrupt_hanging_solc_async() {
+    use foundry_compilers::{artifacts::Source, CancellationToken, Interrupt, InterruptOptions};
+    use std::{os::unix::fs::PermissionsExt, time::Duration};
+
+    let tmp = tempfile::tempdir().unwrap();
+    // a solc that never finishes compiling
+    let solc = tmp.path().join("solc");
+    fs::write(&solc, "#!/bin/sh\nexec sleep 30\n").unwrap();
+    fs::set_permissions(&solc, fs::Permissions::from_mode(0o755)).unwrap();
+    let solc = Solc::new(solc);
+    let sources = BTreeMap::from([("A.sol".into(), Source::new("contract A {}"))]);
+    let input = CompilerInput::with_sources(sources).pop().unwrap();
+
+    let start = std::time::Instant::now();
+    let timeout = InterruptOptions::new(Some(Duration::from_millis(200)), None);
+    let err = solc.async_compile_output_interruptible(&input, timeout).await.unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(20));
+    assert!(matches!(err, SolcError::Interrupted { reason: Interrupt::Timeout(_), .. }), "{err}");
+
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        cancel.cancel();
+    });
+    let start = std::time::Instant::now();
+    let cancelled = InterruptOptions::new(None, Some(&token));
+    let err = solc.async_compile_output_interruptible(&input, cancelled).await.unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(20));
+    assert!(matches!(err, SolcError::Interrupted { reason: Interrupt::Cancelled, .. }), "{err}");
+}
+
+#[test]
+fn can_report_import_cycles() {
+    use foundry_compilers::{
+        artifacts::Severity,
+        project_util::mock_solc::MockSolc,
+        report::{self, Report, Reporter},
+    };
+    use std::sync::{Arc, Mutex};
+
+    type ReportedCycles = (Vec<Vec<PathBuf>>, Severity);
+
+    #[derive(Clone, Debug, Default)]
+    struct CycleReporter(Arc<Mutex<Vec<ReportedCycles>>>);
+
+    impl Reporter for CycleReporter {
+        fn on_import_cycles(&self, cycles: &[Vec<PathBuf>], severity: Severity) {
+            self.0.lock().unwrap().push((cycles.to_vec(), severity));
+        }
+    }
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let a = project
+        .add_source("A", "pragma solidity ^0.8.10;\nimport \"./B.sol\";\ncontract A {}")
+        .unwrap();
+    let b = project
+        .add_source("B", "pragma solidity ^0.8.10;\nimport \"./A.sol\";\ncontract B {}")
+        .unwrap();
+    // cycles between libraries are ignored
+    project
+        .add_lib("L1", "pragma solidity ^0.8.10;\nimport \"./L2.sol\";\ncontract L1 {}")
+        .unwrap();
+    project
+        .add_lib("L2", "pragma solidity ^0.8.10;\nimport \"./L1.sol\";\ncontract L2 {}")
+        .unwrap();
+    project
+        .add_source("C", "pragma solidity ^0.8.10;\nimport \"../lib/L1.sol\";\ncontract C {}")
+        .unwrap();
+
+    let reporter = CycleReporter::default();
+    report::with_scoped(&Report::new(reporter.clone()), || {
+        // not checked by default
+        project.compile().unwrap().assert_success();
+        assert!(reporter.0.lock().unwrap().is_empty());
+
+        project.project_mut().import_cycles = Some(Severity::Warning);
+        project.compile().unwrap().assert_success();
+        assert_eq!(
+            reporter.0.lock().unwrap().pop(),
+            Some((vec![vec![a.clone(), b.clone()]], Severity::Warning))
+        );
+
+        project.project_mut().import_cycles = Some(Severity::Error);
+        match project.compile() {
+            Err(SolcError::ImportCycles(cycles)) => assert_eq!(cycles, vec![vec![a, b]]),
+            res => panic!("expected import cycle error, got {res:?}"),
+        }
+        assert_eq!(reporter.0.lock().unwrap().pop().unwrap().1, Severity::Error);
+    });
+}
+
+#[test]
+fn can_compile_with_compiler_overrides() {
+    use foundry_compilers::{project_util::mock_solc::MockSolc, CompilerOverride};
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let old =
+        project.add_source("legacy/Old", "pragma solidity >=0.6.0;\ncontract Old {}").unwrap();
+    let new = project.add_source("New", "pragma solidity ^0.8.10;\ncontract New {}").unwrap();
+
+    let legacy = CompilerOverride::new("src/legacy/**")
+        .unwrap()
+        .version("^0.6.0".parse().unwrap())
+        .settings(serde_json::json!({ "optimizer": { "enabled": true, "runs": 1 } }));
+    // version overrides only apply if versions are detected
+    project.project_mut().auto_detect = true;
+    project.project_mut().compiler_overrides = vec![legacy.clone()];
+
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    let versions = compiled
+        .artifact_ids()
+        .map(|(id, _)| (id.name, id.version.to_string()))
+        .collect::<BTreeMap<_, _>>();
+    assert_eq!(
+        versions,
+        BTreeMap::from([
+            ("New".to_string(), "0.8.24".to_string()),
+            ("Old".to_string(), "0.6.12".to_string())
+        ])
+    );
+
+    // the cache tracks the settings every file was compiled with
+    let cache = SolFilesCache::read_joined(&project.project().paths).unwrap();
+    assert_eq!(cache.entry(&old).unwrap().solc_config.settings.optimizer.runs, Some(1));
+    assert_eq!(cache.entry(&new).unwrap().solc_config.settings.optimizer.runs, Some(200));
+    assert!(project.compile().unwrap().is_unchanged());
+
+    // only the files of the changed override are recompiled
+    project.project_mut().compiler_overrides =
+        vec![legacy.settings(serde_json::json!({ "optimizer": { "runs": 2 } }))];
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert!(compiled.find_first("Old").is_some());
+    assert!(compiled.compiled_artifacts().find_first("New").is_none());
+    let cache = SolFilesCache::read_joined(&project.project().paths).unwrap();
+    assert_eq!(cache.entry(&old).unwrap().solc_config.settings.optimizer.runs, Some(2));
+
+    // files that can't satisfy the version override are rejected
+    project.project_mut().compiler_overrides =
+        vec![CompilerOverride::new("src/New.sol").unwrap().version("<0.6.0".parse().unwrap())];
+    let err = project.compile().unwrap_err().to_string();
+    assert!(err.contains("version override \"<0.6.0\" of \"src/New.sol\""), "{err}");
+}
+
+#[test]
+fn can_compile_import_from_other_compiler_override() {
+    use foundry_compilers::{project_util::mock_solc::MockSolc, CompilerOverride};
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let shared = project
+        .add_source("legacy/Shared", "pragma solidity ^0.8.10;\ncontract Shared {}")
+        .unwrap();
+    project
+        .add_source(
+            "New",
+            "pragma solidity ^0.8.10;\nimport \"./legacy/Shared.sol\";\ncontract New {}",
+        )
+        .unwrap();
+    project.project_mut().compiler_overrides = vec![CompilerOverride::new("src/legacy/**")
+        .unwrap()
+        .settings(serde_json::json!({ "optimizer": { "runs": 1 } }))];
+
+    // `Shared` is compiled with both settings and the same version, but only emitted once
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    let mut names = compiled.artifact_ids().map(|(id, _)| id.name).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["New", "Shared"]);
+
+    let cache = SolFilesCache::read_joined(&project.project().paths).unwrap();
+    assert_eq!(cache.entry(&shared).unwrap().solc_config.settings.optimizer.runs, Some(1));
+    assert!(project.compile().unwrap().is_unchanged());
+}
+
+#[test]
+fn can_cache_imports_with_profile_of_importer() {
+    use foundry_compilers::{project_util::mock_solc::MockSolc, CompilerOverride};
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let lib = project.add_lib("Lib", "pragma solidity ^0.8.10;\ncontract Lib {}").unwrap();
+    project
+        .add_source(
+            "legacy/Old",
+            "pragma solidity ^0.8.10;\nimport \"../../lib/Lib.sol\";\ncontract Old {}",
+        )
+        .unwrap();
+    let legacy = CompilerOverride::new("src/legacy/**")
+        .unwrap()
+        .settings(serde_json::json!({ "optimizer": { "runs": 1 } }));
+    project.project_mut().compiler_overrides = vec![legacy.clone()];
+
+    // `Lib` is only imported by a file of the override, so it's compiled with its settings
+    project.compile().unwrap().assert_success();
+    let cache = SolFilesCache::read_joined(&project.project().paths).unwrap();
+    assert_eq!(cache.entry(&lib).unwrap().solc_config.settings.optimizer.runs, Some(1));
+    assert!(project.compile().unwrap().is_unchanged());
+
+    // and recompiled if they change
+    project.project_mut().compiler_overrides =
+        vec![legacy.settings(serde_json::json!({ "optimizer": { "runs": 2 } }))];
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert!(compiled.compiled_artifacts().find_first("Lib").is_some());
+    let cache = SolFilesCache::read_joined(&project.project().paths).unwrap();
+    assert_eq!(cache.entry(&lib).unwrap().solc_config.settings.optimizer.runs, Some(2));
+    assert!(project.compile().unwrap().is_unchanged());
+}
+
+#[test]
+fn can_analyze_impact_of_changed_files() {
+    use foundry_compilers::project_util::mock_solc::MockSolc;
+
+    let project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let lib = project.add_lib("Lib", "pragma solidity ^0.8.10;\ncontract Lib {}").unwrap();
+    let token = project
+        .add_source(
+            "Token",
+            "pragma solidity ^0.8.10;\nimport \"../lib/Lib.sol\";\ncontract Token {}",
+        )
+        .unwrap();
+    let other = project.add_source("Other", "pragma solidity ^0.8.10;\ncontract Other {}").unwrap();
+    let test = project
+        .add_test(
+            "Token.t",
+            "pragma solidity ^0.8.10;\nimport \"../src/Token.sol\";\ncontract TokenTest {}",
+        )
+        .unwrap();
+    let script = project
+        .add_script(
+            "Deploy.s",
+            "pragma solidity ^0.8.10;\nimport \"../src/Other.sol\";\ncontract Deploy {}",
+        )
+        .unwrap();
+    project.compile().unwrap().assert_success();
+
+    // a change of a library affects all files that import it transitively
+    let impact = project.project().impact([&lib]).unwrap();
+    assert_eq!(impact.libraries, [lib.clone()].into());
+    assert_eq!(impact.sources, [token.clone()].into());
+    assert_eq!(impact.tests, [test.clone()].into());
+    assert!(impact.scripts.is_empty());
+    assert_eq!(
+        impact.artifacts.iter().map(|id| id.name.as_str()).collect::<HashSet<_>>(),
+        HashSet::from(["Lib", "Token", "TokenTest"])
+    );
+
+    // paths relative to the root are accepted as well
+    let impact = project.project().impact(["src/Other.sol"]).unwrap();
+    assert_eq!(impact.files().collect::<Vec<_>>(), vec![&other, &script]);
+
+    assert!(project.project().impact(["src/Missing.sol"]).unwrap().is_empty());
+}
+
+#[test]
+fn can_compile_source_overlay() {
+    use foundry_compilers::{project_util::mock_solc::MockSolc, SourceOverlay};
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let token = project
+        .add_source("Token", "pragma solidity ^0.8.10;\nimport \"./Math.sol\";\ncontract Token {}")
+        .unwrap();
+    let math = project.add_source("Math", "pragma solidity ^0.8.10;\ncontract Math {}").unwrap();
+    project.compile().unwrap().assert_success();
+    let cache = fs::read(project.cache_path()).unwrap();
+
+    // the unsaved buffer of `Token.sol` imports a file that only exists in the overlay
+    let root = project.root().to_path_buf();
+    project.project_mut().overlay = SourceOverlay::from_iter([
+        (
+            token.clone(),
+            "pragma solidity ^0.8.10;\nimport \"./Math.sol\";\nimport \"./New.sol\";\ncontract Token {}",
+        ),
+        (root.join("src/New.sol"), "pragma solidity ^0.8.10;\ncontract New {}"),
+    ]);
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert!(compiled.compiled_artifacts().find_first("Token").is_some());
+    assert!(compiled.compiled_artifacts().find_first("New").is_some());
+    assert!(compiled.find_first("Math").is_some());
+
+    // nothing of the overlay is written to disk
+    assert_eq!(fs::read(project.cache_path()).unwrap(), cache);
+    assert!(!project.artifacts_path().join("New.sol").exists());
+
+    let graph = Graph::resolve_with_overlay(project.paths(), &project.project().overlay).unwrap();
+    assert!(graph.files().contains_key(&root.join("src/New.sol")));
+    assert!(graph.files().contains_key(&math));
+
+    // files that don't depend on the overlay are still written, overlay imports are remapped
+    let other = project.add_source("Other", "pragma solidity ^0.8.10;\ncontract Other {}").unwrap();
+    project.project_mut().paths.remappings.push(
+        Remapping::from_str(&format!("unsaved/={}/", root.join("src/unsaved").display())).unwrap(),
+    );
+    project.project_mut().overlay = SourceOverlay::from_iter([
+        (token.clone(), "pragma solidity ^0.8.10;\nimport \"unsaved/Lib.sol\";\ncontract Token {}"),
+        (root.join("src/unsaved/Lib.sol"), "pragma solidity ^0.8.10;\ncontract Lib {}"),
+    ]);
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert!(compiled.compiled_artifacts().find_first("Lib").is_some());
+    assert!(project.artifacts_path().join("Other.sol/Other.json").exists());
+    assert!(!project.artifacts_path().join("Lib.sol").exists());
+    let cache = SolFilesCache::read_joined(project.paths()).unwrap();
+    assert!(cache.entry(&other).is_some());
+    assert!(cache.entry(root.join("src/unsaved/Lib.sol")).is_none());
+
+    project.project_mut().overlay = SourceOverlay::new();
+    assert!(project.compile().unwrap().is_unchanged());
+}
+
+#[test]
+fn can_explain_dirty_files() {
+    use foundry_compilers::{
+        cache::{DirtyReason, SettingsChange},
+        project_util::mock_solc::MockSolc,
+        report::{self, Report, Reporter},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Debug, Default)]
+    struct DirtyFilesReporter(Arc<Mutex<Vec<BTreeMap<PathBuf, DirtyReason>>>>);
+
+    impl Reporter for DirtyFilesReporter {
+        fn on_dirty_files(&self, dirty_files: &BTreeMap<PathBuf, DirtyReason>) {
+            self.0.lock().unwrap().push(dirty_files.clone());
+        }
+    }
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let a = project
+        .add_source("A", "pragma solidity ^0.8.10;\nimport \"./B.sol\";\ncontract A {}")
+        .unwrap();
+    let b = project
+        .add_source("B", "pragma solidity ^0.8.10;\nimport \"./C.sol\";\ncontract B {}")
+        .unwrap();
+    let c = project.add_source("C", "pragma solidity ^0.8.10;\ncontract C {}").unwrap();
+
+    let reporter = DirtyFilesReporter::default();
+    report::with_scoped(&Report::new(reporter.clone()), || {
+        let compiled = project.compile().unwrap();
+        compiled.assert_success();
+        assert!(compiled.dirty_reasons().values().all(|reason| *reason == DirtyReason::NewFile));
+        assert_eq!(compiled.dirty_reasons().len(), 3);
+
+        let compiled = project.compile().unwrap();
+        assert!(compiled.is_unchanged());
+        assert!(compiled.dirty_reasons().is_empty());
+    });
+    // nothing is reported if all files are reused
+    assert_eq!(reporter.0.lock().unwrap().len(), 1);
+
+    project.add_source("C", "pragma solidity ^0.8.10;\ncontract C { uint x; }").unwrap();
+    let compiled = project.compile().unwrap();
+    assert_eq!(
+        compiled.dirty_reasons(),
+        &BTreeMap::from([
+            (a.clone(), DirtyReason::DirtyImport(vec![b.clone(), c.clone()])),
+            (b.clone(), DirtyReason::DirtyImport(vec![c.clone()])),
+            (c.clone(), DirtyReason::ContentChanged),
+        ])
+    );
+
+    project.project_mut().solc_config.settings.optimizer.runs = Some(1000);
+    let compiled = project.compile().unwrap();
+    let changes = vec![SettingsChange {
+        field: "optimizer.runs".to_string(),
+        old: Some(200.into()),
+        new: Some(1000.into()),
+    }];
+    assert_eq!(compiled.dirty_reasons()[&a], DirtyReason::SolcConfigChanged(changes));
+    assert_eq!(
+        compiled.dirty_reasons()[&a].to_string(),
+        "solc settings changed: optimizer.runs (200 -> 1000)"
+    );
+
+    let artifact = project.artifacts_path().join("B.sol/B.json");
+    fs::remove_file(&artifact).unwrap();
+    let compiled = project.compile().unwrap();
+    assert_eq!(compiled.dirty_reasons()[&b], DirtyReason::MissingArtifact(artifact));
+}
+
+#[test]
+fn can_recover_from_interrupted_writes() {
+    use foundry_compilers::{cache::DirtyReason, project_util::mock_solc::MockSolc};
+
+    let project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project.add_source("A", "pragma solidity ^0.8.10;\ncontract A {}").unwrap();
+    project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}").unwrap();
+
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    let artifacts = compiled.artifact_ids().map(|(id, _)| (id.name, id.path)).collect::<Vec<_>>();
+    // no temporary files are left behind
+    let files = walkdir::WalkDir::new(project.artifacts_path())
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| utils::is_temp_file(e.path()))
+        .count();
+    assert_eq!(files, 0);
+
+    // a build that was killed while writing leaves truncated artifacts and temporary files behind
+    let (_, b_artifact) = artifacts.iter().find(|(name, _)| name == "B").unwrap();
+    let content = fs::read_to_string(b_artifact).unwrap();
+    fs::write(b_artifact, &content[..content.len() / 2]).unwrap();
+    let tmp = b_artifact.with_file_name(".B.json.1234.0.tmp");
+    fs::write(&tmp, &content[..10]).unwrap();
+
+    // only the sources of unreadable artifacts are recompiled
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert_eq!(
+        compiled.dirty_reasons().values().collect::<Vec<_>>(),
+        vec![&DirtyReason::MissingArtifact(b_artifact.clone())]
+    );
+    assert!(utils::read_json_file::<serde_json::Value>(b_artifact).is_ok());
+
+    let orphaned = project.project().orphaned_files().unwrap();
+    assert_eq!(orphaned.artifacts, BTreeSet::from([tmp.clone()]));
+    project.project().gc().unwrap();
+    assert!(!tmp.exists());
+    assert!(project.compile().unwrap().is_unchanged());
+}
+
+#[test]
+fn can_use_binary_cache() {
+    use foundry_compilers::{
+        cache::{CacheFormat, DirtyReason},
+        project_util::mock_solc::MockSolc,
+        ArtifactIndex,
+    };
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project
+        .add_source("A", "pragma solidity ^0.8.10;\nimport \"./B.sol\";\ncontract A {}")
+        .unwrap();
+    project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}").unwrap();
+    let c = project.add_source("C", "pragma solidity ^0.8.10;\ncontract C {}").unwrap();
+    let json_artifacts = project.compile().unwrap().into_artifacts().collect::<BTreeMap<_, _>>();
+    let json_cache = SolFilesCache::read(project.project().cache_path()).unwrap();
+
+    project.project_mut().cache_format = CacheFormat::Binary;
+    // the json cache is read, so nothing is recompiled
+    let compiled = project.compile().unwrap();
+    assert!(compiled.is_unchanged());
+    assert_eq!(compiled.into_artifacts().collect::<BTreeMap<_, _>>(), json_artifacts);
+
+    // the json cache is still written, next to the binary cache and the index of the artifacts
+    let cache_path = project.project().cache_path().to_path_buf();
+    let binary_cache_path = project.project().binary_cache_path();
+    let index_path = project.project().artifact_index_path();
+    assert_eq!(SolFilesCache::read(&cache_path).unwrap(), json_cache);
+    let (binary_cache, generation) = SolFilesCache::read_binary(&binary_cache_path).unwrap();
+    assert_eq!(binary_cache, json_cache);
+    let index = ArtifactIndex::read(&index_path, project.artifacts_path()).unwrap();
+    assert_eq!(index.generation(), generation);
+    assert_eq!(index.len(), json_artifacts.len());
+
+    // the cached artifacts are decoded from the index
+    let compiled = project.compile().unwrap();
+    assert!(compiled.is_unchanged());
+    assert_eq!(compiled.into_artifacts().collect::<BTreeMap<_, _>>(), json_artifacts);
+
+    // artifacts that were deleted are compiled again
+    let (c_id, _) = json_artifacts.iter().find(|(id, _)| id.name == "C").unwrap();
+    fs::remove_file(&c_id.path).unwrap();
+    project.add_source("B", "pragma solidity ^0.8.10;\ncontract B { uint x; }").unwrap();
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert_eq!(compiled.dirty_reasons().len(), 3);
+    assert_eq!(compiled.dirty_reasons()[&c], DirtyReason::MissingArtifact(c_id.path.clone()));
+    let artifacts = compiled.into_artifacts().collect::<BTreeMap<_, _>>();
+    assert_eq!(artifacts.keys().collect::<Vec<_>>(), json_artifacts.keys().collect::<Vec<_>>());
+
+    let compiled = project.compile().unwrap();
+    assert!(compiled.is_unchanged());
+    assert_eq!(compiled.into_artifacts().collect::<BTreeMap<_, _>>(), artifacts);
+
+    // a build that only writes the json cache removes the binary cache and the index
+    let outdated_binary_cache = fs::read(&binary_cache_path).unwrap();
+    let outdated_index = fs::read(&index_path).unwrap();
+    project.project_mut().cache_format = CacheFormat::Json;
+    let b = project.add_source("B", "pragma solidity ^0.8.10;\ncontract B { uint y; }").unwrap();
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert_eq!(compiled.dirty_reasons()[&b], DirtyReason::ContentChanged);
+    let artifacts = compiled.into_artifacts().collect::<BTreeMap<_, _>>();
+    assert!(!binary_cache_path.exists());
+    assert!(!index_path.exists());
+
+    // the binary cache and the index aren't used if the json cache was changed without them
+    fs::write(&binary_cache_path, outdated_binary_cache).unwrap();
+    fs::write(&index_path, outdated_index).unwrap();
+    project.project_mut().cache_format = CacheFormat::Binary;
+    let compiled = project.compile().unwrap();
+    assert!(compiled.is_unchanged());
+    assert_eq!(compiled.into_artifacts().collect::<BTreeMap<_, _>>(), artifacts);
+    let (_, generation) = SolFilesCache::read_binary(&binary_cache_path).unwrap();
+    let index = ArtifactIndex::read(&index_path, project.artifacts_path()).unwrap();
+    assert_eq!(index.generation(), generation);
+}
+
+#[test]
+fn can_trust_unchanged_mtimes() {
+    use foundry_compilers::{
+        cache::{CacheEntry, DirtyReason},
+        project_util::mock_solc::MockSolc,
+    };
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project.project_mut().trust_mtime = true;
+    project
+        .add_source("A", "pragma solidity ^0.8.10;\nimport \"./B.sol\";\ncontract A {}")
+        .unwrap();
+    let b = project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}\n// 1").unwrap();
+    project.compile().unwrap().assert_success();
+    assert!(project.compile().unwrap().is_unchanged());
+
+    // a change that keeps the size and restores the modification date isn't detected
+    let cache_path = project.project().cache_path().to_path_buf();
+    project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}\n// 2").unwrap();
+    let mut cache = SolFilesCache::read(&cache_path).unwrap();
+    let (_, entry) = cache.files.iter_mut().find(|(file, _)| b.ends_with(file)).unwrap();
+    assert_eq!(entry.file_size, Some(fs::metadata(&b).unwrap().len()));
+    entry.last_modification_date = CacheEntry::read_last_modification_date(&b).unwrap();
+    cache.write(&cache_path).unwrap();
+    assert!(project.compile().unwrap().is_unchanged());
+
+    // unless the sources are hashed
+    project.project_mut().trust_mtime = false;
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert_eq!(compiled.dirty_reasons()[&b], DirtyReason::ContentChanged);
+    project.project_mut().trust_mtime = true;
+    assert!(project.compile().unwrap().is_unchanged());
+
+    // a different size is detected
+    project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}\n// 30").unwrap();
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert_eq!(compiled.dirty_reasons()[&b], DirtyReason::ContentChanged);
+    assert_eq!(compiled.dirty_reasons().len(), 2);
+
+    // a file that's touched without changing its content is hashed once, its cache entry is
+    // updated with the new modification date
+    fs::write(&b, fs::read(&b).unwrap()).unwrap();
+    assert!(project.compile().unwrap().is_unchanged());
+    let cache = SolFilesCache::read(&cache_path).unwrap();
+    let (_, entry) = cache.files.iter().find(|(file, _)| b.ends_with(file)).unwrap();
+    assert_eq!(entry.last_modification_date, CacheEntry::read_last_modification_date(&b).unwrap());
+}
+
+#[test]
+fn can_serialize_concurrent_builds() {
+    use foundry_compilers::{
+        project_util::mock_solc::MockSolc,
+        report::{self, Report, Reporter},
+        BuildLock,
+    };
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    #[derive(Clone, Debug, Default)]
+    struct BuildLockReporter(Arc<Mutex<Vec<PathBuf>>>);
+
+    impl Reporter for BuildLockReporter {
+        fn on_build_lock_wait(&self, lock: &Path) {
+            self.0.lock().unwrap().push(lock.to_path_buf());
+        }
+    }
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project
+        .add_source("A", "pragma solidity ^0.8.10;\nimport \"./B.sol\";\ncontract A {}")
+        .unwrap();
+    project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}").unwrap();
+    let lock_path = project.project().build_lock_path();
+
+    // another build holds the lock
+    let lock = BuildLock::acquire(&lock_path, None, None).unwrap();
+    project.project_mut().build_lock_timeout = Some(Duration::from_millis(50));
+    let err = project.compile().unwrap_err();
+    assert!(matches!(err, SolcError::BuildLockTimeout { .. }), "{err}");
+    project.project_mut().build_lock_timeout = None;
+
+    let reporter = BuildLockReporter::default();
+    let compiled = thread::scope(|s| {
+        let builds = (0..4)
+            .map(|_| {
+                let reporter = reporter.clone();
+                let project = project.project();
+                s.spawn(move || report::with_scoped(&Report::new(reporter), || project.compile()))
+            })
+            .collect::<Vec<_>>();
+
+        let start = Instant::now();
+        while reporter.0.lock().unwrap().len() < builds.len() {
+            assert!(start.elapsed() < Duration::from_secs(10), "builds didn't wait for the lock");
+            thread::sleep(Duration::from_millis(10));
+        }
+        // nothing is written while the lock is held
+        assert!(!project.project().cache_path().exists());
+        drop(lock);
+
+        builds.into_iter().map(|build| build.join().unwrap().unwrap()).collect::<Vec<_>>()
+    });
+
+    assert!(reporter.0.lock().unwrap().iter().all(|path| *path == lock_path));
+    compiled.iter().for_each(ProjectCompileOutput::assert_success);
+    // only the first build compiles, the others reuse its artifacts
+    assert_eq!(compiled.iter().filter(|output| !output.is_unchanged()).count(), 1);
+    assert!(project.project().read_cache_file().is_ok());
+
+    // gc and cleanup wait for the lock as well
+    let lock = BuildLock::acquire(&lock_path, None, None).unwrap();
+    project.project_mut().build_lock_timeout = Some(Duration::from_millis(50));
+    let err = project.project().gc().unwrap_err();
+    assert!(matches!(err, SolcError::BuildLockTimeout { .. }), "{err}");
+    let err = project.project().cleanup().unwrap_err();
+    assert_eq!(err.source().kind(), io::ErrorKind::TimedOut);
+    assert!(project.project().cache_path().exists());
+    drop(lock);
+    project.project().cleanup().unwrap();
+    assert!(!project.project().cache_path().exists());
+    assert!(!lock_path.exists());
+}
+
+#[test]
+fn can_cleanup_cache_dir_with_build_lock() {
+    use foundry_compilers::project_util::mock_solc::{MockDiagnostic, MockSolc};
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default().with_diagnostic(MockDiagnostic::error("src/A.sol", "failed")),
+    )
+    .unwrap();
+    project.add_source("A", "pragma solidity ^0.8.10;\ncontract A {}").unwrap();
+    let cache_dir = project.project().cache_path().parent().unwrap().to_path_buf();
+    fs::remove_dir(&cache_dir).unwrap();
+
+    // nothing is written if the build fails, not even the lock file
+    assert!(project.compile().unwrap().has_compiler_errors());
+    assert!(!cache_dir.exists());
+
+    project.project_mut().compiler.diagnostics.clear();
+    project.compile().unwrap().assert_success();
+    assert!(project.project().build_lock_path().exists());
+
+    // the lock file doesn't keep the cache dir
+    project.project().cleanup().unwrap();
+    assert!(!cache_dir.exists());
+    assert!(!project.project().artifacts_path().exists());
+}
+
+#[test]
+fn can_gc_orphaned_files() {
+    use foundry_compilers::project_util::mock_solc::MockSolc;
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project.project_mut().build_info = true;
+    project.add_source("A", "pragma solidity ^0.8.10;\ncontract A {}").unwrap();
+    let b = project.add_source("B", "pragma solidity ^0.8.10;\ncontract B {}").unwrap();
+
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert!(project.project().orphaned_files().unwrap().is_empty());
+    let b_artifact = compiled.artifact_ids().find(|(id, _)| id.name == "B").unwrap().0.path;
+    assert!(b_artifact.exists());
+    let build_info_path = project.project().build_info_path().clone();
+    let build_infos = || fs::read_dir(&build_info_path).unwrap().count();
+    assert_eq!(build_infos(), 1);
+
+    // renaming a file orphans the artifact of the old file
+    fs::remove_file(&b).unwrap();
+    project.add_source("C", "pragma solidity ^0.8.10;\ncontract C {}").unwrap();
+    project.compile().unwrap().assert_success();
+    let orphaned = project.project().orphaned_files().unwrap();
+    assert_eq!(orphaned.artifacts, BTreeSet::from([b_artifact.clone()]));
+    assert!(orphaned.build_infos.is_empty());
+
+    // the first build info is orphaned once no file references it anymore
+    project.add_source("A", "pragma solidity ^0.8.10;\ncontract A { uint x; }").unwrap();
+    project.compile().unwrap().assert_success();
+    let orphaned = project.project().orphaned_files().unwrap();
+    assert_eq!(orphaned.artifacts, BTreeSet::from([b_artifact.clone()]));
+    assert_eq!(orphaned.build_infos.len(), 1);
+    assert_eq!(build_infos(), 3);
+    // the dry run doesn't delete anything
+    assert!(orphaned.files().all(|file| file.exists()));
+
+    let removed = project.project().gc().unwrap();
+    assert_eq!(removed, orphaned);
+    assert!(removed.files().all(|file| !file.exists()));
+    // the now empty dir of the artifact is removed as well
+    assert!(!b_artifact.parent().unwrap().exists());
+    assert!(project.project().orphaned_files().unwrap().is_empty());
+    assert_eq!(build_infos(), 2);
+
+    // orphaned files are deleted after compiling
+    project.project_mut().auto_gc = true;
+    project.add_source("C", "pragma solidity ^0.8.10;\ncontract C { uint x; }").unwrap();
+    project.compile().unwrap().assert_success();
+    assert!(project.project().orphaned_files().unwrap().is_empty());
+    assert_eq!(build_infos(), 2);
+}
+
+#[test]
+fn gc_keeps_untracked_build_infos_and_cache_files() {
+    use foundry_compilers::project_util::mock_solc::MockSolc;
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project.project_mut().build_info = true;
+    // the cache dir is nested in the artifacts dir
+    let cache = project.artifacts_path().join("cache").join(SOLIDITY_FILES_CACHE_FILENAME);
+    project.project_mut().paths.cache = cache.clone();
+    project.add_source("A", "pragma solidity ^0.8.10;\ncontract A {}").unwrap();
+    project.compile().unwrap().assert_success();
+    assert!(cache.exists());
+    assert!(project.project().orphaned_files().unwrap().is_empty());
+
+    // caches written before the build ids were tracked don't reference any build info
+    let mut sol_cache = SolFilesCache::read(&cache).unwrap();
+    sol_cache.files.values_mut().for_each(|entry| entry.build_ids.clear());
+    sol_cache.write(&cache).unwrap();
+    fs::write(project.artifacts_path().join("cache").join("notes.json"), "{}").unwrap();
+    project.compile().unwrap().assert_success();
+    assert!(project.project().orphaned_files().unwrap().is_empty());
+    assert!(project.project().gc().unwrap().is_empty());
+    assert!(cache.exists());
+    assert_eq!(fs::read_dir(project.project().build_info_path()).unwrap().count(), 1);
+}
+
+#[test]
+fn can_detect_duplicate_contracts() {
+    use foundry_compilers::{
+        artifacts::Severity, project_util::mock_solc::MockSolc, resolver::DuplicateContract,
+    };
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    let a = project.add_source("a/Token", "pragma solidity ^0.8.10;\ncontract Token {}").unwrap();
+    let b = project
+        .add_source(
+            "b/Token",
+            "pragma solidity ^0.8.10;\nimport \"lib/Token.sol\";\ncontract Token {}",
+        )
+        .unwrap();
+    let lib = project
+        .add_lib("lib/Token", "pragma solidity ^0.8.10;\nlibrary Token {}\ncontract Math {}")
+        .unwrap();
+    project.add_source("Math", "pragma solidity ^0.8.10;\ncontract Math {}").unwrap();
+
+    let graph = Graph::resolve(project.paths()).unwrap();
+    assert_eq!(
+        graph.duplicate_contracts(),
+        vec![
+            DuplicateContract {
+                name: "Math".to_string(),
+                files: vec![lib.clone(), project.sources_path().join("Math.sol")],
+            },
+            DuplicateContract { name: "Token".to_string(), files: vec![lib, a.clone(), b.clone()] },
+        ]
+    );
+
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    let names = compiled.duplicate_contracts().into_iter().map(|d| d.name).collect::<Vec<_>>();
+    assert_eq!(names, ["Math", "Token"]);
+
+    // only duplicates in the sources dir fail the build
+    project.project_mut().duplicate_contracts = Some(Severity::Error);
+    match project.compile() {
+        Err(SolcError::DuplicateContracts(duplicates)) => {
+            assert_eq!(
+                duplicates,
+                vec![DuplicateContract { name: "Token".to_string(), files: vec![a, b] }]
+            );
+        }
+        res => panic!("expected duplicate contracts, got {res:?}"),
+    }
+}
+
+#[test]
+fn can_compile_imports_of_custom_resolver() {
+    use foundry_compilers::{project_util::mock_solc::MockSolc, ImportResolver, ResolvedImport};
+    use std::sync::Arc;
+
+    #[derive(Debug, Default)]
+    struct InMemoryResolver(HashMap<PathBuf, String>);
+
+    impl ImportResolver for InMemoryResolver {
+        fn resolve(
+            &self,
+            _paths: &ProjectPathsConfig,
+            _cwd: &Path,
+            import: &Path,
+        ) -> foundry_compilers::error::Result<Option<ResolvedImport>> {
+            Ok(self.0.get(import).cloned().map(ResolvedImport::Content))
+        }
+    }
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project
+        .add_source(
+            "Token",
+            "pragma solidity ^0.8.10;\nimport \"https://example.com/lib/Math.sol\";\ncontract Token {}",
+        )
+        .unwrap();
+    let import = PathBuf::from("https://example.com/lib/Math.sol");
+    let resolver = InMemoryResolver(HashMap::from([(
+        import.clone(),
+        "pragma solidity ^0.8.10;\ncontract Math {}".to_string(),
+    )]));
+    project.paths_mut().import_resolvers.push(Arc::new(resolver));
+
+    let cached = project.paths().imports_cache_dir().join("https/example.com/lib/Math.sol");
+    let (_, edges) = Graph::resolve(project.paths()).unwrap().into_sources();
+    assert!(cached.exists());
+    assert_eq!(
+        edges.import_remappings(),
+        [Remapping {
+            context: None,
+            name: import.display().to_string(),
+            path: utils::source_name(&cached, project.root()).display().to_string(),
+        }]
+    );
+
+    let compiled = project.compile().unwrap();
+    compiled.assert_success();
+    assert!(compiled.find_first("Token").is_some());
+    assert!(compiled.find_first("Math").is_some());
+    assert!(project.compile().unwrap().is_unchanged());
+
+    // fetched imports are resolved from the cache, even offline
+    project.paths_mut().import_resolvers = vec![Arc::new(InMemoryResolver::default())];
+    project.paths_mut().offline = true;
+    assert!(project.compile().unwrap().is_unchanged());
+
+    // imports that weren't fetched yet fail offline
+    let vault = Path::new("https://example.com/lib/Vault.sol");
+    let err = project.paths().resolve_import(project.sources_path(), vault).unwrap_err();
+    assert!(err.to_string().contains("can't be fetched offline"), "{err}");
+    project.paths_mut().offline = false;
+    let err = project.paths().resolve_import(project.sources_path(), vault).unwrap_err();
+    assert!(err.to_string().contains("failed to resolve"), "{err}");
+
+    project.project().cleanup().unwrap();
+    assert!(!cached.exists());
+}
+
+#[test]
+fn can_reuse_shared_cache_across_projects() {
+    use foundry_compilers::{
+        report::{self, Report, Reporter},
+        SharedCache,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Clone, Debug, Default)]
+    struct SharedCacheReporter {
+        hits: Arc<AtomicUsize>,
+        misses: Arc<AtomicUsize>,
+    }
+
+    impl Reporter for SharedCacheReporter {
+        fn on_shared_cache_hit(&self, _: &str, _: &Version, _: &[PathBuf]) {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_shared_cache_miss(&self, _: &str, _: &Version, _: &[PathBuf]) {
+            self.misses.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cache = SharedCache::new(cache_dir.path());
+    let new_project = || {
+        let mut project = TempProject::with_mock_solc().unwrap();
+        project
+            .add_source(
+                "A",
+                r"
+pragma solidity ^0.8.10;
+contract A {}
+",
+            )
+            .unwrap();
+        project.project_mut().shared_cache = Some(cache.clone());
+        project
+    };
+
+    let reporter = SharedCacheReporter::default();
+    report::with_scoped(&Report::new(reporter.clone()), || {
+        let first = new_project();
+        let compiled = first.compile().unwrap();
+        compiled.assert_success();
+        assert_eq!(reporter.misses.load(Ordering::SeqCst), 1);
+        assert_eq!(reporter.hits.load(Ordering::SeqCst), 0);
+        let bytecode = compiled.find_first("A").unwrap().get_bytecode_bytes().unwrap().into_owned();
+
+        // a different project with the same sources gets the output of the first one
+        let second = new_project();
+        let compiled = second.compile().unwrap();
+        compiled.assert_success();
+        assert_eq!(reporter.misses.load(Ordering::SeqCst), 1);
+        assert_eq!(reporter.hits.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            compiled.find_first("A").unwrap().get_bytecode_bytes().unwrap().into_owned(),
+            bytecode
+        );
+        assert!(second.artifacts_path().join("A.sol/A.json").exists());
+    });
+    assert_eq!(cache.len(), 1);
+}
+
+/// Serves `GET /<key>` and `PUT /<key>` of a remote cache from the `entries` on a new thread and
+/// returns its url
+fn spawn_remote_cache_server(
+    entries: std::sync::Arc<std::sync::Mutex<HashMap<String, Vec<u8>>>>,
+) -> String {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::Mutex,
+    };
+
+    /// Serves `GET /<key>` and `PUT /<key>` from memory, one request per connection
+    fn handle(mut stream: TcpStream, entries: &Mutex<HashMap<String, Vec<u8>>>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = (parts.next().unwrap().to_string(), parts.next().unwrap().to_string());
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        let (status, body) = match method.as_str() {
+            "GET" => match entries.lock().unwrap().get(&path) {
+                Some(entry) => ("200 OK", entry.clone()),
+                None => ("404 Not Found", Vec::new()),
+            },
+            "PUT" => {
+                entries.lock().unwrap().insert(path, body);
+                ("200 OK", Vec::new())
+            }
+            _ => ("405 Method Not Allowed", Vec::new()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(&body).unwrap();
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            handle(stream.unwrap(), &entries);
+        }
+    });
+    url
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_use_http_remote_cache_in_runtime() {
+    use foundry_compilers::{project_util::mock_solc::MockSolc, HttpRemoteCache, RemoteCache};
+    use std::sync::{Arc, Mutex};
+
+    let entries = Arc::new(Mutex::new(HashMap::new()));
+    let url = spawn_remote_cache_server(entries.clone());
+
+    // the cache is created, used and dropped within the runtime
+    let cache = HttpRemoteCache::new(&url).unwrap();
+    assert_eq!(cache.get("a").unwrap(), None);
+    cache.put("a", b"{}").unwrap();
+    assert_eq!(cache.get("a").unwrap(), Some(b"{}".to_vec()));
+    drop(cache);
+
+    let mut project = TempProject::with_compiler(
+        ProjectPathsConfig::builder(),
+        ConfigurableArtifacts::default(),
+        MockSolc::default(),
+    )
+    .unwrap();
+    project.add_source("A", "pragma solidity ^0.8.10;\ncontract A {}").unwrap();
+    project.project_mut().remote_cache = Some(Arc::new(HttpRemoteCache::new(&url).unwrap()));
+    project.compile().unwrap().assert_success();
+    assert_eq!(entries.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn can_reuse_remote_cache_over_http() {
+    use foundry_compilers::{
+        project_util::mock_solc::MockSolc,
+        report::{self, Report, Reporter},
+        HttpRemoteCache,
+    };
+    use std::{
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    #[derive(Clone, Debug, Default)]
+    struct RemoteCacheReporter {
+        hits: Arc<AtomicUsize>,
+        misses: Arc<AtomicUsize>,
+        compiled: Arc<AtomicUsize>,
+    }
+
+    impl Reporter for RemoteCacheReporter {
+        fn on_compiler_spawn(&self, _: &str, _: &Version, _: &[PathBuf]) {
+            self.compiled.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_remote_cache_hit(&self, _: &str, _: &Version, _: &[PathBuf]) {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_remote_cache_miss(&self, _: &str, _: &Version, _: &[PathBuf]) {
+            self.misses.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let entries = Arc::new(Mutex::new(HashMap::new()));
+    let url = spawn_remote_cache_server(entries.clone());
+
+    // nothing listens on this port anymore
+    let unreachable = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    };
+
+    let new_project = |url: &str| {
+        let mut project = TempProject::with_compiler(
+            ProjectPathsConfig::builder(),
+            ConfigurableArtifacts::default(),
+            MockSolc::default(),
+        )
+        .unwrap();
+        project
+            .add_source(
+                "A",
+                r"
+pragma solidity ^0.8.10;
+contract A {}
+",
+            )
+            .unwrap();
+        let cache = HttpRemoteCache::with_timeout(url, Duration::from_secs(5)).unwrap();
+        project.project_mut().remote_cache = Some(Arc::new(cache));
+        project
+    };
+
+    let reporter = RemoteCacheReporter::default();
+    report::with_scoped(&Report::new(reporter.clone()), || {
+        let first = new_project(&url);
+        first.compile().unwrap().assert_success();
+        assert_eq!(reporter.misses.load(Ordering::SeqCst), 1);
+        assert_eq!(reporter.compiled.load(Ordering::SeqCst), 1);
+        assert_eq!(entries.lock().unwrap().len(), 1);
+
+        // a different project with the same sources gets the output of the first one
+        let second = new_project(&url);
+        let compiled = second.compile().unwrap();
+        compiled.assert_success();
+        assert!(compiled.find_first("A").is_some());
+        assert_eq!(reporter.hits.load(Ordering::SeqCst), 1);
+        assert_eq!(reporter.compiled.load(Ordering::SeqCst), 1);
+
+        // a broken entry is ignored
+        for entry in entries.lock().unwrap().values_mut() {
+            *entry = b"not an output".to_vec();
+        }
+        let third = new_project(&url);
+        third.compile().unwrap().assert_success();
+        assert_eq!(reporter.misses.load(Ordering::SeqCst), 2);
+        assert_eq!(reporter.compiled.load(Ordering::SeqCst), 2);
+
+        // an unreachable cache degrades to compiling locally
+        let offline = new_project(&unreachable);
+        let compiled = offline.compile().unwrap();
+        compiled.assert_success();
+        assert!(compiled.find_first("A").is_some());
+        assert_eq!(reporter.misses.load(Ordering::SeqCst), 3);
+        assert_eq!(reporter.compiled.load(Ordering::SeqCst), 3);
+    });
+}
+
 #[test]
 fn can_compile_configured() {
     let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data/dapp-sample");
@@ -2535,7 +3853,7 @@ async fn can_install_solc_and_compile_std_json_input_async() {
     tmp.assert_no_errors();
     let source = tmp.list_source_files().into_iter().find(|p| p.ends_with("Dapp.t.sol")).unwrap();
     let input = tmp.project().standard_json_input(source).unwrap();
-    let solc = &tmp.project().solc;
+    let solc = &tmp.project().compiler;
 
     assert!(input.settings.remappings.contains(&"ds-test/=lib/ds-test/src/".parse().unwrap()));
     let input: CompilerInput = input.into();